@@ -86,6 +86,7 @@ mod tests;
 ///
 
 #[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct StorageUsed {
     cells: VarUInteger7,
     bits: VarUInteger7,
@@ -162,6 +163,7 @@ storage_used_short$_
 /// StorageUsedShort struct
 ///
 #[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct StorageUsedShort {
     cells: VarUInteger7,
     bits: VarUInteger7,
@@ -235,6 +237,7 @@ impl fmt::Display for StorageUsedShort {
 /// due_payment:(Maybe Grams) = StorageInfo;
 
 #[derive(PartialEq, Eq, Clone, Debug, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct StorageInfo {
     used: StorageUsed,
     last_paid: u32,
@@ -293,6 +296,7 @@ impl fmt::Display for StorageInfo {
 ///
 
 #[derive(Default, PartialEq, Eq, Clone, Debug, PartialOrd, Ord)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccountStatus {
     #[default]
     AccStateUninit,
@@ -341,6 +345,7 @@ impl Deserializable for AccountStatus {
 ///
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountStorage {
     last_trans_lt: u64,
     balance: CurrencyCollection,
@@ -431,6 +436,7 @@ impl fmt::Display for AccountStorage {
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 enum AccountState {
     #[default]
     AccountUninit,
@@ -483,6 +489,7 @@ impl fmt::Display for AccountState {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 struct AccountStuff {
     addr: MsgAddressInt,
     storage_stat: StorageInfo,
@@ -524,6 +531,7 @@ impl Serializable for AccountStuff {
 }
 
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Account {
     stuff: Option<AccountStuff>
 }
@@ -1231,6 +1239,7 @@ account_descr$_ account:^Account last_trans_hash:bits256
 
 /// struct ShardAccount
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardAccount {
     account: ChildCell<Account>,
     last_trans_hash: UInt256,
@@ -221,6 +221,7 @@ block_info#9bc7a987
 = BlockInfo;
 */
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockInfo {
 
     version: u32,
@@ -536,6 +537,7 @@ prev_blks_info$_
 
 */
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlkPrevInfo {
     Block {
         prev: ExtBlkRef
@@ -643,6 +645,7 @@ impl Serializable for BlkPrevInfo {
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutQueueUpdate {
     pub is_empty: bool,
     pub update: MerkleUpdate
@@ -796,6 +799,7 @@ block#11ef55bb
 define_HashmapE!{OutQueueUpdates, 32, OutQueueUpdate}
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     pub global_id: i32,
     pub info: ChildCell<BlockInfo>,
@@ -967,6 +971,7 @@ impl PartialOrd for Block {
 //    = BlockExtra;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockExtra {
     in_msg_descr: ChildCell<InMsgDescr>,
     out_msg_descr: ChildCell<OutMsgDescr>,
@@ -1230,6 +1235,7 @@ define_HashmapE!(MeshExported, 32, VarUInteger32);
 /// listed inside the square brackets. In this way, several fields can be moved from
 /// a cell containing a large record into a separate subcell.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValueFlow {
     pub from_prev_blk: CurrencyCollection, // serialized into another cell 1
     pub to_next_blk: CurrencyCollection,   // serialized into another cell 1
@@ -1296,6 +1302,7 @@ ext_blk_ref$_ start_lt:uint64 end_lt:uint64
     seq_no:uint32 hash:uint256 = ExtBlkRef;
 */
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtBlkRef {
     pub end_lt: u64,
     pub seq_no: u32,
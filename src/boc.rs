@@ -13,14 +13,14 @@
 use std::{
     collections::{HashMap, HashSet, hash_map},
     io::{Read, Write, Seek, SeekFrom, Cursor},
-    sync::Arc, ops::Deref,
+    sync::{Arc, Mutex, OnceLock}, ops::Deref,
     path::{PathBuf,Path},
     fmt::Debug,
     fs::File,
 };
 
 use crate::{
-    cell::{self, Cell, DataCell, DEPTH_SIZE, MAX_DATA_BYTES, MAX_SAFE_DEPTH, SHA256_SIZE}, crc32_digest, error, fail, full_len, ByteOrderRead, CellData, CellImpl, CellType, Crc32, Result, SliceData, Status, UInt256, MAX_BIG_DATA_BYTES, MAX_REFERENCES_COUNT
+    cell::{self, Cell, DataCell, DEPTH_SIZE, MAX_DATA_BYTES, MAX_SAFE_DEPTH, SHA256_SIZE}, crc32_digest, error, fail, full_len, BlockError, ByteOrderRead, CellData, CellImpl, CellType, Crc32, ExceptionCode, LevelMask, Result, SliceData, Status, UInt256, MAX_BIG_DATA_BYTES, MAX_REFERENCES_COUNT
 };
 use smallvec::SmallVec;
 
@@ -32,6 +32,8 @@ const BOC_GENERIC_TAG: u32 = 0xb5ee9c72;
 const BOC_GENERIC_V2_TAG: u32 = 0xb6ff9a73; // with big cells
 
 const MAX_ROOTS_COUNT: usize = 1024;
+// magic + flags + offset size + cells, roots, absent + total size + big cells count and size + roots list
+const MAX_HEADER_SIZE: usize = 4 + 1 + 1 + 3 * 4 + 8 + 4 + 8 + MAX_ROOTS_COUNT * 4;
 
 pub trait OrderedCellsStorage {
     fn get_cell_by_index(&self, index: u32) -> Result<Cell>;
@@ -93,6 +95,87 @@ impl OrderedCellsStorage for SimpleOrderedCellsStorage {
     fn cleanup(&mut self) -> Result<()> { Ok(()) }
 }
 
+/// Order of cells in serialized BOC
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BocCellsOrder {
+    /// Cells are written in depth-first order
+    #[default]
+    DepthFirst,
+    /// Cells are ordered the same way as the reference node does (`BagOfCells::reorder_cells`)
+    Reference,
+}
+
+/// Options of BOC serialization
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BocWriteOptions {
+    /// Write index of cells offsets (allows random access to cells)
+    pub has_index: bool,
+    /// Append CRC32C of the whole BOC
+    pub has_crc32c: bool,
+    /// Mark cells referenced more than once in the index (requires `has_index`)
+    pub has_cache_bits: bool,
+    /// Write hashes and depths into serialized cells (except big and pruned branch cells)
+    pub store_hashes: bool,
+    /// Order of roots in the root list - permutation of roots given to the writer
+    pub roots_order: Option<Vec<usize>>,
+    /// Order of cells
+    pub cells_order: BocCellsOrder,
+    /// Size of reference in bytes (minimal suitable size if not set)
+    pub ref_size: Option<usize>,
+    /// Size of offset in bytes (minimal suitable size if not set)
+    pub offset_size: Option<usize>,
+}
+
+impl BocWriteOptions {
+    pub fn with_index(has_index: bool, has_crc32c: bool) -> Self {
+        Self { has_index, has_crc32c, ..Default::default() }
+    }
+
+    fn check(&self) -> Status {
+        if self.has_cache_bits && !self.has_index {
+            fail!("cache bits can't be written without index")
+        }
+        if matches!(self.ref_size, Some(ref_size) if ref_size == 0 || ref_size > 4) {
+            fail!("ref size has to be more than 0 and less or equal 4")
+        }
+        if matches!(self.offset_size, Some(offset_size) if offset_size == 0 || offset_size > 8) {
+            fail!("offset size has to be more than 0 and less or equal 8")
+        }
+        Ok(())
+    }
+}
+
+// Count of hashes (and depths) to be added into cell's serialization.
+// Big and pruned branch cells are always written without hashes.
+fn hashes_to_store(cell: &Cell, store_hashes: bool) -> usize {
+    match cell.cell_type() {
+        _ if !store_hashes || cell.store_hashes() => 0,
+        CellType::Big | CellType::PrunedBranch => 0,
+        _ => cell.level() as usize + 1
+    }
+}
+
+fn serialized_cell_size(cell: &Cell, store_hashes: bool) -> Result<usize> {
+    Ok(full_len(cell.raw_data()?) + hashes_to_store(cell, store_hashes) * (SHA256_SIZE + DEPTH_SIZE))
+}
+
+fn write_cell_data<T: Write>(dest: &mut T, cell: &Cell, store_hashes: bool) -> Status {
+    let raw_data = cell.raw_data()?;
+    if hashes_to_store(cell, store_hashes) == 0 {
+        dest.write_all(raw_data)?;
+        return Ok(())
+    }
+    dest.write_all(&[raw_data[0] | cell::HASHES_D1_FLAG, raw_data[1]])?;
+    for hash in cell.hashes() {
+        dest.write_all(hash.as_slice())?;
+    }
+    for depth in cell.depths() {
+        dest.write_all(&depth.to_be_bytes())?;
+    }
+    dest.write_all(&raw_data[2..])?;
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct BocWriter<'a, S: OrderedCellsStorage> {
     roots_indexes_rev: Vec<usize>,
@@ -135,6 +218,7 @@ struct IntermediateState {
     ref_count: usize,
     total_size: u64,
     raw_cell_size: usize,
+    cached_cells: HashSet<u32>, // iterations of the cells referenced more than once
 }
 
 const FILE_BUFFER_LEN: usize = 128 * 1024 * 1024; // 128 MB
@@ -163,6 +247,28 @@ impl BocWriterStack {
         cells_storage: S,
         abort: &dyn Fn() -> bool,
     ) -> Result<()> {
+        Self::write_with_options(
+            dest, temp_dir, root_cell, max_depth, cells_storage, abort, &BocWriteOptions::default()
+        )
+    }
+
+    pub fn write_with_options<T: Write, S: CellByHashStorage>(
+        dest: &mut T,
+        temp_dir: &Path,
+        root_cell: Cell,
+        max_depth: u16,
+        cells_storage: S,
+        abort: &dyn Fn() -> bool,
+        options: &BocWriteOptions,
+    ) -> Result<()> {
+
+        options.check()?;
+        if matches!(&options.roots_order, Some(order) if order[..] != [0]) {
+            fail!("roots order is not a permutation of 1 root");
+        }
+        if options.cells_order != BocCellsOrder::DepthFirst {
+            fail!("only depth-first cells order is supported");
+        }
 
         //check root_cell
         if root_cell.virtualization() != 0 {
@@ -182,14 +288,37 @@ impl BocWriterStack {
             .open(&tmp_file_path)?;
         let remove_on_drop = RemoveOnDrop(tmp_file_path);
         
-        let mut state = Self::traverse(tmp_file,root_cell,cells_storage, abort)?;
-        
+        let state = Self::traverse(tmp_file, root_cell, cells_storage, abort, options)?;
+
         //write to the main file
+        if options.has_crc32c {
+            let mut dest_wrapped = IoCrcFilter::new_writer(dest);
+            Self::write_impl(&mut dest_wrapped, state, abort, options)?;
+            dest_wrapped.finalize()?;
+        } else {
+            Self::write_impl(dest, state, abort, options)?;
+        }
+
+        drop(remove_on_drop);
+
+        Ok(())
+    }
+
+    fn write_impl<T: Write>(
+        dest: &mut T,
+        mut state: IntermediateState,
+        abort: &dyn Fn() -> bool,
+        options: &BocWriteOptions,
+    ) -> Result<()> {
         let cells_count = state.cell_sizes.len();
-        let ref_size = BocWriter::<SimpleOrderedCellsStorage>::number_of_bytes_to_fit(state.cell_sizes.len());
+        let bytes_total_cells = BocWriter::<SimpleOrderedCellsStorage>::number_of_bytes_to_fit(cells_count);
+        let ref_size = options.ref_size.map_or(bytes_total_cells, |crs| std::cmp::max(crs, bytes_total_cells));
         
         let total_cells_size = state.raw_cell_size + state.ref_count * ref_size;
-        let offset_size = BocWriter::<SimpleOrderedCellsStorage>::number_of_bytes_to_fit(total_cells_size);
+        let bytes_total_size = BocWriter::<SimpleOrderedCellsStorage>::number_of_bytes_to_fit(
+            total_cells_size << options.has_cache_bits as usize
+        );
+        let offset_size = options.offset_size.map_or(bytes_total_size, |cos| std::cmp::max(cos, bytes_total_size));
 
         debug_assert!(ref_size <= 4);
         debug_assert!(offset_size <= 8);
@@ -200,7 +329,12 @@ impl BocWriterStack {
 
         // has index | has CRC | has cache bits | flags   | ref_size
         // 7         | 6       | 5              | 4 3     | 2 1 0
-        dest.write_all(&[ref_size as u8])?;
+        dest.write_all(&[
+            (options.has_index as u8) << 7 |
+            (options.has_crc32c as u8) << 6 |
+            (options.has_cache_bits as u8) << 5 |
+            ref_size as u8
+        ])?;
 
         dest.write_all(&[offset_size as u8])?; // off_bytes:(## 8) { off_bytes <= 8 }
         dest.write_all(&(cells_count as u64).to_be_bytes()[(8-ref_size)..8])?;
@@ -211,6 +345,26 @@ impl BocWriterStack {
         // Root's indexes 
         dest.write_all(&(0u64).to_be_bytes()[(8-ref_size)..8])?;
 
+        // Index - cells are read from the temp file in the same order as they will be written
+        if options.has_index {
+            let mut position = state.total_size;
+            let mut total_size = 0;
+            let mut d1 = [0_u8; 1];
+            for (iteration, &cell_size) in state.cell_sizes.iter().enumerate().rev() {
+                check_abort(abort)?;
+                position -= cell_size as u64;
+                state.file.seek(SeekFrom::Start(position))?;
+                state.file.read_exact(&mut d1)?;
+                let ref_count = cell::refs_count(&d1);
+                total_size += cell_size as usize - ref_count * TEMP_REF_SIZE + ref_count * ref_size;
+                let mut offset = total_size;
+                if options.has_cache_bits {
+                    offset = offset << 1 | state.cached_cells.contains(&(iteration as u32)) as usize;
+                }
+                dest.write_all(&(offset as u64).to_be_bytes()[(8-offset_size)..8])?;
+            }
+        }
+
         // Cells
         let mut cell_buffer = [0; 2 + 4 * (SHA256_SIZE + DEPTH_SIZE) + MAX_DATA_BYTES + 4 * TEMP_REF_SIZE];
         for &cell_size in state.cell_sizes.iter().rev() {
@@ -238,13 +392,10 @@ impl BocWriterStack {
                 let slice = &mut cell_buffer[ref_offset..ref_offset + TEMP_REF_SIZE];
                 let index = u32::from_be_bytes(slice.try_into().unwrap());
                 let child_index = cells_count as u64 - index as u64 - 1;
-                dest.write_all(&(child_index as u64).to_be_bytes()[(8-ref_size)..8])?;
+                dest.write_all(&child_index.to_be_bytes()[(8-ref_size)..8])?;
             }
         }
 
-        drop(state);
-        drop(remove_on_drop);
-
         Ok(())
     }
 
@@ -255,6 +406,7 @@ impl BocWriterStack {
         root_cell: Cell,
         cells_storage: S,
         abort: &dyn Fn() -> bool,
+        options: &BocWriteOptions,
     ) -> Result<IntermediateState> {
         enum StackItem {
             New(Cell),
@@ -292,6 +444,8 @@ impl BocWriterStack {
         let mut remap_index = 0u32;
         //total size of all cells without references
         let mut raw_cell_size = 0usize;
+        //indeces in the remap map of the cells referenced more than once
+        let mut cached_indices: HashSet<u32> = HashSet::default();
 
         let mut max_stack_len: usize = 0;
 
@@ -318,6 +472,9 @@ impl BocWriterStack {
                             }
                             hash_map::Entry::Occupied(entry) => {
                                 let (remap_index, written) = *entry.get();
+                                if options.has_cache_bits {
+                                    cached_indices.insert(remap_index);
+                                }
                                 if !written {
                                     cells.push((remap_index,cells_storage.get_cell_by_hash(&cell.reference_repr_hash(i)?)?));
                                 }
@@ -356,7 +513,7 @@ impl BocWriterStack {
                     iteration += 1;
 
                     //update counters
-                    let raw_cell = full_len(loaded.cell.raw_data()?);
+                    let raw_cell = serialized_cell_size(&loaded.cell, options.store_hashes)?;
                     let cell_size = raw_cell + TEMP_REF_SIZE * loaded.cell.references_count();
                     cell_sizes.push(cell_size as u16);
                     ref_count += loaded.cell.references_count();
@@ -364,7 +521,7 @@ impl BocWriterStack {
                     raw_cell_size   += raw_cell;
                         
                     //write cella and references to the temp file
-                    temp_file_buffer.write_all(loaded.cell.raw_data()?)?;
+                    write_cell_data(&mut temp_file_buffer, &loaded.cell, options.store_hashes)?;
                     for index  in loaded.references.iter() {
                         let index = remap.get(index).ok_or_else(|| {error!("index not found")})?;
                         temp_file_buffer.write_all(index)?;
@@ -373,6 +530,12 @@ impl BocWriterStack {
             }
         }
     
+        let mut cached_cells = HashSet::with_capacity(cached_indices.len());
+        for index in cached_indices {
+            let iteration = remap.get(&index).ok_or_else(|| {error!("index not found")})?;
+            cached_cells.insert(u32::from_be_bytes(*iteration));
+        }
+
         let mut file = temp_file_buffer.into_inner()?;
         file.flush()?;
         Ok(IntermediateState {
@@ -380,7 +543,8 @@ impl BocWriterStack {
             cell_sizes,
             ref_count,
             total_size,
-            raw_cell_size
+            raw_cell_size,
+            cached_cells,
         })
     }
 }
@@ -455,7 +619,7 @@ impl<'a, S: OrderedCellsStorage> BocWriter<'a, S> {
     }
 
     pub fn write<T: Write>(self, dest: &mut T) -> Result<()> {
-        self.write_ex(dest, false, false, None, None)
+        self.write_with_options(dest, &BocWriteOptions::default())
     }
 
     pub fn write_ex<T: Write>(
@@ -466,31 +630,83 @@ impl<'a, S: OrderedCellsStorage> BocWriter<'a, S> {
         custom_ref_size: Option<usize>,
         custom_offset_size: Option<usize>,
     ) -> Result<()> {
-        if include_crc {
+        let options = BocWriteOptions {
+            ref_size: custom_ref_size,
+            offset_size: custom_offset_size,
+            ..BocWriteOptions::with_index(include_index, include_crc)
+        };
+        self.write_with_options(dest, &options)
+    }
+
+    pub fn write_with_options<T: Write>(self, dest: &mut T, options: &BocWriteOptions) -> Result<()> {
+        options.check()?;
+        if options.has_crc32c {
             let mut dest_wrapped = IoCrcFilter::new_writer(dest);
-            self.write_ex_impl(&mut dest_wrapped, include_index, include_crc, custom_ref_size, custom_offset_size)?;
+            self.write_with_options_impl(&mut dest_wrapped, options)?;
             dest_wrapped.finalize()
         } else {
-            self.write_ex_impl(dest, include_index, include_crc, custom_ref_size, custom_offset_size)
+            self.write_with_options_impl(dest, options)
         }
     }
 
-    fn write_ex_impl<T: Write>(
+    fn write_with_options_impl<T: Write>(
         mut self,
         dest: &mut T,
-        include_index: bool,
-        include_crc: bool,
-        custom_ref_size: Option<usize>,
-        custom_offset_size: Option<usize>,
+        options: &BocWriteOptions,
     ) -> Result<()> {
+        let roots_indexes_rev = match &options.roots_order {
+            Some(order) => {
+                let mut used = vec![false; self.roots_count()];
+                let mut roots_indexes_rev = Vec::with_capacity(order.len());
+                for &i in order {
+                    match used.get_mut(i) {
+                        Some(used) if !*used => *used = true,
+                        _ => fail!("roots order is not a permutation of {} roots", self.roots_count())
+                    }
+                    roots_indexes_rev.push(self.roots_indexes_rev[i]);
+                }
+                if roots_indexes_rev.len() != self.roots_count() {
+                    fail!("roots order is not a permutation of {} roots", self.roots_count())
+                }
+                roots_indexes_rev
+            }
+            None => self.roots_indexes_rev.clone()
+        };
+
+        // new reversed index -> reversed index in storage and vice versa
+        let (order, new_indexes_rev) = match options.cells_order {
+            BocCellsOrder::DepthFirst => (Vec::new(), Vec::new()),
+            BocCellsOrder::Reference => self.reference_cells_order()?
+        };
+        let cell_by_index = |index: usize| {
+            self.cells.get_cell_by_index(order.get(index).map_or(index as u32, |i| *i))
+        };
+        let index_by_hash = |hash: &UInt256| -> Result<usize> {
+            let index = self.cells.get_rev_index_by_hash(hash)? as usize;
+            Ok(new_indexes_rev.get(index).map_or(index, |i| *i as usize))
+        };
+        let roots_indexes_rev = roots_indexes_rev.into_iter()
+            .map(|index| new_indexes_rev.get(index).map_or(index, |i| *i as usize))
+            .collect::<Vec<_>>();
+
+        let mut data_size = self.data_size;
+        if options.store_hashes {
+            for cell_index in 0..self.cells_count {
+                check_abort(self.abort)?;
+                let cell = cell_by_index(cell_index)?;
+                data_size += hashes_to_store(&cell, true) * (SHA256_SIZE + DEPTH_SIZE);
+            }
+        }
+
         let bytes_total_cells = Self::number_of_bytes_to_fit(self.cells_count);
-        let ref_size = custom_ref_size.map_or(bytes_total_cells, |crs| {
+        let ref_size = options.ref_size.map_or(bytes_total_cells, |crs| {
             debug_assert!(crs >= bytes_total_cells);
             std::cmp::max(crs, bytes_total_cells)
         });
-        let total_cells_size = self.data_size + self.references * ref_size;
-        let bytes_total_size = Self::number_of_bytes_to_fit(total_cells_size);
-        let offset_size = custom_offset_size.map_or(bytes_total_size, |cos| {
+        let total_cells_size = data_size + self.references * ref_size;
+        // with cache bits every offset in the index is shifted by one bit
+        let bytes_total_size = Self::number_of_bytes_to_fit(total_cells_size << options.has_cache_bits as usize);
+        let offset_size = options.offset_size.map_or(bytes_total_size, |cos| {
             debug_assert!(cos >= bytes_total_size);
             std::cmp::max(cos, bytes_total_size)
         });
@@ -509,11 +725,16 @@ impl<'a, S: OrderedCellsStorage> BocWriter<'a, S> {
 
         // has index | has CRC | has cache bits | flags   | ref_size
         // 7         | 6       | 5              | 4 3     | 2 1 0
-        dest.write_all(&[(include_index as u8) << 7 | (include_crc as u8) << 6 | ref_size as u8])?;
+        dest.write_all(&[
+            (options.has_index as u8) << 7 |
+            (options.has_crc32c as u8) << 6 |
+            (options.has_cache_bits as u8) << 5 |
+            ref_size as u8
+        ])?;
 
         dest.write_all(&[offset_size as u8])?; // off_bytes:(## 8) { off_bytes <= 8 }
         dest.write_all(&(self.cells_count as u64).to_be_bytes()[(8-ref_size)..8])?;
-        dest.write_all(&(self.roots_count() as u64).to_be_bytes()[(8-ref_size)..8])?;
+        dest.write_all(&(roots_indexes_rev.len() as u64).to_be_bytes()[(8-ref_size)..8])?;
         dest.write_all(&0_u64.to_be_bytes()[(8-ref_size)..8])?;
         dest.write_all(&(total_cells_size as u64).to_be_bytes()[(8-offset_size)..8])?;
         if self.big_cells_count > 0 {
@@ -522,32 +743,51 @@ impl<'a, S: OrderedCellsStorage> BocWriter<'a, S> {
         }
 
         // Root's indexes 
-        for index in self.roots_indexes_rev.iter() {
+        for index in roots_indexes_rev.iter() {
             check_abort(self.abort)?;
             dest.write_all(&((self.cells_count - *index - 1) as u64).to_be_bytes()[(8-ref_size)..8])?;
         }
     
         // Index
-        if include_index { 
+        if options.has_index { 
+            // Cell must be cached by reader if it is referenced more than once (a root is referenced 
+            // by the root list). All parents are placed before the cell, so counter is final in time.
+            let mut refs_counters = Vec::new();
+            if options.has_cache_bits {
+                refs_counters.resize(self.cells_count, 0_u8);
+                for index in roots_indexes_rev.iter() {
+                    refs_counters[self.cells_count - *index - 1] = 1;
+                }
+            }
             let mut total_size = 0;
-            for cell_index in (0..self.cells_count).rev() {
+            for cell_rev_index in (0..self.cells_count).rev() {
                 check_abort(self.abort)?;
-                let cell = &self.cells.get_cell_by_index(cell_index as u32)?;
-                total_size += full_len(cell.raw_data()?) + ref_size * cell.references_count();
-                dest.write_all(&(total_size as u64).to_be_bytes()[(8-offset_size)..8])?;
+                let cell = &cell_by_index(cell_rev_index)?;
+                total_size += serialized_cell_size(cell, options.store_hashes)? +
+                    ref_size * cell.references_count();
+                let mut offset = total_size;
+                if options.has_cache_bits {
+                    for i in 0..cell.references_count() {
+                        let child_rev_index = index_by_hash(&cell.reference_repr_hash(i)?)?;
+                        let counter = &mut refs_counters[self.cells_count - 1 - child_rev_index];
+                        *counter = counter.saturating_add(1);
+                    }
+                    let cell_index = self.cells_count - 1 - cell_rev_index;
+                    offset = offset << 1 | (refs_counters[cell_index] > 1) as usize;
+                }
+                dest.write_all(&(offset as u64).to_be_bytes()[(8-offset_size)..8])?;
             }
         }
 
         // Cells
         for cell_rev_index in (0..self.cells_count).rev() {
             check_abort(self.abort)?;
-            let cell = &self.cells.get_cell_by_index(cell_rev_index as u32)?;
-            dest.write_all(cell.raw_data()?)?;
+            let cell = &cell_by_index(cell_rev_index)?;
+            write_cell_data(dest, cell, options.store_hashes)?;
             let cell_index = self.cells_count - 1 - cell_rev_index;
             for i in 0..cell.references_count() {
                 let child_hash = cell.reference_repr_hash(i).unwrap();
-                let child_index = self.cells_count - 1 - 
-                    self.cells.get_rev_index_by_hash(&child_hash)? as usize;
+                let child_index = self.cells_count - 1 - index_by_hash(&child_hash)?;
                 debug_assert!(child_index > cell_index);
                 dest.write_all(&(child_index as u64).to_be_bytes()[(8-ref_size)..8])?;
             }
@@ -556,6 +796,113 @@ impl<'a, S: OrderedCellsStorage> BocWriter<'a, S> {
         self.cells.cleanup()
     }
 
+    // Reproduces cells order of the reference node (BagOfCells::reorder_cells and revisit).
+    // Storage order is the same as the reference import order, so the result is
+    // storage reversed index for every new one and new reversed index for every storage one.
+    fn reference_cells_order(&self) -> Result<(Vec<u32>, Vec<u32>)> {
+        const MAX_CELL_WHS: i32 = 64;
+        const NOT_VISITED: i64 = -1;
+        const PREVISITED: i64 = -2;
+        const VISITED: i64 = -3;
+
+        let mut refs = Vec::with_capacity(self.cells_count);
+        let mut wt = Vec::with_capacity(self.cells_count);
+        for index in 0..self.cells_count {
+            check_abort(self.abort)?;
+            let cell = self.cells.get_cell_by_index(index as u32)?;
+            let mut cell_refs = SmallVec::<[u32; MAX_REFERENCES_COUNT]>::new();
+            let mut sum_child_wt = 1_u32;
+            for i in 0..cell.references_count() {
+                let child = self.cells.get_rev_index_by_hash(&cell.reference_repr_hash(i)?)?;
+                sum_child_wt += wt[child as usize] as u32;
+                cell_refs.push(child);
+            }
+            refs.push(cell_refs);
+            wt.push(std::cmp::min(0xff, sum_child_wt) as u8);
+        }
+
+        for index in (0..self.cells_count).rev() {
+            let cell_refs = &refs[index];
+            let s = cell_refs.len() as i32;
+            let mut c = s;
+            let mut sum = MAX_CELL_WHS - 1;
+            let mut mask = 0;
+            for (j, child) in cell_refs.iter().enumerate() {
+                let limit = (MAX_CELL_WHS - 1 + j as i32) / s;
+                if wt[*child as usize] as i32 <= limit {
+                    sum -= wt[*child as usize] as i32;
+                    c -= 1;
+                    mask |= 1 << j;
+                }
+            }
+            if c != 0 {
+                for (j, child) in cell_refs.iter().enumerate() {
+                    if mask & (1 << j) == 0 {
+                        let limit = sum / c;
+                        sum += 1;
+                        if wt[*child as usize] as i32 > limit {
+                            wt[*child as usize] = limit as u8;
+                        }
+                    }
+                }
+            }
+        }
+        for index in 0..self.cells_count {
+            let sum = 1 + refs[index].iter().map(|child| wt[*child as usize] as u32).sum::<u32>();
+            wt[index] = if sum <= wt[index] as u32 { sum as u8 } else { 0 };
+        }
+        let is_special = |index: u32| wt[index as usize] == 0;
+
+        // iterative version of BagOfCells::revisit, children are processed from last to first
+        let mut new_idx = vec![NOT_VISITED; self.cells_count];
+        let mut order = Vec::with_capacity(self.cells_count);
+        let mut stack = Vec::new();
+        for force in [0, 1, 2] {
+            for index in self.roots_indexes_rev.iter().rev() {
+                stack.push((*index as u32, force));
+            }
+            while let Some((index, force)) = stack.pop() {
+                check_abort(self.abort)?;
+                let state = new_idx[index as usize];
+                if state >= 0 {
+                    continue
+                }
+                let cell_refs = &refs[index as usize];
+                match force {
+                    0 => if state == NOT_VISITED {
+                        new_idx[index as usize] = PREVISITED;
+                        for child in cell_refs.iter() {
+                            stack.push((*child, is_special(*child) as u8));
+                        }
+                    }
+                    1 => if state != VISITED {
+                        new_idx[index as usize] = VISITED;
+                        for child in cell_refs.iter() {
+                            stack.push((*child, 2));
+                        }
+                        for child in cell_refs.iter() {
+                            stack.push((*child, 1));
+                        }
+                        if is_special(index) && state == NOT_VISITED {
+                            for child in cell_refs.iter() {
+                                stack.push((*child, is_special(*child) as u8));
+                            }
+                        }
+                    }
+                    _ => {
+                        new_idx[index as usize] = order.len() as i64;
+                        order.push(index);
+                    }
+                }
+            }
+        }
+        if order.len() != self.cells_count {
+            fail!("not all cells are reachable from roots")
+        }
+        let new_indexes_rev = new_idx.into_iter().map(|index| index as u32).collect();
+        Ok((order, new_indexes_rev))
+    }
+
     fn traverse(&mut self, root: Cell) -> Status {
         enum Phase {
             Pre(Cell),
@@ -654,6 +1001,27 @@ pub struct BocHeader {
     pub big_cells_size: usize,
 }
 
+/// Limits for BOCs received from untrusted sources. They are checked against the header
+/// before any cells are read, exceeding one fails with `BlockError::BocLimitExceeded`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BocReadLimits {
+    pub max_cells: Option<usize>,
+    /// Limit for the total size of cells data (`tot_cells_size` in the header)
+    pub max_data_size: Option<usize>,
+    pub max_roots: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BocReadProgress {
+    pub cells_parsed: usize,
+    pub cells_count: usize,
+    pub bytes_consumed: u64,
+    pub bytes_total: u64,
+}
+
+// Progress callback is called every PROGRESS_STEP cells and after the last one
+const PROGRESS_STEP: usize = 1024;
+
 pub struct BocReaderResult {
     pub roots: Vec<Cell>,
     pub header: BocHeader,
@@ -709,6 +1077,8 @@ impl DoneCellsStorage for HashMap<u32, Cell> {
 
 pub struct BocReader<'a> {
     abort: &'a dyn Fn() -> bool,
+    progress: Option<&'a dyn Fn(&BocReadProgress)>,
+    limits: BocReadLimits,
     indexed_cells: Box<dyn IndexedCellsStorage>,
     done_cells: Box<dyn DoneCellsStorage>,
     max_depth: u16,
@@ -719,6 +1089,8 @@ impl<'a> Default for BocReader<'a> {
     fn default() -> Self {
         Self {
             abort: &|| false,
+            progress: None,
+            limits: BocReadLimits::default(),
             indexed_cells: Box::<HashMap::<u32, RawCell>>::default(),
             done_cells: Box::<HashMap::<u32, Cell>>::default(),
             max_depth: MAX_SAFE_DEPTH,
@@ -759,6 +1131,16 @@ impl<'a> BocReader<'a> {
         self
     }
 
+    pub fn set_progress(mut self, progress: &'a dyn Fn(&BocReadProgress)) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn set_limits(mut self, limits: BocReadLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     pub fn set_max_cell_depth(mut self, max_depth: u16) -> Self {
         self.max_depth = max_depth;
         self
@@ -796,16 +1178,22 @@ impl<'a> BocReader<'a> {
         // Read cells
         #[cfg(not(target_family = "wasm"))]
         let now1 = std::time::Instant::now();
-        let mut actual_data_size = src.stream_position()?;
+        let cells_start = src.stream_position()? - position;
+        let bytes_total = cells_start + header.tot_cells_size as u64 + header.has_crc as u64 * 4;
+        let mut actual_data_size = 0;
         let mut remaining_big_cells = header.big_cells_count;
         for cell_index in 0..header.cells_count {
             check_abort(self.abort)?;
             let raw_cell = Self::read_raw_cell(
                 &mut src, header.ref_size, cell_index, header.cells_count, &mut remaining_big_cells)?;
+            actual_data_size += raw_cell.data.len() + cell::refs_count(&raw_cell.data) * header.ref_size;
+            if actual_data_size > header.tot_cells_size {
+                fail!("actual data size disagrees with the size from header")
+            }
             self.indexed_cells.insert(cell_index as u32, raw_cell)?;
+            self.report_progress(cell_index, header.cells_count, cells_start + actual_data_size as u64, bytes_total);
         }
-        actual_data_size = src.stream_position()? - actual_data_size;
-        if actual_data_size as usize != header.tot_cells_size {
+        if actual_data_size != header.tot_cells_size {
             fail!("actual data size disagrees with the size from header")
         }
         #[cfg(not(target_family = "wasm"))]
@@ -874,6 +1262,7 @@ impl<'a> BocReader<'a> {
         let header = self.read_header(&mut src)?;
 
         Self::precheck_cells_tree_len(&header, src.position(), data.len() as u64, false)?;
+        let cells_end = data.len() - header.has_crc as usize * 4;
     
         // Index processing - read existing index or traverse all vector to create own index2
         #[cfg(not(target_family = "wasm"))]
@@ -936,6 +1325,13 @@ impl<'a> BocReader<'a> {
                 remaining_big_cells -= 1;
             }
             self.done_cells.insert(cell_index as u32, Cell::with_cell_impl(cell))?;
+            // cells are processed from the end, so the consumed bytes are counted from there
+            self.report_progress(
+                header.cells_count - 1 - cell_index,
+                header.cells_count,
+                (cells_end - header.tot_cells_size + cells_end - offset) as u64,
+                data.len() as u64
+            );
         }
         #[cfg(not(target_family = "wasm"))]
         let constructing_time = now1.elapsed().as_millis();
@@ -1040,6 +1436,52 @@ impl<'a> BocReader<'a> {
         Ok((header, slice))
     }
 
+    /// Reads only the BOC header and returns roots backed by `source`: cells are loaded
+    /// on demand using the cells index, so the BOC must include it.
+    /// CRC is not verified. Hashes stored in the BOC are used as is. Hashes of a cell
+    /// without stored hashes are calculated when they are requested first by reading
+    /// the cell's subtree, they are kept while the cells of the subtree are alive.
+    /// Errors of the source are returned by cell loading and `Cell::reference_repr_hash`,
+    /// `Cell::hash` panics if hashes of the cell can't be calculated.
+    pub fn read_lazy(&mut self, source: impl BocSource + 'static) -> Result<BocReaderResult> {
+        let size = source.size();
+        let mut header_data = vec![0; size.min(MAX_HEADER_SIZE as u64) as usize];
+        source.read_at(0, &mut header_data)?;
+        let mut src = Cursor::new(&header_data);
+
+        let header = self.read_header(&mut src)?;
+        Self::precheck_cells_tree_len(&header, src.position(), size, false)?;
+        if !header.index_included {
+            fail!("Lazy reading requires the cells index in the BOC");
+        }
+
+        let index_start = src.position();
+        let boc = Arc::new(LazyBoc {
+            source: Box::new(source),
+            ref_size: header.ref_size,
+            offset_size: header.offset_size,
+            cells_count: header.cells_count,
+            has_cache_bits: header.has_cache_bits,
+            index_start,
+            cells_start: index_start + (header.cells_count * header.offset_size) as u64,
+            cells_size: header.tot_cells_size as u64,
+            max_depth: self.max_depth,
+        });
+
+        let roots = if header.roots_indexes.is_empty() {
+            vec!(Cell::with_cell_impl(LazyBoc::cell(&boc, 0, None)?))
+        } else {
+            header.roots_indexes.iter()
+                .map(|index| Ok(Cell::with_cell_impl(LazyBoc::cell(&boc, *index, None)?)))
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        Ok(BocReaderResult {
+            roots,
+            header,
+        })
+    }
+
     fn read_header<T>(&self, src: &mut T) -> Result<BocHeader> where T: Read {
         let magic = src.read_be_u32()?;
         let first_byte = src.read_byte()?;
@@ -1088,6 +1530,8 @@ impl<'a> BocReader<'a> {
 
         let cells_count = src.read_be_uint(ref_size)? as usize; // cells:(##(size * 8))
         let roots_count = src.read_be_uint(ref_size)? as usize; // roots:(##(size * 8))
+        Self::check_limit("cells count", cells_count, self.limits.max_cells)?;
+        Self::check_limit("roots count", roots_count, self.limits.max_roots)?;
         let absent_count = src.read_be_uint(ref_size)? as usize; // absent:(##(size * 8)) { roots + absent <= cells }
 
         if cells_count == 0 {
@@ -1111,6 +1555,7 @@ impl<'a> BocReader<'a> {
         }
 
         let tot_cells_size = src.read_be_uint(offset_size)? as usize; // tot_cells_size:(##(off_bytes * 8))
+        Self::check_limit("cells data size", tot_cells_size, self.limits.max_data_size)?;
         
         let (big_cells_count, big_cells_size) = if has_big_cells {
             let big_cells_count = src.read_be_uint(ref_size)? as usize;
@@ -1191,6 +1636,22 @@ impl<'a> BocReader<'a> {
         })
     }
 
+    fn check_limit(name: &str, value: usize, limit: Option<usize>) -> Status {
+        match limit {
+            Some(limit) if value > limit => fail!(BlockError::BocLimitExceeded(name.to_string(), value, limit)),
+            _ => Ok(())
+        }
+    }
+
+    fn report_progress(&self, cell_index: usize, cells_count: usize, bytes_consumed: u64, bytes_total: u64) {
+        if let Some(progress) = self.progress {
+            let cells_parsed = cell_index + 1;
+            if cells_parsed.is_multiple_of(PROGRESS_STEP) || cells_parsed == cells_count {
+                progress(&BocReadProgress { cells_parsed, cells_count, bytes_consumed, bytes_total });
+            }
+        }
+    }
+
     fn precheck_cells_tree_len(header: &BocHeader, header_len: u64, actual_len: u64, unbounded: bool) -> Result<()> {
         // calculate boc len
         let index_size = header.index_included as u64 * ((header.cells_count * header.offset_size) as u64);
@@ -1321,6 +1782,337 @@ impl<'a> BocReader<'a> {
     }
 }
 
+/// Random access source of a serialized bag of cells for `BocReader::read_lazy`.
+/// Implemented for in-memory buffers (including memory mapped files) and,
+/// through `BocStreamSource`, for any `Read + Seek` object.
+pub trait BocSource: Send + Sync {
+    fn size(&self) -> u64;
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Status;
+}
+
+impl<T> BocSource for T where T: Deref + Send + Sync, T::Target: AsRef<[u8]> {
+    fn size(&self) -> u64 {
+        self.deref().as_ref().len() as u64
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Status {
+        let data = self.deref().as_ref();
+        let start = usize::try_from(offset)?;
+        match data.get(start..).and_then(|data| data.get(..buf.len())) {
+            Some(data) => buf.copy_from_slice(data),
+            None => fail!("Can't read {} bytes at {}: BOC is only {} bytes long", buf.len(), offset, data.len())
+        }
+        Ok(())
+    }
+}
+
+pub struct BocStreamSource<T> {
+    src: Mutex<T>,
+    size: u64,
+}
+
+impl<T: Read + Seek> BocStreamSource<T> {
+    pub fn new(mut src: T) -> Result<Self> {
+        let size = src.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            src: Mutex::new(src),
+            size,
+        })
+    }
+}
+
+impl<T: Read + Seek + Send> BocSource for BocStreamSource<T> {
+    fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Status {
+        let mut src = self.src.lock().map_err(|_| error!("BOC source mutex is poisoned"))?;
+        src.seek(SeekFrom::Start(offset))?;
+        src.read_exact(buf)?;
+        Ok(())
+    }
+}
+
+struct LazyBoc {
+    source: Box<dyn BocSource>,
+    ref_size: usize,
+    offset_size: usize,
+    cells_count: usize,
+    has_cache_bits: bool,
+    index_start: u64,
+    cells_start: u64,
+    cells_size: u64,
+    max_depth: u16,
+}
+
+// Hashes of the cells of a subtree calculated when a hash of its root is requested.
+// They are shared with the cells of the subtree loaded later and released with them.
+type SubtreeHashes = HashMap<u32, ResolvedHashes>;
+
+impl LazyBoc {
+    fn cell(boc: &Arc<Self>, index: u32, subtree: Option<&Arc<SubtreeHashes>>) -> Result<LazyCell> {
+        let (cell_data, references) = boc.load_cell(index)?;
+        let hashes = OnceLock::new();
+        if let Some(subtree) = subtree {
+            if !cell_data.store_hashes() && subtree.contains_key(&index) {
+                hashes.get_or_init(|| subtree.clone());
+            }
+        }
+        Ok(LazyCell {
+            boc: boc.clone(),
+            index,
+            cell_data,
+            references,
+            children: Default::default(),
+            hashes,
+        })
+    }
+
+    // Offset of the cell's end relative to the cells start
+    fn cell_end(&self, index: usize) -> Result<u64> {
+        let mut buf = [0; 8];
+        let offset = self.index_start + (index * self.offset_size) as u64;
+        self.source.read_at(offset, &mut buf[8 - self.offset_size..])?;
+        let end = u64::from_be_bytes(buf) >> self.has_cache_bits as u8;
+        if end > self.cells_size {
+            fail!("Invalid index: cell {} ends at {} out of cells data ({})", index, end, self.cells_size);
+        }
+        Ok(end)
+    }
+
+    fn load_cell(&self, index: u32) -> Result<(CellData, SmallVec<[u32; 4]>)> {
+        let cell_index = index as usize;
+        if cell_index >= self.cells_count {
+            fail!("Cell index {} is out of range (cells count {})", cell_index, self.cells_count);
+        }
+        let start = match cell_index {
+            0 => 0,
+            _ => self.cell_end(cell_index - 1)?
+        };
+        let end = self.cell_end(cell_index)?;
+        if end <= start {
+            fail!("Invalid index: cell {} has wrong bounds {}..{}", cell_index, start, end);
+        }
+        let mut buf = vec!(0; (end - start) as usize);
+        self.source.read_at(self.cells_start + start, &mut buf)?;
+
+        let cell_data = CellData::with_unbounded_raw_data_slice(&buf)?;
+        let refs = &buf[cell_data.raw_data().len()..];
+        let refs_count = cell_data.references_count();
+        if refs.len() != refs_count * self.ref_size {
+            fail!("Invalid index: cell {} has {} bytes after data, {} references expected",
+                cell_index, refs.len(), refs_count);
+        }
+        let mut references = SmallVec::with_capacity(refs_count);
+        let mut src = Cursor::new(refs);
+        for _ in 0..refs_count {
+            let r = src.read_be_uint(self.ref_size)? as usize;
+            if r >= self.cells_count || r <= cell_index {
+                fail!("reference out of range, cells_count: {}, ref: {}, cell_index: {}",
+                    self.cells_count, r, cell_index)
+            }
+            references.push(r as u32);
+        }
+        Ok((cell_data, references))
+    }
+
+    // Calculates hashes of the cells of the subtree bottom-up without recursion.
+    // Cells with stored hashes stop the descent.
+    fn resolve_hashes(&self, index: u32) -> Result<SubtreeHashes> {
+        let mut resolved = SubtreeHashes::new();
+        let mut stack = vec!((index, None));
+        while let Some((index, loaded)) = stack.pop() {
+            if resolved.contains_key(&index) {
+                continue;
+            }
+            let (cell_data, references) = match loaded {
+                Some(loaded) => loaded,
+                None => {
+                    let (cell_data, references) = self.load_cell(index)?;
+                    if cell_data.store_hashes() {
+                        resolved.insert(index, ResolvedHashes::with_stored(&cell_data));
+                        continue;
+                    }
+                    let pending = references.iter()
+                        .filter(|r| !resolved.contains_key(r))
+                        .map(|r| (*r, None))
+                        .collect::<SmallVec<[_; 4]>>();
+                    if !pending.is_empty() {
+                        stack.push((index, Some((cell_data, references))));
+                        stack.extend(pending);
+                        continue;
+                    }
+                    (cell_data, references)
+                }
+            };
+            let mut refs = Vec::with_capacity(references.len());
+            for r in &references {
+                let child = resolved.get(r)
+                    .ok_or_else(|| error!("Hashes of cell {} are not resolved", r))?;
+                refs.push(Cell::with_cell_impl(child.clone()));
+            }
+            let cell = DataCell::with_raw_data(refs, cell_data.raw_data().to_vec(), Some(self.max_depth))?;
+            resolved.insert(index, ResolvedHashes::with_cell(&Cell::with_cell_impl(cell)));
+        }
+        Ok(resolved)
+    }
+}
+
+// Hashes and depths of a cell without its data. It is only used as a child
+// when hashes of the parent are calculated.
+#[derive(Clone)]
+struct ResolvedHashes {
+    level_mask: LevelMask,
+    hashes: SmallVec<[(UInt256, u16); 4]>,
+}
+
+impl ResolvedHashes {
+    fn with_cell(cell: &Cell) -> Self {
+        let hashes = cell.hashes().into_iter().zip(cell.depths()).collect();
+        Self { level_mask: cell.level_mask(), hashes }
+    }
+
+    fn with_stored(cell_data: &CellData) -> Self {
+        let level_mask = cell_data.level_mask();
+        let hashes = (0..=3)
+            .filter(|i| level_mask.is_significant_index(*i))
+            .map(|i| (cell_data.hash(i), cell_data.depth(i)))
+            .collect();
+        Self { level_mask, hashes }
+    }
+}
+
+impl CellImpl for ResolvedHashes {
+    fn data(&self) -> &[u8] { &[] }
+
+    fn raw_data(&self) -> Result<&[u8]> {
+        fail!("Resolved hashes don't contain cell data")
+    }
+
+    fn bit_length(&self) -> usize { 0 }
+
+    fn references_count(&self) -> usize { 0 }
+
+    fn reference(&self, _index: usize) -> Result<Cell> {
+        fail!("Resolved hashes don't contain references")
+    }
+
+    fn cell_type(&self) -> CellType { CellType::Unknown }
+
+    fn level_mask(&self) -> LevelMask { self.level_mask }
+
+    fn hash(&self, index: usize) -> UInt256 {
+        self.hashes[self.level_mask.calc_hash_index(index)].0.clone()
+    }
+
+    fn depth(&self, index: usize) -> u16 {
+        self.hashes[self.level_mask.calc_hash_index(index)].1
+    }
+
+    fn store_hashes(&self) -> bool { false }
+}
+
+// Cell loaded from a BOC source. References are loaded on the first access,
+// hashes not stored in the BOC are resolved when they are requested first.
+struct LazyCell {
+    boc: Arc<LazyBoc>,
+    index: u32,
+    cell_data: CellData,
+    references: SmallVec<[u32; 4]>,
+    children: [OnceLock<Arc<LazyCell>>; 4],
+    hashes: OnceLock<Arc<SubtreeHashes>>,
+}
+
+impl LazyCell {
+    fn child(&self, index: usize) -> Result<&Arc<LazyCell>> {
+        let child_index = *self.references.get(index)
+            .ok_or_else(|| error!(ExceptionCode::CellUnderflow))?;
+        if let Some(child) = self.children[index].get() {
+            return Ok(child)
+        }
+        let child = LazyBoc::cell(&self.boc, child_index, self.hashes.get())?;
+        Ok(self.children[index].get_or_init(|| Arc::new(child)))
+    }
+
+    fn resolved_hashes(&self) -> Result<&ResolvedHashes> {
+        let subtree = match self.hashes.get() {
+            Some(subtree) => subtree,
+            None => {
+                let subtree = Arc::new(self.boc.resolve_hashes(self.index)?);
+                self.hashes.get_or_init(|| subtree)
+            }
+        };
+        subtree.get(&self.index).ok_or_else(|| error!("Hashes of cell {} are not resolved", self.index))
+    }
+
+    fn try_hash(&self, index: usize) -> Result<UInt256> {
+        match self.cell_data.store_hashes() {
+            true => Ok(self.cell_data.hash(index)),
+            false => Ok(self.resolved_hashes()?.hash(index))
+        }
+    }
+
+    fn try_depth(&self, index: usize) -> Result<u16> {
+        match self.cell_data.store_hashes() {
+            true => Ok(self.cell_data.depth(index)),
+            false => Ok(self.resolved_hashes()?.depth(index))
+        }
+    }
+}
+
+// `Cell::hash` can't report errors of the source, so they are only returned
+// by `reference_repr_hash` and `reference_repr_depth` used in serialization
+impl CellImpl for LazyCell {
+    fn data(&self) -> &[u8] {
+        self.cell_data.data()
+    }
+
+    fn raw_data(&self) -> Result<&[u8]> {
+        Ok(self.cell_data.raw_data())
+    }
+
+    fn bit_length(&self) -> usize {
+        self.cell_data.bit_length()
+    }
+
+    fn references_count(&self) -> usize {
+        self.references.len()
+    }
+
+    fn reference(&self, index: usize) -> Result<Cell> {
+        Ok(Cell::with_cell_impl_arc(self.child(index)?.clone()))
+    }
+
+    fn reference_repr_hash(&self, index: usize) -> Result<UInt256> {
+        self.child(index)?.try_hash(cell::MAX_LEVEL)
+    }
+
+    fn reference_repr_depth(&self, index: usize) -> Result<u16> {
+        self.child(index)?.try_depth(cell::MAX_LEVEL)
+    }
+
+    fn cell_type(&self) -> CellType {
+        self.cell_data.cell_type()
+    }
+
+    fn level_mask(&self) -> LevelMask {
+        self.cell_data.level_mask()
+    }
+
+    fn hash(&self, index: usize) -> UInt256 {
+        self.try_hash(index).unwrap_or_else(|err| panic!("Can't resolve hashes of lazy cell {}: {}", self.index, err))
+    }
+
+    fn depth(&self, index: usize) -> u16 {
+        self.try_depth(index).unwrap_or_else(|err| panic!("Can't resolve depths of lazy cell {}: {}", self.index, err))
+    }
+
+    fn store_hashes(&self) -> bool {
+        self.cell_data.store_hashes()
+    }
+}
+
 /// Wraps I/O operations and computes CRC32-C of the data being processed
 struct IoCrcFilter<'a, T> {
     io_object: &'a mut T,
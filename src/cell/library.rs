@@ -0,0 +1,127 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{
+    fail,
+    cell::{Cell, CellImpl, CellType, LevelMask, SliceData, SHA256_SIZE},
+    types::{Result, UInt256},
+};
+use std::sync::Arc;
+
+/// Source of library cells by their representation hashes
+pub trait LibraryResolver: Send + Sync {
+    /// returns root cell of library or None if library is unknown
+    fn find_library(&self, hash: &UInt256) -> Result<Option<Cell>>;
+}
+
+impl Cell {
+    /// returns hash of referenced library if cell is library reference
+    pub fn library_hash(&self) -> Option<UInt256> {
+        match self.cell_type() {
+            CellType::LibraryReference => {
+                let data = self.data();
+                (data.len() > SHA256_SIZE).then(|| UInt256::from_slice(&data[1..=SHA256_SIZE]))
+            }
+            _ => None
+        }
+    }
+
+    /// returns root of referenced library for library reference or the cell itself otherwise
+    pub fn resolve_library(&self, resolver: &dyn LibraryResolver) -> Result<Cell> {
+        let hash = match self.library_hash() {
+            Some(hash) => hash,
+            None => return Ok(self.clone())
+        };
+        let cell = match resolver.find_library(&hash)? {
+            Some(cell) => cell,
+            None => fail!("library {:x} is not found", hash)
+        };
+        if cell.repr_hash() != hash {
+            fail!("library {:x} is resolved to cell {:x}", hash, cell.repr_hash())
+        }
+        if cell.cell_type() == CellType::LibraryReference {
+            fail!("library {:x} is library reference itself", hash)
+        }
+        Ok(cell)
+    }
+
+    /// returns view of the tree where every library reference is swapped
+    /// for the referenced cell on load
+    pub fn with_library_resolver(&self, resolver: Arc<dyn LibraryResolver>) -> Result<Cell> {
+        let cell = self.resolve_library(resolver.as_ref())?;
+        Ok(Cell::with_cell_impl(ResolvingCell { cell, resolver }))
+    }
+}
+
+impl SliceData {
+    /// loads cell resolving library references for the cell and all its references
+    pub fn load_cell_with_resolver(cell: &Cell, resolver: Arc<dyn LibraryResolver>) -> Result<SliceData> {
+        SliceData::load_cell(cell.with_library_resolver(resolver)?)
+    }
+}
+
+// cell returning resolved references, hashes and data are of the original cell
+struct ResolvingCell {
+    cell: Cell,
+    resolver: Arc<dyn LibraryResolver>,
+}
+
+impl CellImpl for ResolvingCell {
+    fn data(&self) -> &[u8] {
+        self.cell.data()
+    }
+
+    fn raw_data(&self) -> Result<&[u8]> {
+        self.cell.raw_data()
+    }
+
+    fn bit_length(&self) -> usize {
+        self.cell.bit_length()
+    }
+
+    fn references_count(&self) -> usize {
+        self.cell.references_count()
+    }
+
+    fn reference(&self, index: usize) -> Result<Cell> {
+        self.cell.reference(index)?.with_library_resolver(self.resolver.clone())
+    }
+
+    fn cell_type(&self) -> CellType {
+        self.cell.cell_type()
+    }
+
+    fn level_mask(&self) -> LevelMask {
+        self.cell.level_mask()
+    }
+
+    fn hash(&self, index: usize) -> UInt256 {
+        self.cell.hash(index)
+    }
+
+    fn depth(&self, index: usize) -> u16 {
+        self.cell.depth(index)
+    }
+
+    fn store_hashes(&self) -> bool {
+        self.cell.store_hashes()
+    }
+
+    fn tree_bits_count(&self) -> u64 { self.cell.tree_bits_count() }
+
+    fn tree_cell_count(&self) -> u64 { self.cell.tree_cell_count() }
+}
+
+#[cfg(test)]
+#[path = "tests/test_library.rs"]
+mod tests;
@@ -1952,6 +1952,14 @@ pub use self::builder::*;
 mod builder_operations;
 
 pub use self::builder_operations::*;
+
+mod stats;
+
+pub use self::stats::*;
+
+mod library;
+
+pub use self::library::*;
 use smallvec::SmallVec;
 
 pub(crate) fn to_hex_string(data: impl AsRef<[u8]>, len: usize, lower: bool) -> String {
@@ -0,0 +1,197 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{
+    error,
+    cell::{Cell, CellType},
+    types::{Result, UInt256},
+    Serializable,
+};
+use std::{collections::HashMap, fmt};
+
+// unique cell of the tree with indexes of its references
+struct StatsNode {
+    cell: Cell,
+    refs: Vec<usize>,
+}
+
+///
+/// Statistics of cell tree. Unique cells and bits are counted once per representation hash
+/// as for storage fees, total ones are counted for every reference as for tree counters of cell.
+/// Counts by cell type and histogram of subtree sizes are calculated for unique cells
+/// if `dedup` is set or for every occurrence of cell in the tree otherwise.
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CellStats {
+    dedup: bool,
+    unique_cells: u64,
+    unique_bits: u64,
+    total_cells: u64,
+    total_bits: u64,
+    max_depth: u16,
+    by_type: HashMap<CellType, u64>,
+    // count of subtrees with total cells in range [2^i, 2^(i+1))
+    subtree_sizes: Vec<u64>,
+}
+
+impl CellStats {
+    /// calculates statistics of one tree
+    pub fn with_cell(cell: &Cell, dedup: bool) -> Result<Self> {
+        Self::with_cells(std::slice::from_ref(cell), dedup)
+    }
+    /// calculates statistics of serialized structure
+    pub fn calculate_for_struct<T: Serializable>(value: &T, dedup: bool) -> Result<Self> {
+        Self::with_cell(&value.serialize()?, dedup)
+    }
+    /// calculates statistics of several trees, cells common for trees are unique only once
+    pub fn with_cells(roots: &[Cell], dedup: bool) -> Result<Self> {
+        let (nodes, roots) = Self::collect(roots)?;
+        let mut stats = Self {
+            dedup,
+            ..Default::default()
+        };
+        // nodes are in post-order so references are calculated before cell
+        let mut sizes = Vec::with_capacity(nodes.len());
+        let mut depths = Vec::with_capacity(nodes.len());
+        for node in &nodes {
+            let bits = node.cell.bit_length() as u64;
+            let mut size = (1u64, bits);
+            let mut depth = 0;
+            for index in &node.refs {
+                let (cells, bits) = sizes[*index];
+                size = (size.0.saturating_add(cells), size.1.saturating_add(bits));
+                depth = depth.max(depths[*index] + 1);
+            }
+            stats.unique_cells += 1;
+            stats.unique_bits = stats.unique_bits.saturating_add(bits);
+            sizes.push(size);
+            depths.push(depth);
+        }
+        // count of cell occurrences in the trees, parents are before children in reverse post-order
+        let mut occurrences = vec![0u64; nodes.len()];
+        for index in &roots {
+            occurrences[*index] += 1;
+            let (cells, bits) = sizes[*index];
+            stats.total_cells = stats.total_cells.saturating_add(cells);
+            stats.total_bits = stats.total_bits.saturating_add(bits);
+            stats.max_depth = stats.max_depth.max(depths[*index]);
+        }
+        for (index, node) in nodes.iter().enumerate().rev() {
+            for child in &node.refs {
+                occurrences[*child] = occurrences[*child].saturating_add(occurrences[index]);
+            }
+            let count = if dedup { 1 } else { occurrences[index] };
+            let by_type = stats.by_type.entry(node.cell.cell_type()).or_default();
+            *by_type = by_type.saturating_add(count);
+            let bucket = 63 - sizes[index].0.leading_zeros() as usize;
+            if stats.subtree_sizes.len() <= bucket {
+                stats.subtree_sizes.resize(bucket + 1, 0);
+            }
+            stats.subtree_sizes[bucket] = stats.subtree_sizes[bucket].saturating_add(count);
+        }
+        Ok(stats)
+    }
+    // collects unique cells in post-order and returns them with indexes of roots
+    fn collect(roots: &[Cell]) -> Result<(Vec<StatsNode>, Vec<usize>)> {
+        let mut indexes = HashMap::<UInt256, usize>::new();
+        let mut nodes = Vec::new();
+        let mut root_indexes = Vec::with_capacity(roots.len());
+        for root in roots {
+            if let Some(index) = indexes.get(&root.repr_hash()) {
+                root_indexes.push(*index);
+                continue
+            }
+            // cell with count of already processed references
+            let mut stack = vec![(root.clone(), 0)];
+            while let Some((cell, next)) = stack.pop() {
+                if next < cell.references_count() {
+                    let child = cell.reference(next)?;
+                    stack.push((cell, next + 1));
+                    if !indexes.contains_key(&child.repr_hash()) {
+                        stack.push((child, 0));
+                    }
+                    continue
+                }
+                let mut refs = Vec::with_capacity(cell.references_count());
+                for i in 0..cell.references_count() {
+                    let hash = cell.reference(i)?.repr_hash();
+                    refs.push(*indexes.get(&hash).ok_or_else(|| error!("reference {:x} is not collected", hash))?);
+                }
+                indexes.insert(cell.repr_hash(), nodes.len());
+                nodes.push(StatsNode { cell, refs });
+            }
+            root_indexes.push(nodes.len() - 1);
+        }
+        Ok((nodes, root_indexes))
+    }
+    pub fn dedup(&self) -> bool {
+        self.dedup
+    }
+    /// count of cells with different hashes
+    pub fn unique_cells(&self) -> u64 {
+        self.unique_cells
+    }
+    /// bits of cells with different hashes
+    pub fn unique_bits(&self) -> u64 {
+        self.unique_bits
+    }
+    /// count of cells with every reference counted
+    pub fn total_cells(&self) -> u64 {
+        self.total_cells
+    }
+    /// bits of cells with every reference counted
+    pub fn total_bits(&self) -> u64 {
+        self.total_bits
+    }
+    /// maximal count of references from root to leaf
+    pub fn max_depth(&self) -> u16 {
+        self.max_depth
+    }
+    pub fn cells_of_type(&self, cell_type: CellType) -> u64 {
+        self.by_type.get(&cell_type).copied().unwrap_or_default()
+    }
+    pub fn pruned_cells(&self) -> u64 {
+        self.cells_of_type(CellType::PrunedBranch)
+    }
+    pub fn big_cells(&self) -> u64 {
+        self.cells_of_type(CellType::Big)
+    }
+    pub fn library_cells(&self) -> u64 {
+        self.cells_of_type(CellType::LibraryReference)
+    }
+    /// element `i` is count of subtrees which contain from 2^i to 2^(i+1)-1 cells
+    pub fn subtree_sizes(&self) -> &[u64] {
+        &self.subtree_sizes
+    }
+}
+
+impl fmt::Display for CellStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CellStats[unique cells = {}, unique bits = {}, total cells = {}, total bits = {}, max depth = {}",
+            self.unique_cells, self.unique_bits, self.total_cells, self.total_bits, self.max_depth
+        )?;
+        for cell_type in [CellType::PrunedBranch, CellType::LibraryReference, CellType::MerkleProof, CellType::MerkleUpdate, CellType::Big] {
+            let count = self.cells_of_type(cell_type);
+            if count != 0 {
+                write!(f, ", {} = {}", cell_type, count)?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_stats.rs"]
+mod tests;
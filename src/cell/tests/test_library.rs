@@ -0,0 +1,95 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{BuilderData, IBitstring, LibDescr, Libraries, SimpleLib, StateInitLib};
+
+fn make_library_cell(hash: &UInt256) -> Cell {
+    let mut builder = BuilderData::new();
+    builder.set_type(CellType::LibraryReference);
+    builder.append_u8(u8::from(CellType::LibraryReference)).unwrap();
+    builder.append_raw(hash.as_slice(), 256).unwrap();
+    builder.into_cell().unwrap()
+}
+
+fn make_cell(data: u32, refs: &[&Cell]) -> Cell {
+    let mut builder = BuilderData::new();
+    builder.append_u32(data).unwrap();
+    for cell in refs {
+        builder.checked_append_reference((*cell).clone()).unwrap();
+    }
+    builder.into_cell().unwrap()
+}
+
+struct WrongResolver;
+
+impl LibraryResolver for WrongResolver {
+    fn find_library(&self, _hash: &UInt256) -> Result<Option<Cell>> {
+        Ok(Some(make_cell(0, &[])))
+    }
+}
+
+#[test]
+fn test_resolve_libraries() {
+    let code = make_cell(1, &[&make_cell(2, &[])]);
+    let library = make_library_cell(&code.repr_hash());
+    assert_eq!(library.library_hash(), Some(code.repr_hash()));
+    assert_eq!(code.library_hash(), None);
+    let root = make_cell(3, &[&library, &make_cell(4, &[&library])]);
+
+    let mut libs = StateInitLib::default();
+    libs.set(&code.repr_hash(), &SimpleLib::new(code.clone(), false)).unwrap();
+    let resolver: Arc<dyn LibraryResolver> = Arc::new(libs);
+
+    let resolved = root.with_library_resolver(resolver.clone()).unwrap();
+    assert_eq!(resolved.repr_hash(), root.repr_hash());
+    assert_eq!(resolved.reference(0).unwrap(), code);
+    assert_eq!(resolved.reference(1).unwrap().reference(0).unwrap(), code);
+    let mut slice = SliceData::load_cell_with_resolver(&root, resolver.clone()).unwrap();
+    assert_eq!(slice.get_next_u32().unwrap(), 3);
+    let mut slice = SliceData::load_cell(slice.checked_drain_reference().unwrap()).unwrap();
+    assert_eq!(slice.get_next_u32().unwrap(), 1);
+    let mut slice = SliceData::load_cell(slice.checked_drain_reference().unwrap()).unwrap();
+    assert_eq!(slice.get_next_u32().unwrap(), 2);
+
+    // library reference as root
+    let mut slice = SliceData::load_cell_with_resolver(&library, resolver.clone()).unwrap();
+    assert_eq!(slice.get_next_u32().unwrap(), 1);
+    assert_eq!(library.resolve_library(resolver.as_ref()).unwrap(), code);
+    assert_eq!(code.resolve_library(resolver.as_ref()).unwrap(), code);
+
+    // unknown library is found only on load of the reference
+    let unknown = make_library_cell(&UInt256::rand());
+    let root = make_cell(5, &[&unknown]);
+    let resolved = root.with_library_resolver(resolver.clone()).unwrap();
+    resolved.reference(0).expect_err("library should not be found");
+    unknown.with_library_resolver(resolver).expect_err("library should not be found");
+
+    library.resolve_library(&WrongResolver).expect_err("library hash should be checked");
+}
+
+#[test]
+fn test_resolve_masterchain_libraries() {
+    let code = make_cell(1, &[]);
+    let library = make_library_cell(&code.repr_hash());
+    let mut libs = Libraries::default();
+    libs.set(&code.repr_hash(), &LibDescr::from_lib_data_by_publisher(code.clone(), UInt256::rand().into())).unwrap();
+    assert_eq!(library.resolve_library(&libs).unwrap(), code);
+    let other = make_library_cell(&make_cell(2, &[]).repr_hash());
+    other.resolve_library(&libs).expect_err("library should not be found");
+
+    // library which is library reference itself is not resolved
+    let mut libs = StateInitLib::default();
+    libs.set(&library.repr_hash(), &SimpleLib::new(library.clone(), true)).unwrap();
+    make_library_cell(&library.repr_hash()).resolve_library(&libs).expect_err("nested library");
+}
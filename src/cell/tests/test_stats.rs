@@ -0,0 +1,120 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    create_big_cell, Block, BuilderData, Deserializable, IBitstring, MerkleProof, StorageUsed,
+};
+
+fn make_cell(data: u8, refs: &[&Cell]) -> Cell {
+    let mut builder = BuilderData::new();
+    builder.append_u8(data).unwrap();
+    for cell in refs {
+        builder.checked_append_reference((*cell).clone()).unwrap();
+    }
+    builder.into_cell().unwrap()
+}
+
+#[test]
+fn test_cell_stats_shared_cells() {
+    let leaf = make_cell(1, &[]);
+    let fork = make_cell(2, &[&leaf, &leaf]);
+    let root = make_cell(3, &[&fork, &leaf]);
+
+    let stats = CellStats::with_cell(&root, true).unwrap();
+    assert_eq!(stats.unique_cells(), 3);
+    assert_eq!(stats.unique_bits(), 24);
+    assert_eq!(stats.total_cells(), 5);
+    assert_eq!(stats.total_bits(), 40);
+    assert_eq!(stats.total_cells(), root.tree_cell_count());
+    assert_eq!(stats.total_bits(), root.tree_bits_count());
+    assert_eq!(stats.max_depth(), 2);
+    assert_eq!(stats.cells_of_type(CellType::Ordinary), 3);
+    // subtrees of 1, 3 and 5 cells
+    assert_eq!(stats.subtree_sizes(), &[1, 1, 1]);
+
+    let stats = CellStats::with_cell(&root, false).unwrap();
+    assert_eq!(stats.unique_cells(), 3);
+    assert_eq!(stats.total_cells(), 5);
+    assert_eq!(stats.cells_of_type(CellType::Ordinary), 5);
+    assert_eq!(stats.subtree_sizes(), &[3, 1, 1]);
+
+    // common cells of several roots are unique only once
+    let stats = CellStats::with_cells(&[root.clone(), fork.clone(), root.clone()], true).unwrap();
+    assert_eq!(stats.unique_cells(), 3);
+    assert_eq!(stats.total_cells(), 13);
+    let stats = CellStats::with_cells(&[fork, root], false).unwrap();
+    assert_eq!(stats.cells_of_type(CellType::Ordinary), 8);
+    assert_eq!(stats.subtree_sizes(), &[5, 2, 1]);
+}
+
+#[test]
+fn test_cell_stats_deep_tree() {
+    let mut cell = make_cell(0, &[]);
+    for i in 0..2000 {
+        cell = make_cell(i as u8, &[&cell, &cell]);
+    }
+    let stats = CellStats::with_cell(&cell, false).unwrap();
+    assert_eq!(stats.unique_cells(), 2001);
+    assert_eq!(stats.max_depth(), 2000);
+    assert_eq!(stats.total_cells(), u64::MAX);
+    assert_eq!(stats.cells_of_type(CellType::Ordinary), u64::MAX);
+}
+
+#[test]
+fn test_cell_stats_exotic_cells() {
+    let big = create_big_cell(&[1; 1000]).unwrap();
+    let stats = CellStats::with_cell(&big, true).unwrap();
+    assert_eq!(stats.big_cells(), 1);
+    assert_eq!(stats.unique_bits(), 8000);
+
+    let mut library = BuilderData::new();
+    library.set_type(CellType::LibraryReference);
+    library.append_u8(u8::from(CellType::LibraryReference)).unwrap();
+    library.append_raw(big.repr_hash().as_slice(), 256).unwrap();
+    let library = library.into_cell().unwrap();
+    let root = make_cell(1, &[&library, &library]);
+    let stats = CellStats::with_cell(&root, false).unwrap();
+    assert_eq!(stats.library_cells(), 2);
+    assert_eq!(CellStats::with_cell(&root, true).unwrap().library_cells(), 1);
+
+    let block = Cell::read_from_file("src/tests/data/9C2B3FC5AD455917D374CFADBED8FC2343E31A27C1DF2EB29E84404FA96DE9F8.boc");
+    let info = block.reference(0).unwrap();
+    let proof = MerkleProof::create(&block, |hash| hash == &block.repr_hash() || hash == &info.repr_hash()).unwrap();
+    let stats = CellStats::calculate_for_struct(&proof, true).unwrap();
+    assert_eq!(stats.cells_of_type(CellType::MerkleProof), 1);
+    assert_eq!(stats.pruned_cells(), block.references_count() as u64 - 1 + info.references_count() as u64);
+    let text = stats.to_string();
+    assert!(text.starts_with(&format!("CellStats[unique cells = {}, ", stats.unique_cells())), "{}", text);
+    assert!(text.contains(&format!(", Pruned branch = {}", stats.pruned_cells())), "{}", text);
+    assert!(text.ends_with(", Merkle proof = 1]"), "{}", text);
+    assert!(!text.contains("Library reference"), "{}", text);
+}
+
+#[test]
+fn test_cell_stats_storage_used() {
+    let block = Block::construct_from_file(
+        "src/tests/data/9C2B3FC5AD455917D374CFADBED8FC2343E31A27C1DF2EB29E84404FA96DE9F8.boc"
+    ).unwrap();
+    let cell = block.serialize().unwrap();
+    let used = StorageUsed::calculate_for_struct(&block).unwrap();
+    let stats = CellStats::calculate_for_struct(&block, true).unwrap();
+    assert_eq!(stats.unique_cells(), used.cells());
+    assert_eq!(stats.unique_bits(), used.bits());
+    assert_eq!(stats.total_cells(), cell.tree_cell_count());
+    assert_eq!(stats.total_bits(), cell.tree_bits_count());
+    assert_eq!(stats.max_depth(), cell.repr_depth());
+    assert_eq!(stats.subtree_sizes().iter().sum::<u64>(), stats.unique_cells());
+    let stats = CellStats::calculate_for_struct(&block, false).unwrap();
+    assert_eq!(stats.subtree_sizes().iter().sum::<u64>(), stats.total_cells());
+}
@@ -12,6 +12,7 @@ const TAG_MESH: usize = 1;
 
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum CommonMessage {
     Std(Message),
     // TODO create message variant for inter network routing
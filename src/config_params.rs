@@ -35,6 +35,7 @@ _ config_addr:uint256
 config:^(Hashmap 32 ^Cell) = ConfigParams;
 */
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConfigParams {
     pub config_addr: UInt256,
     pub config_params: HashmapE // <u32, SliceData>
@@ -342,6 +343,48 @@ impl ConfigParams {
             _ =>  fail!("wrong config 44 (suspended addresses)")
         }
     }
+    pub fn global_id(&self) -> Result<Option<i32>> {
+        match self.config(19)? {
+            Some(ConfigParamEnum::ConfigParam19(param)) => Ok(Some(param.global_id)),
+            None => Ok(None),
+            _ => fail!("wrong config 19 (global id)")
+        }
+    }
+    /// Returns size limits from config 43 or default ones if it is absent
+    pub fn size_limits_config(&self) -> Result<SizeLimitsConfig> {
+        match self.config(43)? {
+            Some(ConfigParamEnum::ConfigParam43(param)) => Ok(param),
+            None => Ok(SizeLimitsConfig::default()),
+            _ => fail!("wrong config 43 (size limits)")
+        }
+    }
+    pub fn precompiled_contracts_config(&self) -> Result<Option<PrecompiledContractsConfig>> {
+        match self.config(45)? {
+            Some(ConfigParamEnum::ConfigParam45(param)) => Ok(Some(param)),
+            None => Ok(None),
+            _ => fail!("wrong config 45 (precompiled contracts)")
+        }
+    }
+    /// Returns oracle bridge params from config 71, 72 or 73
+    pub fn oracle_bridge_params(&self, index: u32) -> Result<Option<OracleBridgeParams>> {
+        match (index, self.config(index)?) {
+            (71, Some(ConfigParamEnum::ConfigParam71(param))) |
+            (72, Some(ConfigParamEnum::ConfigParam72(param))) |
+            (73, Some(ConfigParamEnum::ConfigParam73(param))) => Ok(Some(param)),
+            (71..=73, None) => Ok(None),
+            _ => fail!("wrong config {} (oracle bridge params)", index)
+        }
+    }
+    /// Returns token bridge params from config 79, 81 or 82
+    pub fn jetton_bridge_params(&self, index: u32) -> Result<Option<JettonBridgeParams>> {
+        match (index, self.config(index)?) {
+            (79, Some(ConfigParamEnum::ConfigParam79(param))) |
+            (81, Some(ConfigParamEnum::ConfigParam81(param))) |
+            (82, Some(ConfigParamEnum::ConfigParam82(param))) => Ok(Some(param)),
+            (79 | 81 | 82, None) => Ok(None),
+            _ => fail!("wrong config {} (jetton bridge params)", index)
+        }
+    }
     // TODO 39 validator signed temp keys
     // ConfigParam58(MeshConfig),
     pub fn mesh_config(&self) -> Result<Option<MeshConfig>> {
@@ -563,6 +606,7 @@ pub enum ConfigParamEnum {
     ConfigParam16(ConfigParam16),
     ConfigParam17(ConfigParam17),
     ConfigParam18(ConfigParam18),
+    ConfigParam19(ConfigParam19),
     ConfigParam20(GasLimitsPrices),
     ConfigParam21(GasLimitsPrices),
     ConfigParam22(ConfigParam22),
@@ -582,10 +626,18 @@ pub enum ConfigParamEnum {
     ConfigParam39(ConfigParam39),
     ConfigParam40(ConfigParam40),
     ConfigParam42(ConfigCopyleft),
+    ConfigParam43(SizeLimitsConfig),
     ConfigParam44(SuspendedAddresses),
+    ConfigParam45(PrecompiledContractsConfig),
     ConfigParam58(MeshConfig),
     ConfigParam61(FastFinalityConfig),
     ConfigParam62(SmftParams),
+    ConfigParam71(OracleBridgeParams),
+    ConfigParam72(OracleBridgeParams),
+    ConfigParam73(OracleBridgeParams),
+    ConfigParam79(JettonBridgeParams),
+    ConfigParam81(JettonBridgeParams),
+    ConfigParam82(JettonBridgeParams),
     ConfigParamAny(u32, SliceData),
 }
 
@@ -626,6 +678,7 @@ impl ConfigParamEnum {
             16 => { read_config!(ConfigParam16, ConfigParam16, slice) },
             17 => { read_config!(ConfigParam17, ConfigParam17, slice) },
             18 => { read_config!(ConfigParam18, ConfigParam18, slice) },
+            19 => { read_config!(ConfigParam19, ConfigParam19, slice) },
             20 => { read_config!(ConfigParam20, GasLimitsPrices, slice) },
             21 => { read_config!(ConfigParam21, GasLimitsPrices, slice) },
             22 => { read_config!(ConfigParam22, ConfigParam22, slice) },
@@ -645,10 +698,18 @@ impl ConfigParamEnum {
             39 => { read_config!(ConfigParam39, ConfigParam39, slice) },
             40 => { read_config!(ConfigParam40, ConfigParam40, slice) },
             42 => { read_config!(ConfigParam42, ConfigCopyleft, slice) },
+            43 => { read_config!(ConfigParam43, SizeLimitsConfig, slice) },
             44 => { read_config!(ConfigParam44, SuspendedAddresses, slice) },
+            45 => { read_config!(ConfigParam45, PrecompiledContractsConfig, slice) },
             58 => { read_config!(ConfigParam58, MeshConfig, slice) },
             61 => { read_config!(ConfigParam61, FastFinalityConfig, slice) },
             62 => { read_config!(ConfigParam62, SmftParams, slice) },
+            71 => { read_config!(ConfigParam71, OracleBridgeParams, slice) },
+            72 => { read_config!(ConfigParam72, OracleBridgeParams, slice) },
+            73 => { read_config!(ConfigParam73, OracleBridgeParams, slice) },
+            79 => { read_config!(ConfigParam79, JettonBridgeParams, slice) },
+            81 => { read_config!(ConfigParam81, JettonBridgeParams, slice) },
+            82 => { read_config!(ConfigParam82, JettonBridgeParams, slice) },
             index => Ok(ConfigParamEnum::ConfigParamAny(index, slice.clone())),
         }
     }
@@ -675,6 +736,7 @@ impl ConfigParamEnum {
             ConfigParamEnum::ConfigParam16(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(16)},
             ConfigParamEnum::ConfigParam17(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(17)},
             ConfigParamEnum::ConfigParam18(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(18)},
+            ConfigParamEnum::ConfigParam19(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(19)},
             ConfigParamEnum::ConfigParam20(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(20)},
             ConfigParamEnum::ConfigParam21(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(21)},
             ConfigParamEnum::ConfigParam22(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(22)},
@@ -694,10 +756,18 @@ impl ConfigParamEnum {
             ConfigParamEnum::ConfigParam39(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(39)},
             ConfigParamEnum::ConfigParam40(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(40)},
             ConfigParamEnum::ConfigParam42(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(42)},
+            ConfigParamEnum::ConfigParam43(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(43)},
             ConfigParamEnum::ConfigParam44(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(44)},
+            ConfigParamEnum::ConfigParam45(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(45)},
             ConfigParamEnum::ConfigParam58(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(58)},
             ConfigParamEnum::ConfigParam61(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(61)},
             ConfigParamEnum::ConfigParam62(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(62)},
+            ConfigParamEnum::ConfigParam71(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(71)},
+            ConfigParamEnum::ConfigParam72(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(72)},
+            ConfigParamEnum::ConfigParam73(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(73)},
+            ConfigParamEnum::ConfigParam79(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(79)},
+            ConfigParamEnum::ConfigParam81(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(81)},
+            ConfigParamEnum::ConfigParam82(ref c) => { cell.checked_append_reference(c.serialize()?)?; Ok(82)},
             ConfigParamEnum::ConfigParamAny(index, slice) => { 
                 cell.checked_append_reference(slice.clone().into_cell())?; 
                 Ok(*index)
@@ -928,6 +998,7 @@ impl Serializable for ConfigParam7 {
 // _ GlobalVersion = ConfigParam 8;  // all zero if absent
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlobalVersion {
     pub version: u32,
     pub capabilities: u64,
@@ -1250,6 +1321,7 @@ _ (Hashmap 32 StoragePrices) = ConfigParam 18;
 /// StoragePrices structure
 /// 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct StoragePrices {
     pub utime_since: u32,
     pub bit_price_ps: u64,
@@ -1355,6 +1427,36 @@ impl Serializable for ConfigParam18 {
     }
 }
 
+/*
+_ global_id:int32 = ConfigParam 19;
+*/
+
+///
+/// Config Param 19 structure
+/// 
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct ConfigParam19 {
+    pub global_id: i32,
+}
+
+impl ConfigParam19 {
+    pub fn new() -> Self { Self::default() }
+}
+
+impl Deserializable for ConfigParam19 {
+    fn read_from(&mut self, cell: &mut SliceData) -> Result<()> {
+        self.global_id.read_from(cell)?;
+        Ok(())
+    }
+}
+
+impl Serializable for ConfigParam19 {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        self.global_id.write_to(cell)?;
+        Ok(())
+    }
+}
+
 /*
 gas_prices#dd 
     gas_price:uint64 
@@ -3255,6 +3357,240 @@ impl SuspendedAddresses {
     }
 }
 
+/*
+size_limits_config#01
+    max_msg_bits:uint32
+    max_msg_cells:uint32
+    max_library_cells:uint32
+    max_vm_data_depth:uint16
+    max_ext_msg_size:uint32
+    max_ext_msg_depth:uint16
+= SizeLimitsConfig;
+
+size_limits_config_v2#02
+    max_msg_bits:uint32
+    max_msg_cells:uint32
+    max_library_cells:uint32
+    max_vm_data_depth:uint16
+    max_ext_msg_size:uint32
+    max_ext_msg_depth:uint16
+    max_acc_state_cells:uint32
+    max_acc_state_bits:uint32
+    max_acc_public_libraries:uint32
+    defer_out_queue_size_limit:uint32
+    max_msg_extra_currencies:uint32
+    max_acc_fixed_prefix_length:uint8
+= SizeLimitsConfig;
+
+_ SizeLimitsConfig = ConfigParam 43;
+*/
+
+const SIZE_LIMITS_CONFIG_TAG_1: u8 = 0x01;
+const SIZE_LIMITS_CONFIG_TAG_2: u8 = 0x02;
+
+///
+/// ConfigParam 43 struct
+/// Fields absent in the first version are set to default values while reading it.
+/// `version` keeps the constructor which was read and is written back.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SizeLimitsConfig {
+    pub version: u8,
+    pub max_msg_bits: u32,
+    pub max_msg_cells: u32,
+    pub max_library_cells: u32,
+    pub max_vm_data_depth: u16,
+    pub max_ext_msg_size: u32,
+    pub max_ext_msg_depth: u16,
+    pub max_acc_state_cells: u32,
+    pub max_acc_state_bits: u32,
+    pub max_acc_public_libraries: u32,
+    pub defer_out_queue_size_limit: u32,
+    pub max_msg_extra_currencies: u32,
+    pub max_acc_fixed_prefix_length: u8,
+}
+
+impl SizeLimitsConfig {
+    pub const fn new() -> Self {
+        Self {
+            version: SIZE_LIMITS_CONFIG_TAG_2,
+            max_msg_bits: 1 << 21,
+            max_msg_cells: 1 << 13,
+            max_library_cells: 1000,
+            max_vm_data_depth: 512,
+            max_ext_msg_size: 65535,
+            max_ext_msg_depth: 512,
+            max_acc_state_cells: 1 << 16,
+            max_acc_state_bits: (1 << 16) * 1023,
+            max_acc_public_libraries: 256,
+            defer_out_queue_size_limit: 256,
+            max_msg_extra_currencies: 2,
+            max_acc_fixed_prefix_length: 8,
+        }
+    }
+}
+
+impl Default for SizeLimitsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializable for SizeLimitsConfig {
+    fn read_from(&mut self, cell: &mut SliceData) -> Result<()> {
+        let tag = cell.get_next_byte()?;
+        if (tag != SIZE_LIMITS_CONFIG_TAG_1) && (tag != SIZE_LIMITS_CONFIG_TAG_2) {
+            fail!(
+                BlockError::InvalidConstructorTag {
+                    t: tag as u32,
+                    s: std::any::type_name::<Self>().to_string()
+                }
+            )
+        }
+        *self = Self::new();
+        self.version = tag;
+        self.max_msg_bits.read_from(cell)?;
+        self.max_msg_cells.read_from(cell)?;
+        self.max_library_cells.read_from(cell)?;
+        self.max_vm_data_depth.read_from(cell)?;
+        self.max_ext_msg_size.read_from(cell)?;
+        self.max_ext_msg_depth.read_from(cell)?;
+        if tag == SIZE_LIMITS_CONFIG_TAG_2 {
+            self.max_acc_state_cells.read_from(cell)?;
+            self.max_acc_state_bits.read_from(cell)?;
+            self.max_acc_public_libraries.read_from(cell)?;
+            self.defer_out_queue_size_limit.read_from(cell)?;
+            self.max_msg_extra_currencies.read_from(cell)?;
+            self.max_acc_fixed_prefix_length.read_from(cell)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serializable for SizeLimitsConfig {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        match self.version {
+            SIZE_LIMITS_CONFIG_TAG_1 => {
+                let default = Self::new();
+                if (self.max_acc_state_cells, self.max_acc_state_bits, self.max_acc_public_libraries,
+                    self.defer_out_queue_size_limit, self.max_msg_extra_currencies, self.max_acc_fixed_prefix_length)
+                    != (default.max_acc_state_cells, default.max_acc_state_bits, default.max_acc_public_libraries,
+                    default.defer_out_queue_size_limit, default.max_msg_extra_currencies, default.max_acc_fixed_prefix_length)
+                {
+                    fail!(BlockError::InvalidData(
+                        "first version of SizeLimitsConfig can't contain non default account limits".to_string()
+                    ))
+                }
+            }
+            SIZE_LIMITS_CONFIG_TAG_2 => (),
+            version => fail!(BlockError::InvalidData(format!("unknown SizeLimitsConfig version {}", version)))
+        }
+        cell.append_u8(self.version)?;
+        self.max_msg_bits.write_to(cell)?;
+        self.max_msg_cells.write_to(cell)?;
+        self.max_library_cells.write_to(cell)?;
+        self.max_vm_data_depth.write_to(cell)?;
+        self.max_ext_msg_size.write_to(cell)?;
+        self.max_ext_msg_depth.write_to(cell)?;
+        if self.version == SIZE_LIMITS_CONFIG_TAG_1 {
+            return Ok(())
+        }
+        self.max_acc_state_cells.write_to(cell)?;
+        self.max_acc_state_bits.write_to(cell)?;
+        self.max_acc_public_libraries.write_to(cell)?;
+        self.defer_out_queue_size_limit.write_to(cell)?;
+        self.max_msg_extra_currencies.write_to(cell)?;
+        self.max_acc_fixed_prefix_length.write_to(cell)?;
+        Ok(())
+    }
+}
+
+/*
+precompiled_smc#b0 gas_usage:uint64 = PrecompiledSmc;
+precompiled_contracts_config#c0 list:(HashmapE 256 PrecompiledSmc) = PrecompiledContractsConfig;
+_ PrecompiledContractsConfig = ConfigParam 45;
+*/
+
+const PRECOMPILED_SMC_TAG: u8 = 0xB0;
+const PRECOMPILED_CONTRACTS_CONFIG_TAG: u8 = 0xC0;
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrecompiledSmc {
+    pub gas_usage: u64,
+}
+
+impl Deserializable for PrecompiledSmc {
+    fn read_from(&mut self, cell: &mut SliceData) -> Result<()> {
+        let tag = cell.get_next_byte()?;
+        if tag != PRECOMPILED_SMC_TAG {
+            fail!(
+                BlockError::InvalidConstructorTag {
+                    t: tag as u32,
+                    s: std::any::type_name::<Self>().to_string()
+                }
+            )
+        }
+        self.gas_usage.read_from(cell)?;
+        Ok(())
+    }
+}
+
+impl Serializable for PrecompiledSmc {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        cell.append_u8(PRECOMPILED_SMC_TAG)?;
+        self.gas_usage.write_to(cell)?;
+        Ok(())
+    }
+}
+
+// key is code hash of the contract
+define_HashmapE!{PrecompiledContracts, 256, PrecompiledSmc}
+
+///
+/// ConfigParam 45 struct
+///
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct PrecompiledContractsConfig {
+    pub list: PrecompiledContracts,
+}
+
+impl PrecompiledContractsConfig {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn add_contract(&mut self, code_hash: &UInt256, gas_usage: u64) -> Result<()> {
+        self.list.set(code_hash, &PrecompiledSmc { gas_usage })
+    }
+
+    pub fn gas_usage(&self, code_hash: &UInt256) -> Result<Option<u64>> {
+        Ok(self.list.get(code_hash)?.map(|smc| smc.gas_usage))
+    }
+}
+
+impl Deserializable for PrecompiledContractsConfig {
+    fn read_from(&mut self, cell: &mut SliceData) -> Result<()> {
+        let tag = cell.get_next_byte()?;
+        if tag != PRECOMPILED_CONTRACTS_CONFIG_TAG {
+            fail!(
+                BlockError::InvalidConstructorTag {
+                    t: tag as u32,
+                    s: std::any::type_name::<Self>().to_string()
+                }
+            )
+        }
+        self.list.read_from(cell)?;
+        Ok(())
+    }
+}
+
+impl Serializable for PrecompiledContractsConfig {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        cell.append_u8(PRECOMPILED_CONTRACTS_CONFIG_TAG)?;
+        self.list.write_to(cell)?;
+        Ok(())
+    }
+}
+
 define_HashmapE!{MeshConfig, 32, ConnectedNwConfig}
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -3635,3 +3971,236 @@ impl Serializable for SmftParams {
         Ok(())
     }
 }
+
+/*
+oracle_bridge_params#_
+    bridge_address:bits256
+    oracle_mutlisig_address:bits256
+    oracles:(HashmapE 256 uint256)
+    external_chain_address:bits256
+= OracleBridgeParams;
+
+_ OracleBridgeParams = ConfigParam 71; // Ethereum bridge
+_ OracleBridgeParams = ConfigParam 72; // Binance Smart Chain bridge
+_ OracleBridgeParams = ConfigParam 73; // Polygon bridge
+*/
+
+// oracle's validator public key -> oracle's address in the external chain
+define_HashmapE!{BridgeOracles, 256, UInt256}
+
+///
+/// ConfigParam 71, 72, 73 struct
+///
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct OracleBridgeParams {
+    pub bridge_address: UInt256,
+    pub oracle_multisig_address: UInt256,
+    pub oracles: BridgeOracles,
+    pub external_chain_address: UInt256,
+}
+
+impl OracleBridgeParams {
+    pub fn new() -> Self { Self::default() }
+}
+
+impl Deserializable for OracleBridgeParams {
+    fn read_from(&mut self, cell: &mut SliceData) -> Result<()> {
+        self.bridge_address.read_from(cell)?;
+        self.oracle_multisig_address.read_from(cell)?;
+        self.oracles.read_from(cell)?;
+        self.external_chain_address.read_from(cell)?;
+        Ok(())
+    }
+}
+
+impl Serializable for OracleBridgeParams {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        self.bridge_address.write_to(cell)?;
+        self.oracle_multisig_address.write_to(cell)?;
+        self.oracles.write_to(cell)?;
+        self.external_chain_address.write_to(cell)?;
+        Ok(())
+    }
+}
+
+/*
+jetton_bridge_prices#_
+    bridge_burn_fee:Coins
+    bridge_mint_fee:Coins
+    wallet_min_tons_for_storage:Coins
+    wallet_gas_consumption:Coins
+    minter_min_tons_for_storage:Coins
+    discover_gas_consumption:Coins
+= JettonBridgePrices;
+
+jetton_bridge_params_v0#00
+    bridge_address:bits256
+    oracles_address:bits256
+    oracles:(HashmapE 256 uint256)
+    state_flags:uint8
+    burn_bridge_fee:Coins
+= JettonBridgeParams;
+
+jetton_bridge_params_v1#01
+    bridge_address:bits256
+    oracles_address:bits256
+    oracles:(HashmapE 256 uint256)
+    state_flags:uint8
+    prices:^JettonBridgePrices
+    external_chain_address:bits256
+= JettonBridgeParams;
+
+_ JettonBridgeParams = ConfigParam 79; // ETH->TON token bridge
+_ JettonBridgeParams = ConfigParam 81; // BNB->TON token bridge
+_ JettonBridgeParams = ConfigParam 82; // Polygon->TON token bridge
+*/
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct JettonBridgePrices {
+    pub bridge_burn_fee: Grams,
+    pub bridge_mint_fee: Grams,
+    pub wallet_min_tons_for_storage: Grams,
+    pub wallet_gas_consumption: Grams,
+    pub minter_min_tons_for_storage: Grams,
+    pub discover_gas_consumption: Grams,
+}
+
+impl Deserializable for JettonBridgePrices {
+    fn read_from(&mut self, cell: &mut SliceData) -> Result<()> {
+        self.bridge_burn_fee.read_from(cell)?;
+        self.bridge_mint_fee.read_from(cell)?;
+        self.wallet_min_tons_for_storage.read_from(cell)?;
+        self.wallet_gas_consumption.read_from(cell)?;
+        self.minter_min_tons_for_storage.read_from(cell)?;
+        self.discover_gas_consumption.read_from(cell)?;
+        Ok(())
+    }
+}
+
+impl Serializable for JettonBridgePrices {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        self.bridge_burn_fee.write_to(cell)?;
+        self.bridge_mint_fee.write_to(cell)?;
+        self.wallet_min_tons_for_storage.write_to(cell)?;
+        self.wallet_gas_consumption.write_to(cell)?;
+        self.minter_min_tons_for_storage.write_to(cell)?;
+        self.discover_gas_consumption.write_to(cell)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct JettonBridgeParamsV0 {
+    pub bridge_address: UInt256,
+    pub oracles_address: UInt256,
+    pub oracles: BridgeOracles,
+    pub state_flags: u8,
+    pub burn_bridge_fee: Grams,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct JettonBridgeParamsV1 {
+    pub bridge_address: UInt256,
+    pub oracles_address: UInt256,
+    pub oracles: BridgeOracles,
+    pub state_flags: u8,
+    pub prices: JettonBridgePrices,
+    pub external_chain_address: UInt256,
+}
+
+const JETTON_BRIDGE_PARAMS_V0_TAG: u8 = 0x00;
+const JETTON_BRIDGE_PARAMS_V1_TAG: u8 = 0x01;
+
+///
+/// ConfigParam 79, 81, 82 enum
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum JettonBridgeParams {
+    V0(JettonBridgeParamsV0),
+    V1(JettonBridgeParamsV1),
+}
+
+impl Default for JettonBridgeParams {
+    fn default() -> Self {
+        JettonBridgeParams::V1(JettonBridgeParamsV1::default())
+    }
+}
+
+impl JettonBridgeParams {
+    pub fn bridge_address(&self) -> &UInt256 {
+        match self {
+            JettonBridgeParams::V0(params) => &params.bridge_address,
+            JettonBridgeParams::V1(params) => &params.bridge_address,
+        }
+    }
+    pub fn oracles(&self) -> &BridgeOracles {
+        match self {
+            JettonBridgeParams::V0(params) => &params.oracles,
+            JettonBridgeParams::V1(params) => &params.oracles,
+        }
+    }
+    pub fn state_flags(&self) -> u8 {
+        match self {
+            JettonBridgeParams::V0(params) => params.state_flags,
+            JettonBridgeParams::V1(params) => params.state_flags,
+        }
+    }
+}
+
+impl Deserializable for JettonBridgeParams {
+    fn construct_from(slice: &mut SliceData) -> Result<Self> {
+        let tag = slice.get_next_byte()?;
+        match tag {
+            JETTON_BRIDGE_PARAMS_V0_TAG => {
+                Ok(JettonBridgeParams::V0(JettonBridgeParamsV0 {
+                    bridge_address: Deserializable::construct_from(slice)?,
+                    oracles_address: Deserializable::construct_from(slice)?,
+                    oracles: Deserializable::construct_from(slice)?,
+                    state_flags: Deserializable::construct_from(slice)?,
+                    burn_bridge_fee: Deserializable::construct_from(slice)?,
+                }))
+            }
+            JETTON_BRIDGE_PARAMS_V1_TAG => {
+                Ok(JettonBridgeParams::V1(JettonBridgeParamsV1 {
+                    bridge_address: Deserializable::construct_from(slice)?,
+                    oracles_address: Deserializable::construct_from(slice)?,
+                    oracles: Deserializable::construct_from(slice)?,
+                    state_flags: Deserializable::construct_from(slice)?,
+                    prices: JettonBridgePrices::construct_from_reference(slice)?,
+                    external_chain_address: Deserializable::construct_from(slice)?,
+                }))
+            }
+            _ => fail!(
+                BlockError::InvalidConstructorTag {
+                    t: tag as u32,
+                    s: std::any::type_name::<Self>().to_string()
+                }
+            )
+        }
+    }
+}
+
+impl Serializable for JettonBridgeParams {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        match self {
+            JettonBridgeParams::V0(params) => {
+                cell.append_u8(JETTON_BRIDGE_PARAMS_V0_TAG)?;
+                params.bridge_address.write_to(cell)?;
+                params.oracles_address.write_to(cell)?;
+                params.oracles.write_to(cell)?;
+                params.state_flags.write_to(cell)?;
+                params.burn_bridge_fee.write_to(cell)?;
+            }
+            JettonBridgeParams::V1(params) => {
+                cell.append_u8(JETTON_BRIDGE_PARAMS_V1_TAG)?;
+                params.bridge_address.write_to(cell)?;
+                params.oracles_address.write_to(cell)?;
+                params.oracles.write_to(cell)?;
+                params.state_flags.write_to(cell)?;
+                cell.checked_append_reference(params.prices.serialize()?)?;
+                params.external_chain_address.write_to(cell)?;
+            }
+        }
+        Ok(())
+    }
+}
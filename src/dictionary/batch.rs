@@ -0,0 +1,238 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::{HashmapBulkBuilder, HashmapRemover, HashmapType};
+use crate::{
+    cell::{BuilderData, Cell, SliceData},
+    error::BlockError, fail, Augmentable, Augmentation, Deserializable, HashmapAugType, Result,
+    Serializable, SERDE_OPTS_EMPTY,
+};
+use std::{collections::BTreeMap, marker::PhantomData};
+
+// key and new leaf remainder or None to remove
+type BatchOp = (SliceData, Option<SliceData>);
+
+// node of the tree with label starting from some position of the key
+struct BatchNode {
+    label: SliceData,
+    remainder: SliceData,
+    // original cell if label is not changed
+    cell: Option<Cell>,
+}
+
+impl BatchNode {
+    fn with_cell(cell: &Cell, bit_len: usize) -> Result<Self> {
+        let mut remainder = SliceData::load_cell_ref(cell)?;
+        let label = remainder.get_label(bit_len)?;
+        Ok(Self { label, remainder, cell: Some(cell.clone()) })
+    }
+    fn into_cell<T: HashmapType + ?Sized>(self, bit_len: usize) -> Result<Cell> {
+        match self.cell {
+            Some(cell) => Ok(cell),
+            None => {
+                let is_leaf = self.label.remaining_bits() == bit_len;
+                T::make_cell_with_label_and_data(self.label, bit_len, is_leaf, &self.remainder)?.into_cell()
+            }
+        }
+    }
+}
+
+///
+/// Buffer of sets and removes applied to dictionary at once.
+/// Operations are sorted by keys and merged with the tree in one traversal:
+/// untouched subtrees are shared with the original tree and cells
+/// (with augmentation for augmented dictionaries) are rebuilt only along changed paths.
+/// The last operation with the same key wins.
+///
+pub struct DictBatch<T: HashmapType + ?Sized> {
+    bit_len: usize,
+    opts: u8,
+    ops: BTreeMap<SliceData, Option<SliceData>>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: HashmapType + ?Sized> DictBatch<T> {
+    pub fn new(bit_len: usize) -> Self {
+        Self::with_serde_opts(bit_len, SERDE_OPTS_EMPTY)
+    }
+    pub fn with_serde_opts(bit_len: usize, opts: u8) -> Self {
+        Self {
+            bit_len,
+            opts,
+            ops: BTreeMap::new(),
+            phantom: PhantomData,
+        }
+    }
+    /// Number of buffered operations
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+    /// Sets leaf remainder (with augmentation for augmented dictionaries)
+    pub fn set(&mut self, key: SliceData, value: SliceData) -> Result<()> {
+        T::check_key_fail(self.bit_len, &key)?;
+        self.ops.insert(key, Some(value));
+        Ok(())
+    }
+    pub fn set_builder(&mut self, key: SliceData, value: &BuilderData) -> Result<()> {
+        self.set(key, SliceData::load_builder(value.clone())?)
+    }
+    pub fn setref(&mut self, key: SliceData, value: &Cell) -> Result<()> {
+        let mut builder = BuilderData::new();
+        builder.checked_append_reference(value.clone())?;
+        self.set_builder(key, &builder)
+    }
+    pub fn remove(&mut self, key: SliceData) -> Result<()> {
+        T::check_key_fail(self.bit_len, &key)?;
+        self.ops.insert(key, None);
+        Ok(())
+    }
+    /// Sets value of augmented dictionary calculating its augmentation
+    pub fn set_augmentable<K, X, Y>(&mut self, key: &K, value: &X) -> Result<()>
+    where
+        T: HashmapAugType<K, X, Y>,
+        K: Serializable + Deserializable,
+        X: Serializable + Deserializable + Augmentation<Y>,
+        Y: Augmentable,
+    {
+        let key = key.write_to_bitstring_with_opts(self.opts)?;
+        let mut leaf = value.aug()?.write_to_new_cell_with_opts(self.opts)?;
+        value.write_with_opts(&mut leaf, self.opts)?;
+        self.set_builder(key, &leaf)
+    }
+    pub fn del<K: Serializable>(&mut self, key: &K) -> Result<()> {
+        self.remove(key.write_to_bitstring_with_opts(self.opts)?)
+    }
+    /// Applies all the operations to the tree and updates its root augmentation
+    pub fn apply(self, tree: &mut T) -> Result<()> where T: HashmapRemover {
+        if tree.bit_len() != self.bit_len {
+            fail!(BlockError::InvalidArg(format!(
+                "batch is for {} bits keys, but dictionary has {}", self.bit_len, tree.bit_len()
+            )))
+        }
+        let ops = self.ops.into_iter().collect::<Vec<_>>();
+        let root = tree.data().map(|root| BatchNode::with_cell(root, self.bit_len)).transpose()?;
+        *tree.data_mut() = merge_node::<T>(root, 0, self.bit_len, &ops)?;
+        tree.after_remove()
+    }
+}
+
+fn common_len(a: &SliceData, b: &SliceData) -> usize {
+    match SliceData::common_prefix(a, b).0 {
+        Some(prefix) => prefix.remaining_bits(),
+        None => 0
+    }
+}
+
+// splits sorted operations by the bit of key at position
+fn split_ops(ops: &[BatchOp], position: usize) -> (&[BatchOp], &[BatchOp]) {
+    let index = ops.partition_point(|(key, _)| key.get_bit_opt(position) == Some(false));
+    ops.split_at(index)
+}
+
+// merges operations with keys starting from position `start` into the subtree with `bit_len` bits keys
+fn merge_node<T: HashmapType + ?Sized>(
+    node: Option<BatchNode>,
+    start: usize,
+    bit_len: usize,
+    ops: &[BatchOp],
+) -> Result<Option<Cell>> {
+    let node = match node {
+        None => return build_subtree::<T>(start, bit_len, ops),
+        Some(node) if ops.is_empty() => return Ok(Some(node.into_cell::<T>(bit_len)?)),
+        Some(node) => node
+    };
+    let label_len = node.label.remaining_bits();
+    let first = ops[0].0.get_slice(start, bit_len)?;
+    let last = ops[ops.len() - 1].0.get_slice(start, bit_len)?;
+    let common = common_len(&node.label, &first).min(common_len(&node.label, &last));
+    if common < label_len {
+        // some keys differ from the label - split node to a fork
+        let (zeros, ones) = split_ops(ops, start + common);
+        let bit = node.label.get_bit(common)?;
+        let (node_ops, other_ops) = if bit { (ones, zeros) } else { (zeros, ones) };
+        if node_ops.is_empty() && other_ops.iter().all(|(_, value)| value.is_none()) {
+            return Ok(Some(node.into_cell::<T>(bit_len)?))
+        }
+        let next_bit_len = bit_len - common - 1;
+        let existing = BatchNode {
+            label: node.label.get_slice(common + 1, label_len - common - 1)?,
+            remainder: node.remainder,
+            cell: None,
+        };
+        let existing = merge_node::<T>(Some(existing), start + common + 1, next_bit_len, node_ops)?;
+        let other = merge_node::<T>(None, start + common + 1, next_bit_len, other_ops)?;
+        let prefix = node.label.get_slice(0, common)?;
+        return match bit {
+            true => make_node::<T>(prefix, bit_len, other, existing),
+            false => make_node::<T>(prefix, bit_len, existing, other),
+        }
+    }
+    if label_len == bit_len {
+        // keys are unique so there is the only operation for this leaf
+        return match &ops[0].1 {
+            Some(value) => Ok(Some(T::make_leaf(&node.label, bit_len, value)?.into_cell()?)),
+            None => Ok(None)
+        }
+    }
+    let next_bit_len = bit_len - label_len - 1;
+    let left = node.remainder.reference(0)?;
+    let right = node.remainder.reference(1)?;
+    let (zeros, ones) = split_ops(ops, start + label_len);
+    let new_left = merge_node::<T>(Some(BatchNode::with_cell(&left, next_bit_len)?), start + label_len + 1, next_bit_len, zeros)?;
+    let new_right = merge_node::<T>(Some(BatchNode::with_cell(&right, next_bit_len)?), start + label_len + 1, next_bit_len, ones)?;
+    if let Some(cell) = node.cell {
+        if new_left.as_ref() == Some(&left) && new_right.as_ref() == Some(&right) {
+            return Ok(Some(cell))
+        }
+    }
+    make_node::<T>(node.label, bit_len, new_left, new_right)
+}
+
+// makes fork or edge to the only branch left
+fn make_node<T: HashmapType + ?Sized>(
+    label: SliceData,
+    bit_len: usize,
+    left: Option<Cell>,
+    right: Option<Cell>,
+) -> Result<Option<Cell>> {
+    let builder = match (left, right) {
+        (Some(left), Some(right)) => T::make_fork(&label, bit_len, left, right, false)?.0,
+        (Some(left), None) => T::make_edge(label, bit_len, true, SliceData::load_cell(left)?)?,
+        (None, Some(right)) => T::make_edge(label, bit_len, false, SliceData::load_cell(right)?)?,
+        (None, None) => return Ok(None)
+    };
+    Ok(Some(builder.into_cell()?))
+}
+
+// builds new subtree of items set by operations
+fn build_subtree<T: HashmapType + ?Sized>(start: usize, bit_len: usize, ops: &[BatchOp]) -> Result<Option<Cell>> {
+    let mut values = ops.iter().filter_map(|(key, value)| value.as_ref().map(|value| (key, value)));
+    if bit_len == 0 {
+        return match values.next() {
+            Some((_, value)) => Ok(Some(T::make_leaf(&SliceData::default(), 0, value)?.into_cell()?)),
+            None => Ok(None)
+        }
+    }
+    let mut builder = HashmapBulkBuilder::<T>::new(bit_len);
+    for (key, value) in values {
+        builder.push(key.get_slice(start, bit_len)?, value.clone())?;
+    }
+    builder.finish()
+}
+
+#[cfg(test)]
+#[path = "tests/test_batch.rs"]
+mod tests;
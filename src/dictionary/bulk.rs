@@ -0,0 +1,132 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::HashmapType;
+use crate::{
+    cell::{Cell, SliceData},
+    error::BlockError, fail, Result,
+};
+use std::marker::PhantomData;
+
+enum BulkNode {
+    Leaf(SliceData),
+    Fork(Cell, Cell),
+}
+
+// completed subtree which position in the tree is not known yet
+struct BulkSubtree {
+    // key of any leaf in the subtree - all of them have the same bits before fork
+    key: SliceData,
+    fork_at: usize,
+    node: BulkNode,
+}
+
+///
+/// Builds dictionary from items sorted by keys in ascending order of key bits
+/// (as unsigned integers) in one bottom-up pass. Every cell is created only once
+/// after all the items of its subtree are pushed, so augmentation of forks
+/// is calculated along the way for augmented dictionaries.
+///
+pub struct HashmapBulkBuilder<T: HashmapType + ?Sized> {
+    bit_len: usize,
+    // forks with completed left branch: key, fork position and left branch
+    forks: Vec<(SliceData, usize, Cell)>,
+    // the last pushed leaf
+    current: Option<BulkSubtree>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: HashmapType + ?Sized> HashmapBulkBuilder<T> {
+    pub fn new(bit_len: usize) -> Self {
+        Self {
+            bit_len,
+            forks: Vec::new(),
+            current: None,
+            phantom: PhantomData,
+        }
+    }
+    /// Adds leaf with the key greater than all the previous ones,
+    /// `value` is the leaf remainder (with augmentation for augmented dictionaries)
+    pub fn push(&mut self, key: SliceData, value: SliceData) -> Result<()> {
+        T::check_key_fail(self.bit_len, &key)?;
+        let depth = self.fork_depth(&key)?;
+        if let (Some(depth), Some(mut current)) = (depth, self.current.take()) {
+            while let Some((_, fork_at, _)) = self.forks.last() {
+                if *fork_at <= depth {
+                    break
+                }
+                current = self.close_fork(current)?;
+            }
+            let left = self.make_cell(&current, depth + 1)?;
+            self.forks.push((current.key, depth, left));
+        }
+        self.current = Some(BulkSubtree {
+            key,
+            fork_at: self.bit_len,
+            node: BulkNode::Leaf(value),
+        });
+        Ok(())
+    }
+    /// Completes all the forks and returns root cell of dictionary
+    pub fn finish(mut self) -> Result<Option<Cell>> {
+        match self.current.take() {
+            Some(mut current) => {
+                while !self.forks.is_empty() {
+                    current = self.close_fork(current)?;
+                }
+                Ok(Some(self.make_cell(&current, 0)?))
+            }
+            None => Ok(None)
+        }
+    }
+    // finds position where the key forks from the last one checking keys order
+    fn fork_depth(&self, key: &SliceData) -> Result<Option<usize>> {
+        let current = match &self.current {
+            Some(current) => current,
+            None => return Ok(None)
+        };
+        let depth = match SliceData::common_prefix(&current.key, key).0 {
+            Some(prefix) => prefix.remaining_bits(),
+            None => 0
+        };
+        if depth == self.bit_len || !key.get_bit(depth)? {
+            fail!(BlockError::InvalidArg(format!(
+                "keys must be sorted in ascending order without duplicates, but {} follows {}",
+                key, current.key
+            )))
+        }
+        Ok(Some(depth))
+    }
+    // sets subtree as right branch of the last fork
+    fn close_fork(&mut self, right: BulkSubtree) -> Result<BulkSubtree> {
+        let (key, fork_at, left) = self.forks.pop()
+            .ok_or_else(|| BlockError::InvalidOperation("no fork to close".to_string()))?;
+        let right = self.make_cell(&right, fork_at + 1)?;
+        Ok(BulkSubtree { key, fork_at, node: BulkNode::Fork(left, right) })
+    }
+    // makes cell of subtree starting from position `start` of the key
+    fn make_cell(&self, subtree: &BulkSubtree, start: usize) -> Result<Cell> {
+        let label = subtree.key.get_slice(start, subtree.fork_at - start)?;
+        let builder = match &subtree.node {
+            BulkNode::Leaf(value) => T::make_leaf(&label, self.bit_len - start, value)?,
+            BulkNode::Fork(left, right) => {
+                T::make_fork(&label, self.bit_len - start, left.clone(), right.clone(), false)?.0
+            }
+        };
+        builder.into_cell()
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_bulk.rs"]
+mod tests;
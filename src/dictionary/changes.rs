@@ -0,0 +1,227 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::HashmapType;
+use crate::{
+    cell::{BuilderData, Cell, IBitstring, SliceData},
+    error::BlockError, fail, Result,
+};
+use std::marker::PhantomData;
+
+/// Change of dictionary item between two versions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DictChange<K, V> {
+    Added(K, V),
+    Removed(K, V),
+    Changed(K, V, V),
+}
+
+impl<K, V> DictChange<K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            DictChange::Added(key, _) => key,
+            DictChange::Removed(key, _) => key,
+            DictChange::Changed(key, _, _) => key,
+        }
+    }
+    pub fn old_value(&self) -> Option<&V> {
+        match self {
+            DictChange::Added(_, _) => None,
+            DictChange::Removed(_, old) => Some(old),
+            DictChange::Changed(_, old, _) => Some(old),
+        }
+    }
+    pub fn new_value(&self) -> Option<&V> {
+        match self {
+            DictChange::Added(_, new) => Some(new),
+            DictChange::Removed(_, _) => None,
+            DictChange::Changed(_, _, new) => Some(new),
+        }
+    }
+}
+
+// rest of the node of one version: not consumed label bits and remainder
+struct DiffNode {
+    label: SliceData,
+    remainder: SliceData,
+    // original cell if label is not consumed
+    cell: Option<Cell>,
+}
+
+impl DiffNode {
+    fn with_cell(cell: Cell, bit_len: usize) -> Result<Self> {
+        let mut remainder = SliceData::load_cell_ref(&cell)?;
+        let label = remainder.get_label(bit_len)?;
+        Ok(Self { label, remainder, cell: Some(cell) })
+    }
+    fn skip_label(&mut self, bits: usize) -> Result<()> {
+        let label_len = self.label.remaining_bits();
+        self.label = self.label.get_slice(bits, label_len - bits)?;
+        self.cell = None;
+        Ok(())
+    }
+    fn child(&self, index: usize, bit_len: usize) -> Result<Self> {
+        Self::with_cell(self.remainder.reference(index)?, bit_len)
+    }
+}
+
+// subtrees of both versions starting from the same key prefix
+struct DiffPos {
+    key: BuilderData,
+    bit_len: usize,
+    old: Option<DiffNode>,
+    new: Option<DiffNode>,
+}
+
+///
+/// Iterator over differences between two versions of dictionary in ascending order of keys.
+/// Equal subtrees are skipped by comparing hashes of cells, so only changed paths are loaded.
+///
+pub struct HashmapDiff<T: HashmapType + ?Sized> {
+    pos: Vec<DiffPos>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: HashmapType + ?Sized> HashmapDiff<T> {
+    pub fn new(old: &T, new: &T) -> Result<Self> {
+        let bit_len = old.bit_len();
+        if bit_len != new.bit_len() {
+            fail!(BlockError::InvalidArg(format!(
+                "dictionaries have different key length {} and {}", bit_len, new.bit_len()
+            )))
+        }
+        let pos = DiffPos {
+            key: BuilderData::default(),
+            bit_len,
+            old: old.data().map(|root| DiffNode::with_cell(root.clone(), bit_len)).transpose()?,
+            new: new.data().map(|root| DiffNode::with_cell(root.clone(), bit_len)).transpose()?,
+        };
+        Ok(Self { pos: vec![pos], phantom: PhantomData })
+    }
+    pub fn next_item(&mut self) -> Result<Option<DictChange<BuilderData, SliceData>>> {
+        while let Some(pos) = self.pos.pop() {
+            if let Some(change) = self.step(pos)? {
+                return Ok(Some(change))
+            }
+        }
+        Ok(None)
+    }
+    // processes one position pushing next ones in reverse order
+    fn step(&mut self, pos: DiffPos) -> Result<Option<DictChange<BuilderData, SliceData>>> {
+        let DiffPos { mut key, bit_len, old, new } = pos;
+        let (mut old, mut new) = match (old, new) {
+            (Some(old), Some(new)) => (old, new),
+            (Some(old), None) => return self.step_single(key, bit_len, old, true),
+            (None, Some(new)) => return self.step_single(key, bit_len, new, false),
+            (None, None) => return Ok(None)
+        };
+        if let (Some(old_cell), Some(new_cell)) = (&old.cell, &new.cell) {
+            if old_cell == new_cell {
+                return Ok(None)
+            }
+        }
+        // skip common part of labels
+        let common = match SliceData::common_prefix(&old.label, &new.label).0 {
+            Some(prefix) => prefix.remaining_bits(),
+            None => 0
+        };
+        if common != 0 {
+            key.append_bytestring(&old.label.get_slice(0, common)?)?;
+            old.skip_label(common)?;
+            new.skip_label(common)?;
+        }
+        let bit_len = bit_len - common;
+        if bit_len == 0 {
+            // the same leaf in both versions
+            return match old.remainder == new.remainder {
+                true => Ok(None),
+                false => Ok(Some(DictChange::Changed(key, old.remainder, new.remainder)))
+            }
+        }
+        let old_bit = old.label.get_bit_opt(0);
+        let new_bit = new.label.get_bit_opt(0);
+        let mut branches = [(None, None), (None, None)];
+        match old_bit {
+            Some(bit) => {
+                old.skip_label(1)?;
+                branches[bit as usize].0 = Some(old);
+            }
+            None => {
+                branches[0].0 = Some(old.child(0, bit_len - 1)?);
+                branches[1].0 = Some(old.child(1, bit_len - 1)?);
+            }
+        }
+        match new_bit {
+            Some(bit) => {
+                new.skip_label(1)?;
+                branches[bit as usize].1 = Some(new);
+            }
+            None => {
+                branches[0].1 = Some(new.child(0, bit_len - 1)?);
+                branches[1].1 = Some(new.child(1, bit_len - 1)?);
+            }
+        }
+        self.push_branches(key, bit_len, branches)?;
+        Ok(None)
+    }
+    // processes subtree present only in one version
+    fn step_single(
+        &mut self,
+        mut key: BuilderData,
+        bit_len: usize,
+        node: DiffNode,
+        is_old: bool
+    ) -> Result<Option<DictChange<BuilderData, SliceData>>> {
+        let label_len = node.label.remaining_bits();
+        key.append_bytestring(&node.label)?;
+        let bit_len = bit_len - label_len;
+        if bit_len == 0 {
+            return match is_old {
+                true => Ok(Some(DictChange::Removed(key, node.remainder))),
+                false => Ok(Some(DictChange::Added(key, node.remainder))),
+            }
+        }
+        let children = [node.child(0, bit_len - 1)?, node.child(1, bit_len - 1)?];
+        let branches = children.map(|child| match is_old {
+            true => (Some(child), None),
+            false => (None, Some(child)),
+        });
+        self.push_branches(key, bit_len, branches)?;
+        Ok(None)
+    }
+    fn push_branches(
+        &mut self,
+        key: BuilderData,
+        bit_len: usize,
+        branches: [(Option<DiffNode>, Option<DiffNode>); 2]
+    ) -> Result<()> {
+        let [zero, one] = branches;
+        for (bit, (old, new)) in [(true, one), (false, zero)] {
+            let mut key = key.clone();
+            key.append_bit_bool(bit)?;
+            self.pos.push(DiffPos { key, bit_len: bit_len - 1, old, new });
+        }
+        Ok(())
+    }
+}
+
+impl<T: HashmapType + ?Sized> Iterator for HashmapDiff<T> {
+    type Item = Result<DictChange<BuilderData, SliceData>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().transpose()
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_changes.rs"]
+mod tests;
@@ -0,0 +1,241 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::{find_leaf, get_min_max, HashmapType, LabelReader};
+use crate::{
+    cell::{BuilderData, Cell, IBitstring, SliceData},
+    error::BlockError, fail, Result,
+};
+use std::{marker::PhantomData, ops::Bound};
+
+// Key bits padded to bytes and with inverted sign bit for signed keys,
+// so keys can be compared as byte strings in the order of the dictionary
+type OrderedKey = Vec<u8>;
+
+fn ordered_key(prefix: &BuilderData, bit_len: usize, fill: bool, signed: bool) -> OrderedKey {
+    let data = prefix.data();
+    let prefix_len = prefix.length_in_bits();
+    let mut key = vec![0; bit_len.div_ceil(8)];
+    let full_bytes = prefix_len / 8;
+    key[..full_bytes].copy_from_slice(&data[..full_bytes]);
+    for i in full_bytes * 8..bit_len {
+        let bit = match i < prefix_len {
+            true => (data[i / 8] >> (7 - i % 8)) & 1 == 1,
+            false => fill
+        };
+        if bit {
+            key[i / 8] |= 0x80 >> (i % 8);
+        }
+    }
+    if signed && bit_len > 0 {
+        key[0] ^= 0x80;
+    }
+    key
+}
+
+fn ordered_bound(bound: Bound<SliceData>, bit_len: usize, signed: bool) -> Result<Bound<OrderedKey>> {
+    let convert = |key: SliceData| {
+        if key.remaining_bits() != bit_len {
+            fail!(BlockError::InvalidArg(format!(
+                "key must contain {} bits, but {}", bit_len, key.remaining_bits()
+            )))
+        }
+        Ok(ordered_key(&key.as_builder(), bit_len, false, signed))
+    };
+    Ok(match bound {
+        Bound::Included(key) => Bound::Included(convert(key)?),
+        Bound::Excluded(key) => Bound::Excluded(convert(key)?),
+        Bound::Unbounded => Bound::Unbounded,
+    })
+}
+
+///
+/// Iterator over items of dictionary with keys in the range in direct or reverse order.
+/// Subtrees out of the range are not loaded.
+///
+pub struct HashmapRange<T: HashmapType + ?Sized> {
+    pos: Vec<(LabelReader, usize, BuilderData)>,
+    bit_len: usize,
+    from: Bound<OrderedKey>,
+    to: Bound<OrderedKey>,
+    signed: bool,
+    backward: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T: HashmapType + ?Sized> HashmapRange<T> {
+    pub fn new(
+        tree: &T,
+        from: Bound<SliceData>,
+        to: Bound<SliceData>,
+        signed: bool,
+        backward: bool
+    ) -> Result<Self> {
+        let bit_len = tree.bit_len();
+        let mut pos = vec![];
+        if let Some(root) = tree.data() {
+            pos.push((LabelReader::with_cell(root)?, bit_len, BuilderData::default()));
+        }
+        Ok(Self {
+            pos,
+            bit_len,
+            from: ordered_bound(from, bit_len, signed)?,
+            to: ordered_bound(to, bit_len, signed)?,
+            signed,
+            backward,
+            phantom: PhantomData,
+        })
+    }
+    // checks if any key with the prefix can be in the range
+    fn intersects(&self, prefix: &BuilderData) -> bool {
+        // sign bit is not fixed - the subtree has both minimal and maximal keys
+        if prefix.length_in_bits() == 0 {
+            return true
+        }
+        let above_from = match &self.from {
+            Bound::Included(from) => &ordered_key(prefix, self.bit_len, true, self.signed) >= from,
+            Bound::Excluded(from) => &ordered_key(prefix, self.bit_len, true, self.signed) > from,
+            Bound::Unbounded => true,
+        };
+        let below_to = match &self.to {
+            Bound::Included(to) => &ordered_key(prefix, self.bit_len, false, self.signed) <= to,
+            Bound::Excluded(to) => &ordered_key(prefix, self.bit_len, false, self.signed) < to,
+            Bound::Unbounded => true,
+        };
+        above_from && below_to
+    }
+    pub fn next_item(&mut self) -> Result<Option<(BuilderData, SliceData)>> {
+        while let Some((mut cursor, mut bit_len, key)) = self.pos.pop() {
+            let key = cursor.get_label_raw(&mut bit_len, key)?;
+            if !self.intersects(&key) {
+                continue
+            }
+            if bit_len == 0 {
+                return Ok(Some((key, cursor.remainder()?)))
+            }
+            let mut first = match self.signed && key.length_in_bits() == 0 {
+                true => 1,
+                false => 0,
+            };
+            if self.backward {
+                first = 1 - first;
+            }
+            // push second branch first to pop it after the first one
+            for index in [1 - first, first] {
+                let mut key = key.clone();
+                key.append_bit_bool(index == 1)?;
+                if self.intersects(&key) {
+                    let cursor = cursor.next_reader::<T>(index, &mut 0)?;
+                    self.pos.push((cursor, bit_len - 1, key));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<T: HashmapType + ?Sized> Iterator for HashmapRange<T> {
+    type Item = Result<(BuilderData, SliceData)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().transpose()
+    }
+}
+
+///
+/// Cursor over dictionary items which can be positioned to some key and moved
+/// in both directions. Every move searches the tree from the root,
+/// so only the path to the found item is loaded. Cursor which ran off either end
+/// or was not found by seek stays unpositioned until it is positioned again by seek.
+///
+pub struct HashmapCursor<T: HashmapType + ?Sized> {
+    root: Option<Cell>,
+    bit_len: usize,
+    signed: bool,
+    current: Option<(BuilderData, SliceData)>,
+    // moves don't restart from the ends after the cursor was positioned once
+    positioned: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T: HashmapType + ?Sized> HashmapCursor<T> {
+    pub fn new(tree: &T, signed: bool) -> Self {
+        Self {
+            root: tree.data().cloned(),
+            bit_len: tree.bit_len(),
+            signed,
+            current: None,
+            positioned: false,
+            phantom: PhantomData,
+        }
+    }
+    /// current item if cursor is positioned
+    pub fn current(&self) -> Option<&(BuilderData, SliceData)> {
+        self.current.as_ref()
+    }
+    /// positions to the item with minimal key
+    pub fn seek_first(&mut self) -> Result<Option<&(BuilderData, SliceData)>> {
+        self.seek_min_max(0)
+    }
+    /// positions to the item with maximal key
+    pub fn seek_last(&mut self) -> Result<Option<&(BuilderData, SliceData)>> {
+        self.seek_min_max(1)
+    }
+    /// positions to the key (if `eq`) or to the next/previous one
+    pub fn seek(&mut self, key: SliceData, next: bool, eq: bool) -> Result<Option<&(BuilderData, SliceData)>> {
+        if key.remaining_bits() != self.bit_len {
+            fail!(BlockError::InvalidArg(format!(
+                "key must contain {} bits, but {}", self.bit_len, key.remaining_bits()
+            )))
+        }
+        self.current = None;
+        self.positioned = true;
+        if let Some(root) = self.root.clone() {
+            let mut path = BuilderData::new();
+            let next_index = if next { 0 } else { 1 };
+            let found = find_leaf::<T>(root, &mut path, self.bit_len, key, next_index, eq, self.signed, &mut 0)?;
+            self.current = found.map(|value| (path, value));
+        }
+        Ok(self.current.as_ref())
+    }
+    /// moves to the next item, positions to the first one if cursor was never positioned
+    pub fn move_next(&mut self) -> Result<Option<&(BuilderData, SliceData)>> {
+        self.step(true)
+    }
+    /// moves to the previous item, positions to the last one if cursor was never positioned
+    pub fn move_prev(&mut self) -> Result<Option<&(BuilderData, SliceData)>> {
+        self.step(false)
+    }
+    fn step(&mut self, next: bool) -> Result<Option<&(BuilderData, SliceData)>> {
+        match self.current.take() {
+            Some((key, _)) => self.seek(SliceData::load_bitstring(key)?, next, false),
+            None if self.positioned => Ok(None),
+            None if next => self.seek_first(),
+            None => self.seek_last(),
+        }
+    }
+    fn seek_min_max(&mut self, next_index: usize) -> Result<Option<&(BuilderData, SliceData)>> {
+        self.current = None;
+        self.positioned = true;
+        if let Some(root) = self.root.clone() {
+            let mut path = BuilderData::new();
+            let index = if self.signed { 1 - next_index } else { next_index };
+            let found = get_min_max::<T>(root, &mut path, self.bit_len, next_index, index, &mut 0)?;
+            self.current = found.map(|value| (path, value));
+        }
+        Ok(self.current.as_ref())
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_cursor.rs"]
+mod tests;
@@ -0,0 +1,655 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{
+    error::BlockError,
+    Serializable, Deserializable, SERDE_OPTS_EMPTY,
+    error, fail, Result, IBitstring, BuilderData, Cell, SliceData, UInt256, AccountId,
+    HashmapE, HashmapType, HashmapRemover, HashmapSubtree, HashmapIterator, GasConsumer,
+    hashmap_subtree_with_prefix,
+    HashmapAugType, Augmentable, Augmentation, HashmapRange, HashmapCursor, HashmapBulkBuilder,
+    HashmapDiff, DictChange, hm_label,
+};
+use std::{fmt, marker::PhantomData, ops::Bound};
+
+/// Key of typed dictionary with fixed bit length
+pub trait DictKey: Serializable + Deserializable {
+    const BIT_LEN: usize;
+    /// keys are ordered as signed integers in ranges and cursors
+    const SIGNED: bool = false;
+
+    /// serializes key checking its length
+    fn write_key(&self, opts: u8) -> Result<SliceData> {
+        let key = self.write_to_bitstring_with_opts(opts)?;
+        if key.remaining_bits() != Self::BIT_LEN || key.remaining_references() != 0 {
+            fail!(BlockError::InvalidArg(format!(
+                "key {} must contain {} bits, but {}",
+                std::any::type_name::<Self>(), Self::BIT_LEN, key.remaining_bits()
+            )))
+        }
+        Ok(key)
+    }
+}
+
+impl DictKey for u32 {
+    const BIT_LEN: usize = 32;
+}
+
+impl DictKey for i32 {
+    const BIT_LEN: usize = 32;
+    const SIGNED: bool = true;
+}
+
+impl DictKey for u64 {
+    const BIT_LEN: usize = 64;
+}
+
+impl DictKey for UInt256 {
+    const BIT_LEN: usize = 256;
+}
+
+impl DictKey for AccountId {
+    const BIT_LEN: usize = 256;
+}
+
+///////////////////////////////////////////////
+/// Typed dictionary (HashmapE) with keys of type K and values of type V
+///
+pub struct Dict<K: DictKey, V: Serializable + Deserializable> {
+    map: HashmapE,
+    opts: u8,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> Dict<K, V> {
+    /// default constructor
+    pub const fn new() -> Self {
+        Self::with_hashmap(None, SERDE_OPTS_EMPTY)
+    }
+    pub const fn with_serde_opts(opts: u8) -> Self {
+        Self::with_hashmap(None, opts)
+    }
+    /// constructor with HashmapE root
+    pub const fn with_hashmap(data: Option<Cell>, opts: u8) -> Self {
+        Self {
+            map: HashmapE::with_hashmap(K::BIT_LEN, data),
+            opts,
+            phantom: PhantomData,
+        }
+    }
+    /// constructs from items sorted by keys in ascending order of key bits in one pass
+    pub fn from_sorted<I: IntoIterator<Item = (K, V)>>(items: I) -> Result<Self> {
+        let mut builder = HashmapBulkBuilder::<HashmapE>::new(K::BIT_LEN);
+        for (key, value) in items {
+            let value = value.write_to_new_cell()?;
+            builder.push(key.write_key(SERDE_OPTS_EMPTY)?, SliceData::load_builder(value)?)?;
+        }
+        Ok(Self::with_hashmap(builder.finish()?, SERDE_OPTS_EMPTY))
+    }
+    pub fn root(&self) -> Option<&Cell> {
+        self.map.data()
+    }
+    pub fn inner(self) -> HashmapE {
+        self.map
+    }
+    pub fn as_hashmap(&self) -> &HashmapE {
+        &self.map
+    }
+    pub fn serde_opts(&self) -> u8 {
+        self.opts
+    }
+    /// Return true if no items
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+    /// Calculates length
+    pub fn len(&self) -> Result<usize> {
+        self.map.len()
+    }
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        self.get_as_slice(key)?.map(
+            |ref mut slice| V::construct_from_with_opts(slice, self.opts)
+        ).transpose()
+    }
+    pub fn get_as_slice(&self, key: &K) -> Result<Option<SliceData>> {
+        self.map.get(key.write_key(self.opts)?)
+    }
+    pub fn contains_key(&self, key: &K) -> Result<bool> {
+        Ok(self.get_as_slice(key)?.is_some())
+    }
+    pub fn set(&mut self, key: &K, value: &V) -> Result<()> {
+        let key = key.write_key(self.opts)?;
+        let value = value.write_to_new_cell_with_opts(self.opts)?;
+        self.map.set_builder(key, &value)?;
+        Ok(())
+    }
+    pub fn setref(&mut self, key: &K, value: &Cell) -> Result<()> {
+        self.map.setref(key.write_key(self.opts)?, value)?;
+        Ok(())
+    }
+    /// removes item, returns true if it was present
+    pub fn remove(&mut self, key: &K) -> Result<bool> {
+        let leaf = self.map.remove(key.write_key(self.opts)?)?;
+        Ok(leaf.is_some())
+    }
+    /// iterates items with keys
+    pub fn iterate_with_keys<F>(&self, mut p: F) -> Result<bool>
+    where F: FnMut(K, V) -> Result<bool> {
+        self.map.iterate_slices(|ref mut key, ref mut slice| p(
+            K::construct_from_with_opts(key, self.opts)?,
+            V::construct_from_with_opts(slice, self.opts)?
+        ))
+    }
+    /// iterates keys
+    pub fn iterate_keys<F>(&self, mut p: F) -> Result<bool>
+    where F: FnMut(K) -> Result<bool> {
+        self.map.iterate_slices(|ref mut key, _| p(K::construct_from_with_opts(key, self.opts)?))
+    }
+    /// iterates items
+    pub fn iterate<F>(&self, mut p: F) -> Result<bool>
+    where F: FnMut(V) -> Result<bool> {
+        self.map.iterate_slices(|_, ref mut slice| p(V::construct_from_with_opts(slice, self.opts)?))
+    }
+    pub fn iter(&self) -> DictIterator<HashmapIterator<HashmapE>, K, V> {
+        DictIterator::new(self.map.iter(), self.opts, skip_nothing)
+    }
+    /// iterates items with keys in the range in direct or reverse order
+    pub fn range(
+        &self,
+        from: Bound<&K>,
+        to: Bound<&K>,
+        backward: bool
+    ) -> Result<DictIterator<HashmapRange<HashmapE>, K, V>> {
+        let from = key_bound(from, self.opts)?;
+        let to = key_bound(to, self.opts)?;
+        let range = self.map.hashmap_range(from, to, K::SIGNED, backward)?;
+        Ok(DictIterator::new(range, self.opts, skip_nothing))
+    }
+    /// makes cursor to move over items in both directions
+    pub fn cursor(&self) -> DictCursor<HashmapE, K, V> {
+        DictCursor::new(self.map.hashmap_cursor(K::SIGNED), self.opts, skip_nothing)
+    }
+    /// iterates typed differences from this version to the other one
+    pub fn diff(&self, other: &Self) -> Result<DictDiff<HashmapE, K, V>> {
+        Ok(DictDiff::new(self.map.hashmap_diff(&other.map)?, self.opts, skip_nothing))
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> Clone for Dict<K, V> {
+    fn clone(&self) -> Self {
+        Self::with_hashmap(self.root().cloned(), self.opts)
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> Default for Dict<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> PartialEq for Dict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map && self.opts == other.opts
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> Eq for Dict<K, V> {}
+
+impl<K: DictKey, V: Serializable + Deserializable> fmt::Debug for Dict<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Dict").field(&self.map).field(&self.opts).finish()
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> Serializable for Dict<K, V> {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        self.write_with_opts(cell, SERDE_OPTS_EMPTY)
+    }
+    fn write_with_opts(&self, cell: &mut BuilderData, opts: u8) -> Result<()> {
+        if opts != self.opts {
+            fail!(BlockError::MismatchedSerdeOptions(
+                std::any::type_name::<Self>().to_string(),
+                self.opts as usize,
+                opts as usize,
+            ));
+        }
+        self.map.write_with_opts(cell, opts)
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> Deserializable for Dict<K, V> {
+    fn read_from(&mut self, slice: &mut SliceData) -> Result<()> {
+        self.map.read_from(slice)
+    }
+    fn read_from_with_opts(&mut self, slice: &mut SliceData, opts: u8) -> Result<()> {
+        self.opts = opts;
+        self.map.read_from_with_opts(slice, opts)
+    }
+}
+
+impl<K: DictKey, V: Serializable + Deserializable> IntoIterator for &Dict<K, V> {
+    type Item = Result<(K, V)>;
+    type IntoIter = DictIterator<HashmapIterator<HashmapE>, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+///////////////////////////////////////////////
+/// Typed augmented dictionary (HashmapAugE) with keys of type K,
+/// values of type V and augmentation of type A
+///
+pub struct AugDict<K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable> {
+    extra: A,
+    data: Option<Cell>,
+    opts: u8,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V, A> AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    /// Constructs new empty dictionary
+    pub fn new() -> Self {
+        Self::with_serde_opts(SERDE_OPTS_EMPTY)
+    }
+    pub fn with_serde_opts(opts: u8) -> Self {
+        Self {
+            extra: A::default(),
+            data: None,
+            opts,
+            phantom: PhantomData,
+        }
+    }
+    /// Constructs from cell, extracts total aug
+    pub fn with_hashmap(data: Option<Cell>, opts: u8) -> Result<Self> {
+        let extra = match data {
+            Some(ref root) => Self::find_extra(&mut SliceData::load_cell_ref(root)?, K::BIT_LEN)?,
+            None => A::default()
+        };
+        Ok(Self { extra, data, opts, phantom: PhantomData })
+    }
+    pub fn root(&self) -> Option<&Cell> {
+        self.data.as_ref()
+    }
+    /// removes item, returns true if it was present
+    pub fn remove(&mut self, key: &K) -> Result<bool> {
+        let leaf = self.hashmap_remove(key.write_key(self.opts)?, &mut 0)?;
+        Ok(leaf.is_some())
+    }
+    pub fn contains_key(&self, key: &K) -> Result<bool> {
+        Ok(self.get_raw(key)?.is_some())
+    }
+    pub fn iter(&self) -> DictIterator<HashmapIterator<Self>, K, V> {
+        DictIterator::new(HashmapType::iter(self), self.opts, skip_aug::<A>)
+    }
+    /// iterates items with keys in the range in direct or reverse order
+    pub fn range(
+        &self,
+        from: Bound<&K>,
+        to: Bound<&K>,
+        backward: bool
+    ) -> Result<DictIterator<HashmapRange<Self>, K, V>> {
+        HashmapAugType::range(self, from, to, K::SIGNED, backward)
+    }
+    /// makes cursor to move over items in both directions
+    pub fn cursor(&self) -> DictCursor<Self, K, V> {
+        HashmapAugType::cursor(self, K::SIGNED)
+    }
+}
+
+impl<K, V, A> Clone for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn clone(&self) -> Self {
+        Self {
+            extra: self.extra.clone(),
+            data: self.data.clone(),
+            opts: self.opts,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, V, A> Default for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, A> PartialEq for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable + PartialEq {
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.extra == other.extra && self.opts == other.opts
+    }
+}
+
+impl<K, V, A> Eq for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable + Eq {}
+
+impl<K, V, A> fmt::Debug for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable + fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AugDict")
+            .field("extra", &self.extra)
+            .field("data", &self.data)
+            .field("opts", &self.opts)
+            .finish()
+    }
+}
+
+// the same as in define_HashmapAugE
+impl<K, V, A> HashmapType for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn check_key(bit_len: usize, key: &SliceData) -> bool {
+        bit_len == key.remaining_bits()
+    }
+    fn make_cell_with_label_and_data(
+        key: SliceData,
+        max: usize,
+        _is_leaf: bool,
+        data: &SliceData
+    ) -> Result<BuilderData> {
+        let mut builder = hm_label(&key, max)?;
+        builder.checked_append_references_and_data(data)?;
+        Ok(builder)
+    }
+    fn make_cell_with_label_and_builder(
+        key: SliceData,
+        max: usize,
+        _is_leaf: bool,
+        data: &BuilderData
+    ) -> Result<BuilderData> {
+        let mut builder = hm_label(&key, max)?;
+        builder.append_builder(data)?;
+        Ok(builder)
+    }
+    fn make_fork(
+        key: &SliceData,
+        bit_len: usize,
+        mut left: Cell,
+        mut right: Cell,
+        swap: bool
+    ) -> Result<(BuilderData, SliceData)> {
+        let next_bit_len = bit_len
+            .checked_sub(key.remaining_bits() + 1)
+            .ok_or_else(|| error!("fail too short label"))?;
+        let mut builder = Self::make_cell_with_label(key.clone(), bit_len)?;
+        let aug = Self::calc_extra(&left, &right, next_bit_len)?;
+        let mut remainder = BuilderData::new();
+        if swap {
+            std::mem::swap(&mut left, &mut right);
+        }
+        remainder.checked_append_reference(left)?;
+        remainder.checked_append_reference(right)?;
+        aug.write_to(&mut remainder)?;
+        builder.append_builder(&remainder)?;
+        Ok((builder, SliceData::load_builder(remainder)?))
+    }
+    fn is_fork(slice: &mut SliceData) -> Result<bool> {
+        Ok(slice.remaining_references() > 1)
+    }
+    fn is_leaf(_slice: &mut SliceData) -> bool {
+        true
+    }
+    fn data(&self) -> Option<&Cell> {
+        self.data.as_ref()
+    }
+    fn data_mut(&mut self) -> &mut Option<Cell> {
+        &mut self.data
+    }
+    fn bit_len(&self) -> usize {
+        K::BIT_LEN
+    }
+}
+
+impl<K, V, A> HashmapAugType<K, V, A> for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn root_extra(&self) -> &A {
+        &self.extra
+    }
+    fn set_root_extra(&mut self, aug: A) {
+        self.extra = aug;
+    }
+    fn serde_opts(&self) -> u8 {
+        self.opts
+    }
+}
+
+impl<K, V, A> HashmapRemover for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn after_remove(&mut self) -> Result<()> {
+        let aug = match &self.data {
+            Some(root) => Self::find_extra(&mut SliceData::load_cell_ref(root)?, K::BIT_LEN)?,
+            None => A::default()
+        };
+        self.set_root_extra(aug);
+        Ok(())
+    }
+}
+
+impl<K, V, A> HashmapSubtree for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn subtree_with_prefix(&self, prefix: &SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Self> {
+        let mut subtree = hashmap_subtree_with_prefix(self, prefix, gas_consumer)?;
+        subtree.after_remove()?;
+        Ok(subtree)
+    }
+}
+
+impl<K, V, A> Serializable for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        self.write_with_opts(cell, SERDE_OPTS_EMPTY)
+    }
+    fn write_with_opts(&self, cell: &mut BuilderData, opts: u8) -> Result<()> {
+        if self.opts != opts {
+            fail!(BlockError::MismatchedSerdeOptions(
+                std::any::type_name::<Self>().to_string(),
+                self.opts as usize,
+                opts as usize
+            ));
+        }
+        if let Some(root) = &self.data {
+            cell.append_bit_one()?;
+            cell.checked_append_reference(root.clone())?;
+        } else {
+            cell.append_bit_zero()?;
+        }
+        self.extra.write_with_opts(cell, opts)?;
+        Ok(())
+    }
+}
+
+impl<K, V, A> Deserializable for AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    fn construct_from(slice: &mut SliceData) -> Result<Self> {
+        Self::construct_from_with_opts(slice, SERDE_OPTS_EMPTY)
+    }
+    fn construct_from_with_opts(slice: &mut SliceData, opts: u8) -> Result<Self> {
+        let data = match slice.get_next_bit()? {
+            true => Some(slice.checked_drain_reference()?),
+            false => None
+        };
+        let extra = A::construct_from_with_opts(slice, opts)?;
+        if data.is_none() && extra.write_to_new_cell()? != A::default().write_to_new_cell()? {
+            fail!("root extra for empty {} is not default", std::any::type_name::<Self>())
+        }
+        Ok(Self { extra, data, opts, phantom: PhantomData })
+    }
+    fn read_from_with_opts(&mut self, slice: &mut SliceData, opts: u8) -> Result<()> {
+        *self = Self::construct_from_with_opts(slice, opts)?;
+        Ok(())
+    }
+}
+
+impl<K, V, A> IntoIterator for &AugDict<K, V, A>
+where K: DictKey, V: Serializable + Deserializable + Augmentation<A>, A: Augmentable {
+    type Item = Result<(K, V)>;
+    type IntoIter = DictIterator<HashmapIterator<AugDict<K, V, A>>, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub(crate) fn key_bound<K: Serializable>(bound: Bound<&K>, opts: u8) -> Result<Bound<SliceData>> {
+    Ok(match bound {
+        Bound::Included(key) => Bound::Included(key.write_to_bitstring_with_opts(opts)?),
+        Bound::Excluded(key) => Bound::Excluded(key.write_to_bitstring_with_opts(opts)?),
+        Bound::Unbounded => Bound::Unbounded,
+    })
+}
+
+pub(crate) fn skip_nothing(_slice: &mut SliceData, _opts: u8) -> Result<()> {
+    Ok(())
+}
+
+// values of augmented dictionaries are preceded by augmentation
+pub(crate) fn skip_aug<A: Deserializable>(slice: &mut SliceData, opts: u8) -> Result<()> {
+    A::construct_from_with_opts(slice, opts)?;
+    Ok(())
+}
+
+fn decode_item<K: Deserializable, V: Deserializable>(
+    key: BuilderData,
+    value: SliceData,
+    opts: u8,
+    skip_aug: fn(&mut SliceData, u8) -> Result<()>
+) -> Result<(K, V)> {
+    let key = K::construct_from_with_opts(&mut SliceData::load_bitstring(key)?, opts)?;
+    Ok((key, decode_value(value, opts, skip_aug)?))
+}
+
+fn decode_value<V: Deserializable>(
+    mut value: SliceData,
+    opts: u8,
+    skip_aug: fn(&mut SliceData, u8) -> Result<()>
+) -> Result<V> {
+    skip_aug(&mut value, opts)?;
+    V::construct_from_with_opts(&mut value, opts)
+}
+
+/// Iterator over typed items of dictionary
+pub struct DictIterator<I, K: Deserializable, V: Deserializable> {
+    inner: I,
+    opts: u8,
+    skip_aug: fn(&mut SliceData, u8) -> Result<()>,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<I, K: Deserializable, V: Deserializable> DictIterator<I, K, V> {
+    pub(crate) fn new(inner: I, opts: u8, skip_aug: fn(&mut SliceData, u8) -> Result<()>) -> Self {
+        Self { inner, opts, skip_aug, phantom: PhantomData }
+    }
+}
+
+impl<I, K, V> Iterator for DictIterator<I, K, V>
+where I: Iterator<Item = Result<(BuilderData, SliceData)>>, K: Deserializable, V: Deserializable {
+    type Item = Result<(K, V)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.inner.next()?;
+        Some(item.and_then(|(key, value)| decode_item(key, value, self.opts, self.skip_aug)))
+    }
+}
+
+/// Cursor over typed items of dictionary, see `HashmapCursor`
+pub struct DictCursor<T: HashmapType, K: Serializable + Deserializable, V: Deserializable> {
+    inner: HashmapCursor<T>,
+    opts: u8,
+    skip_aug: fn(&mut SliceData, u8) -> Result<()>,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<T, K, V> DictCursor<T, K, V>
+where T: HashmapType, K: Serializable + Deserializable, V: Deserializable {
+    pub(crate) fn new(
+        inner: HashmapCursor<T>,
+        opts: u8,
+        skip_aug: fn(&mut SliceData, u8) -> Result<()>
+    ) -> Self {
+        Self { inner, opts, skip_aug, phantom: PhantomData }
+    }
+    fn decode(&self) -> Result<Option<(K, V)>> {
+        match self.inner.current() {
+            Some((key, value)) => Ok(Some(decode_item(key.clone(), value.clone(), self.opts, self.skip_aug)?)),
+            None => Ok(None)
+        }
+    }
+    /// current item if cursor is positioned
+    pub fn current(&self) -> Result<Option<(K, V)>> {
+        self.decode()
+    }
+    /// positions to the item with minimal key
+    pub fn seek_first(&mut self) -> Result<Option<(K, V)>> {
+        self.inner.seek_first()?;
+        self.decode()
+    }
+    /// positions to the item with maximal key
+    pub fn seek_last(&mut self) -> Result<Option<(K, V)>> {
+        self.inner.seek_last()?;
+        self.decode()
+    }
+    /// positions to the key (if `eq`) or to the next/previous one
+    pub fn seek(&mut self, key: &K, next: bool, eq: bool) -> Result<Option<(K, V)>> {
+        self.inner.seek(key.write_to_bitstring_with_opts(self.opts)?, next, eq)?;
+        self.decode()
+    }
+    /// moves to the next item, positions to the first one if cursor was never positioned
+    pub fn move_next(&mut self) -> Result<Option<(K, V)>> {
+        self.inner.move_next()?;
+        self.decode()
+    }
+    /// moves to the previous item, positions to the last one if cursor was never positioned
+    pub fn move_prev(&mut self) -> Result<Option<(K, V)>> {
+        self.inner.move_prev()?;
+        self.decode()
+    }
+}
+
+/// Iterator over typed differences between two versions of dictionary, see `HashmapDiff`
+pub struct DictDiff<T: HashmapType, K: Deserializable, V: Deserializable> {
+    inner: HashmapDiff<T>,
+    opts: u8,
+    skip_aug: fn(&mut SliceData, u8) -> Result<()>,
+    phantom: PhantomData<fn() -> (K, V)>,
+}
+
+impl<T: HashmapType, K: Deserializable, V: Deserializable> DictDiff<T, K, V> {
+    pub(crate) fn new(inner: HashmapDiff<T>, opts: u8, skip_aug: fn(&mut SliceData, u8) -> Result<()>) -> Self {
+        Self { inner, opts, skip_aug, phantom: PhantomData }
+    }
+    fn decode(&self, change: DictChange<BuilderData, SliceData>) -> Result<DictChange<K, V>> {
+        let (opts, skip_aug) = (self.opts, self.skip_aug);
+        let key = |key| K::construct_from_with_opts(&mut SliceData::load_bitstring(key)?, opts);
+        Ok(match change {
+            DictChange::Added(k, new) => DictChange::Added(key(k)?, decode_value(new, opts, skip_aug)?),
+            DictChange::Removed(k, old) => DictChange::Removed(key(k)?, decode_value(old, opts, skip_aug)?),
+            DictChange::Changed(k, old, new) => DictChange::Changed(
+                key(k)?, decode_value(old, opts, skip_aug)?, decode_value(new, opts, skip_aug)?
+            ),
+        })
+    }
+}
+
+impl<T: HashmapType, K: Deserializable, V: Deserializable> Iterator for DictDiff<T, K, V> {
+    type Item = Result<DictChange<K, V>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let change = self.inner.next()?;
+        Some(change.and_then(|change| self.decode(change)))
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_dict.rs"]
+mod tests;
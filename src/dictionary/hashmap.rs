@@ -47,6 +47,15 @@ impl HashmapE {
     pub const fn with_hashmap(bit_len: usize, data: Option<Cell>) -> Self {
         Self { bit_len, data }
     }
+    /// constructs from items sorted by keys in ascending order in one pass
+    pub fn from_sorted<I>(bit_len: usize, items: I) -> Result<Self>
+    where I: IntoIterator<Item = (SliceData, SliceData)> {
+        let mut builder = HashmapBulkBuilder::<Self>::new(bit_len);
+        for (key, value) in items {
+            builder.push(key, value)?;
+        }
+        Ok(Self::with_hashmap(bit_len, builder.finish()?))
+    }
     /// serialize not empty root in current cell
     pub fn write_hashmap_root(&self, cell: &mut BuilderData) -> Result<()> {
         match self.data() {
@@ -494,6 +503,8 @@ macro_rules! define_HashmapE {
                 self.0.read_from_with_opts(slice, opts)
             }
         }
+
+        $crate::define_dictionary_json!(HashmapE, $varname, $bit_len, $x_type);
     }
 }
 
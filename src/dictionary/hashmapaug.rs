@@ -16,8 +16,10 @@ use crate::{
     Serializable, Deserializable,
     fail, Result, IBitstring, BuilderData, Cell, SliceData, LabelReader,
     ExceptionCode, HashmapType, Leaf,
+    DictCursor, DictDiff, DictIterator, HashmapRange, HashmapBulkBuilder,
+    dictionary::dict::{key_bound, skip_aug},
 };
-use std::cmp::Ordering;
+use std::{cmp::Ordering, ops::Bound};
 
 /// trait for types used as Augment to calc aug on forks
 pub trait Augmentable: Clone + Default + Serializable + Deserializable {
@@ -318,6 +320,8 @@ macro_rules! define_HashmapAugE {
                 }
             }
         }
+
+        $crate::define_dictionary_json!(HashmapAugE, $varname, $k_type, $x_type, $y_type);
     }
 }
 
@@ -450,6 +454,23 @@ pub trait HashmapAugType<
         Ok(())
     }
 
+    /// constructs from items sorted by keys in ascending order of key bits in one pass,
+    /// augmentation of forks is calculated while building
+    fn from_sorted<I: IntoIterator<Item = (K, X)>>(items: I) -> Result<Self> where Self: Default {
+        let mut map = Self::default();
+        let opts = map.serde_opts();
+        let mut builder = HashmapBulkBuilder::<Self>::new(map.bit_len());
+        for (key, value) in items {
+            let key = key.write_to_bitstring_with_opts(opts)?;
+            let mut leaf = value.aug()?.write_to_new_cell_with_opts(opts)?;
+            value.write_with_opts(&mut leaf, opts)?;
+            builder.push(key, SliceData::load_builder(leaf)?)?;
+        }
+        *map.data_mut() = builder.finish()?;
+        map.update_root_extra()?;
+        Ok(map)
+    }
+
     fn find_key(&self, min: bool, signed: bool) -> Result<Option<(SliceData, SliceData)>> {
         match self.data() {
             Some(root) => {
@@ -507,6 +528,28 @@ pub trait HashmapAugType<
             None => Ok(None)
         }
     }
+    /// iterates items with keys in the range in direct or reverse order
+    fn range(
+        &self,
+        from: Bound<&K>,
+        to: Bound<&K>,
+        signed: bool,
+        backward: bool
+    ) -> Result<DictIterator<HashmapRange<Self>, K, X>> where Self: Sized {
+        let from = key_bound(from, self.serde_opts())?;
+        let to = key_bound(to, self.serde_opts())?;
+        let range = self.hashmap_range(from, to, signed, backward)?;
+        Ok(DictIterator::new(range, self.serde_opts(), skip_aug::<Y>))
+    }
+    /// makes cursor to move over items in both directions
+    fn cursor(&self, signed: bool) -> DictCursor<Self, K, X> where Self: Sized {
+        DictCursor::new(self.hashmap_cursor(signed), self.serde_opts(), skip_aug::<Y>)
+    }
+    /// iterates typed differences from this version to the other one,
+    /// items with changed augmentation only are reported as changed too
+    fn diff(&self, other: &Self) -> Result<DictDiff<Self, K, X>> where Self: Sized {
+        Ok(DictDiff::new(self.hashmap_diff(other)?, self.serde_opts(), skip_aug::<Y>))
+    }
 
     // /// Checks if HashmapAugE is empty
     // fn is_empty(&self) -> bool {
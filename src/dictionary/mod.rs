@@ -11,7 +11,7 @@
 * limitations under the License.
 */
 
-use std::marker::PhantomData;
+use std::{marker::PhantomData, ops::Bound};
 
 use crate::{error, fail};
 use crate::cell::{BuilderData, Cell, IBitstring, SliceData};
@@ -22,11 +22,95 @@ use crate::types::{ExceptionCode, Result};
 mod hashmap;
 pub use self::hashmap::HashmapE;
 mod pfxhashmap;
-pub use self::pfxhashmap::PfxHashmapE;
+pub use self::pfxhashmap::{PfxHashmapE, PfxHashmapIterator};
 
 pub(crate) mod hashmapaug;
 pub use self::hashmapaug::*;
 
+mod dict;
+pub use self::dict::*;
+
+mod cursor;
+pub use self::cursor::*;
+
+mod bulk;
+pub use self::bulk::*;
+
+mod batch;
+pub use self::batch::*;
+
+mod changes;
+pub use self::changes::*;
+
+/// Implements JSON representation for dictionaries defined with macros
+#[cfg(feature = "json")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_dictionary_json {
+    ( HashmapE, $varname:ident, $bit_len:expr, $x_type:ty ) => {
+        $crate::define_dictionary_json!(
+            @impl $varname,
+            (self, serializer) => $crate::json::serialize_dictionary(
+                &self.0,
+                self.1,
+                |slice| <$x_type>::construct_from_with_opts(slice, self.1),
+                serializer
+            ),
+            (deserializer) => {
+                let root = $crate::json::deserialize_dictionary::<$x_type, D>($bit_len, deserializer)?;
+                Ok($varname::with_hashmap(root, $crate::SERDE_OPTS_EMPTY))
+            }
+        );
+    };
+    ( HashmapAugE, $varname:ident, $k_type:ty, $x_type:ty, $y_type:ty ) => {
+        $crate::define_dictionary_json!(
+            @impl $varname,
+            (self, serializer) => $crate::json::serialize_dictionary(
+                self,
+                self.opts,
+                |slice| {
+                    let (value, aug) = <Self as $crate::HashmapAugType<$k_type, $x_type, $y_type>>
+                        ::value_aug(self.opts, slice)?;
+                    Ok($crate::json::AugItem { value, aug })
+                },
+                serializer
+            ),
+            (deserializer) => $crate::json::deserialize_aug_dictionary::<
+                Self, $k_type, $x_type, $y_type, D
+            >(deserializer)
+        );
+    };
+    (
+        @impl $varname:ident,
+        ($self:ident, $serializer:ident) => $serialize:expr,
+        ($deserializer:ident) => $deserialize:expr
+    ) => {
+        impl $crate::json::serde::Serialize for $varname {
+            fn serialize<S: $crate::json::serde::Serializer>(
+                &$self,
+                $serializer: S
+            ) -> std::result::Result<S::Ok, S::Error> {
+                $serialize
+            }
+        }
+
+        impl<'de> $crate::json::serde::Deserialize<'de> for $varname {
+            fn deserialize<D: $crate::json::serde::Deserializer<'de>>(
+                $deserializer: D
+            ) -> std::result::Result<Self, D::Error> {
+                $deserialize
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "json"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! define_dictionary_json {
+    ( $kind:ident, $varname:ident, $( $types:tt )* ) => {};
+}
+
 pub type Leaf = Result<Option<SliceData>>;
 
 pub const ADD: u8 = 0x01;
@@ -469,6 +553,24 @@ pub trait HashmapType {
     fn iter(&self) -> HashmapIterator<Self> {
         HashmapIterator::from_hashmap(self)
     }
+    /// iterates items with keys in the range in direct or reverse order
+    fn hashmap_range(
+        &self,
+        from: Bound<SliceData>,
+        to: Bound<SliceData>,
+        signed: bool,
+        backward: bool
+    ) -> Result<HashmapRange<Self>> {
+        HashmapRange::new(self, from, to, signed, backward)
+    }
+    /// makes cursor to move over items in both directions
+    fn hashmap_cursor(&self, signed: bool) -> HashmapCursor<Self> {
+        HashmapCursor::new(self, signed)
+    }
+    /// iterates differences from this version to the other one in ascending order of keys
+    fn hashmap_diff(&self, other: &Self) -> Result<HashmapDiff<Self>> {
+        HashmapDiff::new(self, other)
+    }
     fn count_cells(&self, max: usize) -> Result<usize> {
         match self.data() {
             Some(root) => root.count_cells(max),
@@ -491,6 +593,10 @@ pub trait HashmapType {
             cursor = gas_consumer.load_cell(cursor.reference(next_index)?)?;
             bit_len = bit_len.checked_sub(label.remaining_bits() + 1).ok_or(ExceptionCode::CellUnderflow)?;
             label = LabelReader::read_label(&mut cursor, bit_len)?;
+            // shorter key of PfxHashmap can end on fork
+            if key.is_empty() && !label.is_empty() {
+                return Ok(None)
+            }
         }
         if key.is_empty() && Self::is_leaf(&mut cursor) {
             Ok(Some(cursor))
@@ -1531,24 +1637,34 @@ where
     Ok((split_result, left, right))
 }
 
+/// Makes subtree by common prefix keeping root augmentation of the original map,
+/// augmented maps have to recalculate it in own implementation of `subtree_with_prefix`
+pub fn hashmap_subtree_with_prefix<T: HashmapSubtree>(
+    map: &T,
+    prefix: &SliceData,
+    gas_consumer: &mut dyn GasConsumer
+) -> Result<T> {
+    let mut new_map = map.clone();
+    if prefix.is_empty() {
+        return Ok(new_map);
+    }
+    if let Some((key, mut remainder, None)) = map.subtree_root(prefix, gas_consumer)? {
+        let label = SliceData::load_bitstring(key)?;
+        let is_leaf = T::is_leaf(&mut remainder);
+        if remainder.cell_opt() != map.data() {
+            let root = T::make_cell_with_label_and_data(label, map.bit_len(), is_leaf, &remainder)?;
+            *new_map.data_mut() = Some(gas_consumer.finalize_cell(root)?);
+        }
+    } else {
+        *new_map.data_mut() = None;
+    }
+    Ok(new_map)
+}
+
 pub trait HashmapSubtree: HashmapType + Clone + Sized {
     /// make subtree by common prefix
     fn subtree_with_prefix(&self, prefix: &SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Self> {
-        let mut new_map = self.clone();
-        if prefix.is_empty() {
-            return Ok(new_map);
-        }
-        if let Some((key, mut remainder, None)) = self.subtree_root(prefix, gas_consumer)? {
-            let label = SliceData::load_bitstring(key)?;
-            let is_leaf = Self::is_leaf(&mut remainder);
-            if remainder.cell_opt() != self.data() {
-                let root = Self::make_cell_with_label_and_data(label, self.bit_len(), is_leaf, &remainder)?;
-                *new_map.data_mut() = Some(gas_consumer.finalize_cell(root)?);
-            }
-        } else {
-            *new_map.data_mut() = None;
-        }
-        Ok(new_map)
+        hashmap_subtree_with_prefix(self, prefix, gas_consumer)
     }
 
     /// transform to subtree with the common prefix
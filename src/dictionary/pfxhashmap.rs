@@ -13,10 +13,10 @@
 
 use std::fmt;
 
-use crate::{GasConsumer, LabelReader};
-use crate::types::Result;
+use crate::{error::BlockError, fail, Deserializable, GasConsumer, LabelReader};
+use crate::types::{ExceptionCode, Result};
 
-use super::{ADD, HashmapRemover, HashmapType, hm_label, Leaf, REPLACE};
+use super::{ADD, HashmapRemover, HashmapType, hm_label, Leaf, remove_node, REPLACE};
 use super::{BuilderData, Cell, IBitstring, SliceData};
 
 #[derive(Clone, Debug)]
@@ -97,7 +97,7 @@ impl PfxHashmapE {
         let mut label = LabelReader::read_label(&mut cursor, bit_len)?;
         loop {
             match SliceData::common_prefix(&label, &key) {
-                (_, None, None) => (), // label == key
+                (_, None, None) => return Ok(!Self::is_leaf(&mut cursor)), // label == key
                 (_, None, Some(remainder)) => key = remainder, // usual case
                 (_, _, None) => return Ok(true), // key is prefix
                 (_, Some(_), Some(_)) => return Ok(false)
@@ -204,6 +204,102 @@ impl PfxHashmapE {
             path.checked_append_references_and_data(&label)?;
         }
     }
+    /// finds item which key is prefix of key and returns its key and value
+    /// keys are prefix-free so there is at most one such item
+    pub fn get_longest_prefix(&self, key: SliceData) -> Result<Option<(SliceData, SliceData)>> {
+        self.get_longest_prefix_with_gas(key, &mut 0)
+    }
+    pub fn get_longest_prefix_with_gas(&self, key: SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Option<(SliceData, SliceData)>> {
+        match self.get_prefix_leaf_with_gas(key, gas_consumer)? {
+            (path, Some(value), _) => Ok(Some((path, value))),
+            _ => Ok(None)
+        }
+    }
+    /// returns item with minimal key
+    pub fn get_min(&self, gas_consumer: &mut dyn GasConsumer) -> Result<Option<(BuilderData, SliceData)>> {
+        self.get_min_max(true, gas_consumer)
+    }
+    /// returns item with maximal key
+    pub fn get_max(&self, gas_consumer: &mut dyn GasConsumer) -> Result<Option<(BuilderData, SliceData)>> {
+        self.get_min_max(false, gas_consumer)
+    }
+    pub fn get_min_max(&self, min: bool, gas_consumer: &mut dyn GasConsumer) -> Result<Option<(BuilderData, SliceData)>> {
+        let mut bit_len = self.bit_len;
+        let mut data = match self.data() {
+            Some(root) => root.clone(),
+            None => return Ok(None)
+        };
+        let mut path = BuilderData::default();
+        loop {
+            let mut cursor = gas_consumer.load_cell(data)?;
+            path = LabelReader::read_label_raw(&mut cursor, &mut bit_len, path)?;
+            if Self::is_leaf(&mut cursor) {
+                return Ok(Some((path, cursor)))
+            }
+            bit_len = bit_len.checked_sub(1).ok_or(ExceptionCode::DictionaryError)?;
+            path.append_bit_bool(!min)?;
+            data = cursor.reference(if min { 0 } else { 1 })?;
+        }
+    }
+    /// returns dictionary with items which keys start with prefix
+    pub fn subtree_with_prefix(&self, prefix: &SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Self> {
+        if prefix.remaining_bits() > self.bit_len {
+            fail!(BlockError::InvalidArg(format!(
+                "prefix too long {} more than {}", prefix.remaining_bits(), self.bit_len
+            )))
+        }
+        let mut cursor = match self.data().cloned() {
+            Some(root) if !prefix.is_empty() => gas_consumer.load_cell(root)?,
+            _ => return Ok(self.clone())
+        };
+        let mut bit_len = self.bit_len;
+        let mut key = prefix.clone();
+        let mut path = BuilderData::default();
+        let mut is_root = true;
+        loop {
+            let label = LabelReader::read_label(&mut cursor, bit_len)?;
+            path.append_bytestring(&label)?;
+            match SliceData::common_prefix(&label, &key) {
+                (_, _, None) => break, // prefix is found
+                (_, None, Some(remainder)) => key = remainder,
+                (_, Some(_), Some(_)) => return Ok(Self::with_bit_len(self.bit_len))
+            }
+            // leaf key is shorter than prefix
+            if Self::is_leaf(&mut cursor) {
+                return Ok(Self::with_bit_len(self.bit_len))
+            }
+            let next_index = key.get_next_bit_int()?;
+            path.append_bit_bool(next_index == 1)?;
+            cursor = gas_consumer.load_cell(cursor.reference(next_index)?)?;
+            bit_len = bit_len.checked_sub(label.remaining_bits() + 1).ok_or(ExceptionCode::DictionaryError)?;
+            is_root = false;
+        }
+        // all the items have the prefix
+        if is_root {
+            return Ok(self.clone())
+        }
+        let is_leaf = Self::is_leaf(&mut cursor);
+        let root = Self::make_cell_with_label_and_data(SliceData::load_bitstring(path)?, self.bit_len, is_leaf, &cursor)?;
+        Ok(Self::with_hashmap(self.bit_len, Some(gas_consumer.finalize_cell(root)?)))
+    }
+    /// iterates items in ascending order of keys
+    pub fn iter(&self) -> PfxHashmapIterator {
+        PfxHashmapIterator::from_hashmap(self)
+    }
+    /// iterates items with deserialized values
+    pub fn iterate_with_keys<V, F>(&self, mut p: F) -> Result<bool>
+    where V: Deserializable, F: FnMut(SliceData, V) -> Result<bool> {
+        self.iterate_slices(|key, mut value| p(key, V::construct_from(&mut value)?))
+    }
+    /// returns all the items as keys and deserialized values
+    pub fn export_items<V: Deserializable>(&self) -> Result<Vec<(SliceData, V)>> {
+        let mut items = Vec::new();
+        self.iterate_with_keys(|key, value| {
+            items.push((key, value));
+            Ok(true)
+        })?;
+        Ok(items)
+    }
 }
 
 // phm_edge#_ {n:#} {X:Type} {l:#} {m:#} label:(HmLabel ~l n)
@@ -247,8 +343,8 @@ impl HashmapType for PfxHashmapE {
     }
     fn make_leaf(key: &SliceData, bit_len: usize, value: &SliceData) -> Result<BuilderData> {
         let mut builder = hm_label(key, bit_len)?;
-        builder.checked_append_references_and_data(value)?;
         builder.append_bit_zero()?;
+        builder.checked_append_references_and_data(value)?;
         Ok(builder)
     }
     fn is_fork(slice: &mut SliceData) -> Result<bool> {
@@ -266,9 +362,89 @@ impl HashmapType for PfxHashmapE {
     fn bit_len(&self) -> usize {
         self.bit_len
     }
+    // leaves can be on any depth so common iteration by key length can not be used
+    fn iterate_slices<F> (&self, mut p: F) -> Result<bool>
+    where F: FnMut(SliceData, SliceData) -> Result<bool> {
+        let mut iter = self.iter();
+        while let Some((key, value)) = iter.next_item()? {
+            if !p(SliceData::load_bitstring(key)?, value)? {
+                return Ok(false)
+            }
+        }
+        Ok(true)
+    }
+    fn len(&self) -> Result<usize> {
+        self.count(usize::MAX)
+    }
+    fn count(&self, max: usize) -> Result<usize> {
+        let mut len = 0;
+        self.iterate_slices(|_, _| {
+            if len == max {
+                return Ok(false)
+            }
+            len += 1;
+            Ok(true)
+        })?;
+        Ok(len)
+    }
+}
+
+impl HashmapRemover for PfxHashmapE {
+    // path of missing key can end inside of label, on fork or on shorter key leaf
+    fn hashmap_remove(&mut self, key: SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Option<SliceData>> {
+        let bit_len = self.bit_len;
+        Self::check_key_fail(bit_len, &key)?;
+        if self.hashmap_get(key.clone(), &mut 0)?.is_none() {
+            return Ok(None)
+        }
+        let result = remove_node::<Self>(self.data_mut(), bit_len, key, false, gas_consumer)?;
+        Ok(result.map(|(value, _)| value))
+    }
+}
+
+///
+/// Iterator over items of PfxHashmap in ascending order of keys.
+/// Keys have different length, leaves are distinguished by their tag.
+///
+pub struct PfxHashmapIterator {
+    pos: Vec<(Cell, usize, BuilderData)>,
 }
 
-impl HashmapRemover for PfxHashmapE {}
+impl PfxHashmapIterator {
+    pub fn from_hashmap(tree: &PfxHashmapE) -> Self {
+        let mut pos = vec![];
+        if let Some(root) = tree.data() {
+            pos.push((root.clone(), tree.bit_len(), BuilderData::default()));
+        }
+        Self { pos }
+    }
+    pub fn next_item(&mut self) -> Result<Option<(BuilderData, SliceData)>> {
+        while let Some((cell, mut bit_len, key)) = self.pos.pop() {
+            let mut cursor = SliceData::load_cell(cell)?;
+            let key = LabelReader::read_label_raw(&mut cursor, &mut bit_len, key)?;
+            if PfxHashmapE::is_leaf(&mut cursor) {
+                return Ok(Some((key, cursor)))
+            }
+            if bit_len == 0 || cursor.remaining_references() < 2 {
+                fail!(ExceptionCode::DictionaryError)
+            }
+            // push right branch first to pop it after the left one
+            for index in [1, 0] {
+                let mut key = key.clone();
+                key.append_bit_bool(index == 1)?;
+                self.pos.push((cursor.reference(index)?, bit_len - 1, key));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Iterator for PfxHashmapIterator {
+    type Item = Result<(BuilderData, SliceData)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().transpose()
+    }
+}
 
 #[cfg(test)]
 #[path = "tests/test_pfxhashmap.rs"]
@@ -0,0 +1,169 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{HashmapE, ShardAccount, ShardAccounts, ShardStateUnsplit, UInt256, UsageTree};
+use rand::{Rng, SeedableRng};
+
+fn key(bit_len: usize, key: u32) -> SliceData {
+    SliceData::from_raw((key << (32 - bit_len)).to_be_bytes().to_vec(), bit_len)
+}
+
+fn value(value: u32) -> SliceData {
+    SliceData::new(value.to_be_bytes().into_iter().chain([0x80]).collect())
+}
+
+#[test]
+fn test_batch_random() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(2024);
+    for bit_len in [1, 3, 8, 16, 32] {
+        let mask = if bit_len == 32 { u32::MAX } else { (1 << bit_len) - 1 };
+        for round in 0..30 {
+            let mut map = HashmapE::with_bit_len(bit_len);
+            for _ in 0..rng.gen_range(0..200) {
+                let k = rng.gen::<u32>() & mask;
+                map.set(key(bit_len, k), &value(k)).unwrap();
+            }
+            let mut expected = map.clone();
+            let mut batch = DictBatch::<HashmapE>::new(bit_len);
+            for _ in 0..rng.gen_range(0..100) {
+                let k = rng.gen::<u32>() & mask;
+                if rng.gen_bool(0.5) || round == 0 {
+                    batch.remove(key(bit_len, k)).unwrap();
+                    expected.remove(key(bit_len, k)).unwrap();
+                } else {
+                    let v = rng.gen::<u32>();
+                    batch.set(key(bit_len, k), value(v)).unwrap();
+                    expected.set(key(bit_len, k), &value(v)).unwrap();
+                }
+            }
+            batch.apply(&mut map).unwrap();
+            assert_eq!(map, expected, "bit_len {} round {}", bit_len, round);
+        }
+    }
+}
+
+#[test]
+fn test_batch_cases() {
+    let mut map = HashmapE::with_bit_len(8);
+    for k in [0x10, 0x11, 0x80, 0xF0] {
+        map.set(key(8, k), &value(k)).unwrap();
+    }
+    let original = map.clone();
+
+    // removing of absent keys keeps the tree
+    let mut batch = DictBatch::<HashmapE>::new(8);
+    for k in [0x00, 0x12, 0x81, 0xFF] {
+        batch.remove(key(8, k)).unwrap();
+    }
+    assert_eq!(batch.len(), 4);
+    batch.apply(&mut map).unwrap();
+    assert_eq!(map, original);
+
+    // the last operation with the same key wins
+    let mut batch = DictBatch::<HashmapE>::new(8);
+    batch.set(key(8, 0x12), value(1)).unwrap();
+    batch.remove(key(8, 0x12)).unwrap();
+    batch.remove(key(8, 0x80)).unwrap();
+    batch.set(key(8, 0x80), value(2)).unwrap();
+    batch.setref(key(8, 0x01), &value(3).into_cell()).unwrap();
+    batch.apply(&mut map).unwrap();
+    let mut expected = original.clone();
+    expected.set(key(8, 0x80), &value(2)).unwrap();
+    expected.setref(key(8, 0x01), &value(3).into_cell()).unwrap();
+    assert_eq!(map, expected);
+
+    // remove everything
+    let mut batch = DictBatch::<HashmapE>::new(8);
+    for k in [0x01, 0x10, 0x11, 0x80, 0xF0] {
+        batch.remove(key(8, k)).unwrap();
+    }
+    batch.apply(&mut map).unwrap();
+    assert!(map.is_empty());
+
+    // empty tree
+    let mut batch = DictBatch::<HashmapE>::new(8);
+    batch.set(key(8, 0x80), value(0x80)).unwrap();
+    batch.set(key(8, 0xF0), value(0xF0)).unwrap();
+    batch.apply(&mut map).unwrap();
+    let mut expected = HashmapE::with_bit_len(8);
+    expected.set(key(8, 0x80), &value(0x80)).unwrap();
+    expected.set(key(8, 0xF0), &value(0xF0)).unwrap();
+    assert_eq!(map, expected);
+
+    let mut batch = DictBatch::<HashmapE>::new(8);
+    batch.set(key(7, 1), value(1)).expect_err("wrong key length must fail");
+    batch.apply(&mut HashmapE::with_bit_len(16)).expect_err("wrong dictionary must fail");
+}
+
+#[test]
+fn test_batch_loads_only_changed_paths() {
+    let mut map = HashmapE::with_bit_len(32);
+    for k in 0..1000 {
+        map.set(key(32, k * 13), &value(k)).unwrap();
+    }
+    let usage_tree = UsageTree::with_params(map.data().unwrap().clone(), true);
+    let mut map = HashmapE::with_hashmap(32, Some(usage_tree.root_cell()));
+    let mut expected = map.clone();
+    let mut batch = DictBatch::<HashmapE>::new(32);
+    batch.set(key(32, 13 * 500), value(0)).unwrap();
+    batch.set(key(32, 13 * 500 + 1), value(1)).unwrap();
+    batch.remove(key(32, 13 * 501)).unwrap();
+    batch.apply(&mut map).unwrap();
+    assert!(usage_tree.build_visited_set().len() < 50);
+
+    expected.set(key(32, 13 * 500), &value(0)).unwrap();
+    expected.set(key(32, 13 * 500 + 1), &value(1)).unwrap();
+    expected.remove(key(32, 13 * 501)).unwrap();
+    assert_eq!(map, expected);
+}
+
+#[test]
+fn test_batch_aug() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let accounts = state.read_accounts().unwrap();
+    let mut items = vec![];
+    accounts.iterate_with_keys(|key: UInt256, account| {
+        items.push((key, account));
+        Ok(true)
+    }).unwrap();
+    assert!(items.len() > 2);
+
+    let mut expected = accounts.clone();
+    let mut batch = DictBatch::<ShardAccounts>::new(256);
+    // move the first account to a new address and remove the second one
+    let (key, account) = items[0].clone();
+    let new_key = UInt256::from_slice(&[0x55; 32]);
+    batch.del(&key).unwrap();
+    batch.set_augmentable(&new_key, &account).unwrap();
+    batch.del(&items[1].0).unwrap();
+    expected.del(&key).unwrap();
+    expected.set_augmentable(&new_key, &account).unwrap();
+    expected.del(&items[1].0).unwrap();
+
+    let mut map = accounts.clone();
+    batch.apply(&mut map).unwrap();
+    assert_eq!(map.data(), expected.data());
+    assert_eq!(map.root_extra(), expected.root_extra());
+    assert_ne!(map.root_extra(), accounts.root_extra());
+
+    // remove all
+    let mut batch = DictBatch::<ShardAccounts>::new(256);
+    map.iterate_with_keys(|key: UInt256, _: ShardAccount| {
+        batch.del(&key)?;
+        Ok(true)
+    }).unwrap();
+    batch.apply(&mut map).unwrap();
+    assert!(map.is_empty());
+    assert_eq!(map, ShardAccounts::default());
+}
@@ -0,0 +1,133 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    AugDict, Block, DepthBalanceInfo, Deserializable, Dict, HashmapAugType, HashmapE, InMsgDescr,
+    OutMsgDescr, Serializable, ShardAccount, ShardAccounts, ShardStateUnsplit, UInt256,
+};
+use rand::{Rng, SeedableRng};
+
+fn check_hashmap(bit_len: usize, keys: &[u64]) {
+    let mut keys = keys.to_vec();
+    keys.sort();
+    keys.dedup();
+    let mut expected = HashmapE::with_bit_len(bit_len);
+    let mut items = vec![];
+    for key in &keys {
+        let value = SliceData::new(vec![*key as u8, 0x80]);
+        let key = SliceData::from_raw((key << (64 - bit_len)).to_be_bytes().to_vec(), bit_len);
+                expected.set(key.clone(), &value).unwrap();
+        items.push((key, value));
+    }
+    let map = HashmapE::from_sorted(bit_len, items).unwrap();
+    assert_eq!(map, expected);
+    assert_eq!(map.len().unwrap(), keys.len());
+}
+
+#[test]
+fn test_bulk_hashmap() {
+    check_hashmap(8, &[]);
+    check_hashmap(8, &[7]);
+    check_hashmap(8, &[0, 255]);
+    check_hashmap(8, &[0, 1]);
+    check_hashmap(8, &(0..256).collect::<Vec<_>>());
+    check_hashmap(8, &[1, 2, 3, 64, 65, 127, 128, 200]);
+    check_hashmap(1, &[0, 1]);
+    check_hashmap(64, &[0, 1, u64::MAX - 1, u64::MAX, 1 << 63, (1 << 63) + 1]);
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(12345);
+    for bit_len in [5, 16, 32, 64] {
+        let mask = if bit_len == 64 { u64::MAX } else { (1 << bit_len) - 1 };
+        let keys = (0..2000).map(|_| rng.gen::<u64>() & mask).collect::<Vec<_>>();
+        check_hashmap(bit_len, &keys);
+    }
+}
+
+#[test]
+fn test_bulk_wrong_order() {
+    let key = |k: u8| SliceData::new(vec![k, 0x80]);
+    let value = SliceData::new(vec![0x80]);
+    let mut builder = HashmapBulkBuilder::<HashmapE>::new(8);
+    builder.push(key(5), value.clone()).unwrap();
+    builder.push(key(6), value.clone()).unwrap();
+    builder.push(key(6), value.clone()).expect_err("duplicate key must fail");
+    builder.push(key(3), value.clone()).expect_err("unsorted key must fail");
+    builder.push(SliceData::new(vec![7]), value.clone()).expect_err("short key must fail");
+
+    let items = [(key(2), value.clone()), (key(1), value.clone())];
+    HashmapE::from_sorted(8, items).expect_err("unsorted keys must fail");
+    Dict::<u32, u32>::from_sorted([(2, 2), (1, 1)]).expect_err("unsorted keys must fail");
+}
+
+#[test]
+fn test_bulk_dict() {
+    let mut expected = Dict::<u32, u64>::new();
+    let mut items = vec![];
+    for i in (0..1000u32).map(|i| i * 7919) {
+        expected.set(&i, &(i as u64 * 3)).unwrap();
+        items.push((i, i as u64 * 3));
+    }
+    assert_eq!(Dict::from_sorted(items).unwrap(), expected);
+    assert_eq!(Dict::<u32, u64>::from_sorted([]).unwrap(), Dict::new());
+}
+
+#[test]
+fn test_bulk_aug_dict() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let accounts = state.read_accounts().unwrap();
+    let mut items = vec![];
+    accounts.iterate_with_keys(|key: UInt256, account| {
+        items.push((key, account));
+        Ok(true)
+    }).unwrap();
+
+    let rebuilt = ShardAccounts::from_sorted(items.clone()).unwrap();
+    assert_eq!(rebuilt, accounts);
+    assert_eq!(rebuilt.root_extra(), accounts.root_extra());
+
+    let dict = AugDict::<UInt256, ShardAccount, DepthBalanceInfo>::from_sorted(items).unwrap();
+    assert_eq!(dict.root(), accounts.data());
+    assert_eq!(dict.root_extra(), accounts.root_extra());
+
+    assert_eq!(ShardAccounts::from_sorted([]).unwrap(), ShardAccounts::default());
+}
+
+#[test]
+fn test_bulk_msg_descr() {
+    let block = Block::construct_from_file(
+        "src/tests/data/9C2B3FC5AD455917D374CFADBED8FC2343E31A27C1DF2EB29E84404FA96DE9F8.boc"
+    ).unwrap();
+    let extra = block.read_extra().unwrap();
+
+    let in_msgs = extra.read_in_msg_descr().unwrap();
+    let mut items = vec![];
+    in_msgs.iterate_with_keys(|key: UInt256, msg| {
+        items.push((key, msg));
+        Ok(true)
+    }).unwrap();
+    assert!(!items.is_empty());
+    let rebuilt = InMsgDescr::from_sorted(items).unwrap();
+    assert_eq!(rebuilt.data(), in_msgs.data());
+    assert_eq!(rebuilt.root_extra(), in_msgs.root_extra());
+
+    let out_msgs = extra.read_out_msg_descr().unwrap();
+    let mut items = vec![];
+    out_msgs.iterate_with_keys(|key: UInt256, msg| {
+        items.push((key, msg));
+        Ok(true)
+    }).unwrap();
+    assert!(!items.is_empty());
+    let rebuilt = OutMsgDescr::from_sorted(items).unwrap();
+    assert_eq!(rebuilt.serialize().unwrap(), out_msgs.serialize().unwrap());
+}
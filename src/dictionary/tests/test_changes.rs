@@ -0,0 +1,190 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    read_single_root_boc, ConfigParam1, ConfigParamEnum, ConfigParams, Dict, HashmapAugType,
+    HashmapE, HashmapRemover, ShardAccount, ShardStateUnsplit, UInt256, UsageTree, Deserializable,
+};
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+fn key(bit_len: usize, key: u32) -> SliceData {
+    SliceData::from_raw((key << (32 - bit_len)).to_be_bytes().to_vec(), bit_len)
+}
+
+fn value(value: u32) -> SliceData {
+    SliceData::new(value.to_be_bytes().into_iter().chain([0x80]).collect())
+}
+
+fn to_map(bit_len: usize, items: &BTreeMap<u32, u32>) -> HashmapE {
+    let mut map = HashmapE::with_bit_len(bit_len);
+    for (k, v) in items {
+        map.set(key(bit_len, *k), &value(*v)).unwrap();
+    }
+    map
+}
+
+fn decode(change: DictChange<BuilderData, SliceData>, bit_len: usize) -> DictChange<u32, u32> {
+    let k = SliceData::load_bitstring(change.key().clone()).unwrap().get_next_int(bit_len).unwrap() as u32;
+    let v = |value: &SliceData| value.clone().get_next_u32().unwrap();
+    match &change {
+        DictChange::Added(_, new) => DictChange::Added(k, v(new)),
+        DictChange::Removed(_, old) => DictChange::Removed(k, v(old)),
+        DictChange::Changed(_, old, new) => DictChange::Changed(k, v(old), v(new)),
+    }
+}
+
+fn expected_diff(old: &BTreeMap<u32, u32>, new: &BTreeMap<u32, u32>) -> Vec<DictChange<u32, u32>> {
+    let mut keys = old.keys().chain(new.keys()).cloned().collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter().filter_map(|k| match (old.get(&k), new.get(&k)) {
+        (Some(old), Some(new)) if old != new => Some(DictChange::Changed(k, *old, *new)),
+        (Some(old), None) => Some(DictChange::Removed(k, *old)),
+        (None, Some(new)) => Some(DictChange::Added(k, *new)),
+        _ => None
+    }).collect()
+}
+
+#[test]
+fn test_hashmap_diff_random() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(777);
+    for bit_len in [1, 4, 8, 16, 32] {
+        let mask = if bit_len == 32 { u32::MAX } else { (1 << bit_len) - 1 };
+        for _ in 0..30 {
+            let mut old = BTreeMap::new();
+            for _ in 0..rng.gen_range(0..100) {
+                old.insert(rng.gen::<u32>() & mask, rng.gen::<u32>() % 4);
+            }
+            // new version is derived from the old one or independent
+            let mut new = match rng.gen_bool(0.8) {
+                true => old.clone(),
+                false => BTreeMap::new()
+            };
+            for _ in 0..rng.gen_range(0..20) {
+                let k = rng.gen::<u32>() & mask;
+                match rng.gen_bool(0.3) {
+                    true => new.remove(&k),
+                    false => new.insert(k, rng.gen::<u32>() % 4),
+                };
+            }
+            let (old_map, new_map) = (to_map(bit_len, &old), to_map(bit_len, &new));
+            let found = old_map.hashmap_diff(&new_map).unwrap()
+                .map(|change| decode(change.unwrap(), bit_len))
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected_diff(&old, &new));
+
+            let reversed = new_map.hashmap_diff(&old_map).unwrap().count();
+            assert_eq!(reversed, found.len());
+        }
+    }
+    let map = HashmapE::with_bit_len(8);
+    assert!(map.hashmap_diff(&HashmapE::with_bit_len(16)).is_err());
+    assert_eq!(map.hashmap_diff(&map).unwrap().count(), 0);
+}
+
+#[test]
+fn test_hashmap_diff_skips_equal_subtrees() {
+    let mut old = HashmapE::with_bit_len(32);
+    for k in 0..2000 {
+        old.set(key(32, k * 7), &value(k)).unwrap();
+    }
+    let mut new = old.clone();
+    new.set(key(32, 700), &value(0)).unwrap();
+    new.set(key(32, 701), &value(1)).unwrap();
+    new.remove(key(32, 7000)).unwrap();
+
+    let old_tree = UsageTree::with_params(old.data().unwrap().clone(), true);
+    let new_tree = UsageTree::with_params(new.data().unwrap().clone(), true);
+    let old = HashmapE::with_hashmap(32, Some(old_tree.root_cell()));
+    let new = HashmapE::with_hashmap(32, Some(new_tree.root_cell()));
+    let found = old.hashmap_diff(&new).unwrap()
+        .map(|change| decode(change.unwrap(), 32))
+        .collect::<Vec<_>>();
+    assert_eq!(found, vec![
+        DictChange::Changed(700, 100, 0),
+        DictChange::Added(701, 1),
+        DictChange::Removed(7000, 1000),
+    ]);
+    assert!(old_tree.build_visited_set().len() < 100);
+    assert!(new_tree.build_visited_set().len() < 100);
+}
+
+#[test]
+fn test_dict_diff() {
+    let old = Dict::<u32, u64>::from_sorted((0..100).map(|i| (i, i as u64))).unwrap();
+    let mut new = old.clone();
+    new.set(&5, &500).unwrap();
+    new.remove(&10).unwrap();
+    new.set(&1000, &1).unwrap();
+    let changes = old.diff(&new).unwrap().collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(changes, vec![
+        DictChange::Changed(5, 5, 500),
+        DictChange::Removed(10, 10),
+        DictChange::Added(1000, 1),
+    ]);
+    assert_eq!(changes[0].key(), &5);
+    assert_eq!(changes[1].old_value(), Some(&10));
+    assert_eq!(changes[1].new_value(), None);
+}
+
+#[test]
+fn test_shard_accounts_diff() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let old = state.read_accounts().unwrap();
+    let mut items = vec![];
+    old.iterate_with_keys(|key: UInt256, account| {
+        items.push((key, account));
+        Ok(true)
+    }).unwrap();
+
+    let mut new = old.clone();
+    new.del(&items[0].0).unwrap();
+    let (key, mut account) = items[1].clone();
+    let mut changed = account.read_account().unwrap();
+    changed.set_balance(Default::default());
+    account.write_account(&changed).unwrap();
+    new.set_augmentable(&key, &account).unwrap();
+
+    let changes = old.diff(&new).unwrap().collect::<Result<Vec<DictChange<UInt256, ShardAccount>>>>().unwrap();
+    assert_eq!(changes, vec![
+        DictChange::Removed(items[0].0.clone(), items[0].1.clone()),
+        DictChange::Changed(key, items[1].1.clone(), account),
+    ]);
+
+    let mut empty = old.clone();
+    empty.hashmap_filter(|_, _| Ok(crate::HashmapFilterResult::Remove)).unwrap();
+    let removed = old.diff(&empty).unwrap().collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(removed.len(), items.len());
+    assert!(removed.iter().all(|change| matches!(change, DictChange::Removed(_, _))));
+}
+
+#[test]
+fn test_config_params_diff() {
+    let cell = read_single_root_boc(std::fs::read("src/tests/data/config.boc").unwrap()).unwrap();
+    let old = ConfigParams::with_address_and_params(UInt256::from([1; 32]), Some(cell));
+    let mut new = old.clone();
+    new.set_config(ConfigParamEnum::ConfigParam1(ConfigParam1 { elector_addr: UInt256::from([7; 32]) })).unwrap();
+
+    let old_params = Dict::<u32, Cell>::with_hashmap(old.config_params.data().cloned(), 0);
+    let new_params = Dict::<u32, Cell>::with_hashmap(new.config_params.data().cloned(), 0);
+    let changes = old_params.diff(&new_params).unwrap().collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(changes, vec![DictChange::Changed(
+        1,
+        old_params.get(&1).unwrap().unwrap(),
+        new_params.get(&1).unwrap().unwrap(),
+    )]);
+    let new_param = ConfigParam1::construct_from_cell(changes[0].new_value().unwrap().clone()).unwrap();
+    assert_eq!(new_param.elector_addr, UInt256::from([7; 32]));
+}
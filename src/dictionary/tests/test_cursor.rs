@@ -0,0 +1,226 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    Deserializable, Dict, HashmapAugType, HashmapE, ShardAccount, ShardStateUnsplit, UInt256, UsageTree,
+};
+
+fn make_map(keys: &[u8]) -> HashmapE {
+    let mut map = HashmapE::with_bit_len(8);
+    for key in keys {
+        map.set(SliceData::new(vec![*key, 0x80]), &SliceData::new(vec![*key, 0x80])).unwrap();
+    }
+    map
+}
+
+// converts key to its position in dictionary order
+fn order(key: u8, signed: bool) -> i16 {
+    match signed {
+        true => key as i8 as i16,
+        false => key as i16,
+    }
+}
+
+fn bound(value: i16, signed: bool, included: bool) -> Bound<SliceData> {
+    let key = match signed {
+        true => value as i8 as u8,
+        false => value as u8,
+    };
+    match included {
+        true => Bound::Included(SliceData::new(vec![key, 0x80])),
+        false => Bound::Excluded(SliceData::new(vec![key, 0x80])),
+    }
+}
+
+fn check_ranges(keys: &[u8]) {
+    let map = make_map(keys);
+    for signed in [false, true] {
+        let mut sorted = keys.iter().map(|key| order(*key, signed)).collect::<Vec<_>>();
+        sorted.sort();
+        sorted.dedup();
+        let (min, max) = if signed { (-128, 127) } else { (0, 255) };
+        for from in (min..=max).step_by(9).chain([min, max]) {
+            for to in (from..=max).step_by(13).chain([max]) {
+                for (from_inc, to_inc) in [(true, false), (false, true), (true, true)] {
+                    let expected = sorted.iter().cloned().filter(|key| {
+                        (if from_inc { *key >= from } else { *key > from })
+                            && (if to_inc { *key <= to } else { *key < to })
+                    }).collect::<Vec<_>>();
+                    for backward in [false, true] {
+                        let found = map.hashmap_range(
+                            bound(from, signed, from_inc), bound(to, signed, to_inc), signed, backward
+                        ).unwrap().map(|item| {
+                            let (key, value) = item.unwrap();
+                            assert_eq!(key.data()[0], value.get_bytestring(0)[0]);
+                            order(key.data()[0], signed)
+                        }).collect::<Vec<_>>();
+                        let mut expected = expected.clone();
+                        if backward {
+                            expected.reverse();
+                        }
+                        assert_eq!(found, expected, "from {} to {} signed {} backward {}", from, to, signed, backward);
+                    }
+                }
+            }
+        }
+        let all = map.hashmap_range(Bound::Unbounded, Bound::Unbounded, signed, false).unwrap().count();
+        assert_eq!(all, sorted.len());
+    }
+}
+
+#[test]
+fn test_hashmap_range() {
+    check_ranges(&[0, 1, 2, 5, 17, 100, 127, 128, 129, 200, 254, 255]);
+    // root label contains sign bit
+    check_ranges(&[0x80, 0x81, 0x85, 0x8F, 0xF0]);
+    check_ranges(&[3, 7, 64, 65]);
+    check_ranges(&[42]);
+    check_ranges(&[]);
+}
+
+#[test]
+fn test_hashmap_range_loads_only_needed_cells() {
+    let mut dict = Dict::<u32, u32>::new();
+    for i in 0..1000u32 {
+        dict.set(&(i * 3), &i).unwrap();
+    }
+    let usage_tree = UsageTree::with_params(dict.root().unwrap().clone(), true);
+    let dict = Dict::<u32, u32>::with_hashmap(Some(usage_tree.root_cell()), 0);
+    let items = dict.range(Bound::Included(&1500), Bound::Excluded(&1510), false).unwrap()
+        .collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(items, vec![(1500, 500), (1503, 501), (1506, 502), (1509, 503)]);
+    assert!(usage_tree.build_visited_set().len() < 50);
+
+    let items = dict.range(Bound::Unbounded, Bound::Excluded(&9), true).unwrap()
+        .collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(items, vec![(6, 2), (3, 1), (0, 0)]);
+}
+
+#[test]
+fn test_hashmap_cursor() {
+    let keys = [0u8, 1, 2, 5, 17, 100, 127, 128, 129, 200, 254, 255];
+    let map = make_map(&keys);
+    for signed in [false, true] {
+        let mut sorted = keys.iter().map(|key| order(*key, signed)).collect::<Vec<_>>();
+        sorted.sort();
+        let key_of = |item: Option<&(BuilderData, SliceData)>| item.map(|(key, _)| order(key.data()[0], signed));
+
+        let mut cursor = map.hashmap_cursor(signed);
+        assert!(cursor.current().is_none());
+        let mut found = vec![];
+        while let Some(key) = key_of(cursor.move_next().unwrap()) {
+            found.push(key);
+        }
+        assert_eq!(found, sorted);
+        assert!(cursor.current().is_none());
+        // cursor ran off the end doesn't wrap around
+        assert!(cursor.move_next().unwrap().is_none());
+        assert!(cursor.move_prev().unwrap().is_none());
+
+        let mut found = vec![];
+        let mut cursor = map.hashmap_cursor(signed);
+        while let Some(key) = key_of(cursor.move_prev().unwrap()) {
+            found.push(key);
+        }
+        found.reverse();
+        assert_eq!(found, sorted);
+        assert!(cursor.move_prev().unwrap().is_none());
+        assert!(cursor.move_next().unwrap().is_none());
+
+        // stops at both ends after seek
+        assert_eq!(key_of(cursor.seek_last().unwrap()), sorted.last().cloned());
+        assert!(cursor.move_next().unwrap().is_none());
+        assert!(cursor.move_prev().unwrap().is_none());
+        assert_eq!(key_of(cursor.seek_first().unwrap()), sorted.first().cloned());
+        assert!(cursor.move_prev().unwrap().is_none());
+        assert!(cursor.move_next().unwrap().is_none());
+
+        assert_eq!(key_of(cursor.seek_first().unwrap()), sorted.first().cloned());
+        assert_eq!(key_of(cursor.seek_last().unwrap()), sorted.last().cloned());
+
+        let (min, max) = if signed { (-128, 127) } else { (0, 255) };
+        for value in min..=max {
+            let key = match bound(value, signed, true) {
+                Bound::Included(key) => key,
+                _ => unreachable!()
+            };
+            let next = sorted.iter().cloned().find(|key| *key > value);
+            let next_eq = sorted.iter().cloned().find(|key| *key >= value);
+            let prev = sorted.iter().rev().cloned().find(|key| *key < value);
+            assert_eq!(key_of(cursor.seek(key.clone(), true, false).unwrap()), next);
+            assert_eq!(key_of(cursor.seek(key.clone(), true, true).unwrap()), next_eq);
+            assert_eq!(key_of(cursor.seek(key.clone(), false, false).unwrap()), prev);
+            // move in both directions from the found position
+            if let Some(found) = key_of(cursor.seek(key.clone(), true, true).unwrap()) {
+                let index = sorted.iter().position(|key| *key == found).unwrap();
+                assert_eq!(key_of(cursor.move_next().unwrap()), sorted.get(index + 1).cloned());
+                if index + 1 < sorted.len() {
+                    assert_eq!(key_of(cursor.move_prev().unwrap()), Some(found));
+                }
+            }
+        }
+    }
+    assert!(map.hashmap_cursor(false).seek(SliceData::new(vec![0x80]), true, true).is_err());
+
+    // key not found by seek is the end too
+    let mut cursor = map.hashmap_cursor(false);
+    assert!(cursor.seek(SliceData::new(vec![0xFF, 0x80]), true, false).unwrap().is_none());
+    assert!(cursor.move_next().unwrap().is_none());
+    assert!(cursor.move_prev().unwrap().is_none());
+
+    // empty dictionary
+    let mut cursor = make_map(&[]).hashmap_cursor(false);
+    assert!(cursor.move_next().unwrap().is_none());
+    assert!(cursor.move_prev().unwrap().is_none());
+}
+
+#[test]
+fn test_aug_range_and_cursor() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let accounts = state.read_accounts().unwrap();
+    let mut all = vec![];
+    accounts.iterate_with_keys(|key: UInt256, account| {
+        all.push((key, account));
+        Ok(true)
+    }).unwrap();
+    assert!(all.len() > 3);
+
+    // paged listing
+    let mut pages = vec![];
+    let mut last: Option<UInt256> = None;
+    loop {
+        let from = match &last {
+            Some(key) => Bound::Excluded(key),
+            None => Bound::Unbounded
+        };
+        let page = accounts.range(from, Bound::Unbounded, false, false).unwrap()
+            .take(2).collect::<Result<Vec<(UInt256, ShardAccount)>>>().unwrap();
+        if page.is_empty() {
+            break
+        }
+        last = Some(page.last().unwrap().0.clone());
+        pages.extend(page);
+    }
+    assert_eq!(pages, all);
+
+    let mut cursor = accounts.cursor(false);
+    assert_eq!(cursor.seek_last().unwrap().unwrap(), all.last().unwrap().clone());
+    assert_eq!(cursor.move_prev().unwrap().unwrap(), all[all.len() - 2].clone());
+    assert_eq!(cursor.seek(&all[1].0, true, false).unwrap().unwrap(), all[2].clone());
+    assert_eq!(cursor.current().unwrap().unwrap(), all[2].clone());
+
+    let backward = accounts.range(Bound::Unbounded, Bound::Included(&all[1].0), false, true).unwrap()
+        .collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(backward, vec![all[1].clone(), all[0].clone()]);
+}
@@ -0,0 +1,136 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    ConfigParam18Map, DepthBalanceInfo, OutMsgQueueKey, ShardAccount, ShardAccounts,
+    ShardStateUnsplit, StoragePrices,
+};
+
+#[test]
+fn test_dict() {
+    let mut dict = Dict::<u32, u64>::new();
+    assert!(dict.is_empty());
+    for i in [5u32, 1, 3, 0xFFFF_FFFF] {
+        dict.set(&i, &(i as u64 * 10)).unwrap();
+    }
+    assert_eq!(dict.len().unwrap(), 4);
+    assert_eq!(dict.get(&3).unwrap(), Some(30));
+    assert_eq!(dict.get(&4).unwrap(), None);
+    assert!(dict.contains_key(&0xFFFF_FFFF).unwrap());
+
+    // the same as raw HashmapE
+    let mut map = HashmapE::with_bit_len(32);
+    for i in [1u32, 3, 5, 0xFFFF_FFFF] {
+        map.set_builder(i.write_to_bitstring().unwrap(), &(i as u64 * 10).write_to_new_cell().unwrap()).unwrap();
+    }
+    assert_eq!(dict.as_hashmap(), &map);
+
+    let items = dict.iter().collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(items, vec![(1, 10), (3, 30), (5, 50), (0xFFFF_FFFF, 0xFFFF_FFFF * 10)]);
+
+    assert!(dict.remove(&3).unwrap());
+    assert!(!dict.remove(&3).unwrap());
+    let mut keys = vec![];
+    dict.iterate_keys(|key| {
+        keys.push(key);
+        Ok(true)
+    }).unwrap();
+    assert_eq!(keys, vec![1, 5, 0xFFFF_FFFF]);
+
+    let cell = dict.serialize().unwrap();
+    assert_eq!(Dict::<u32, u64>::construct_from_cell(cell).unwrap(), dict);
+}
+
+#[test]
+fn test_dict_compatible_with_macro() {
+    let mut dict = Dict::<u32, StoragePrices>::new();
+    let mut map = ConfigParam18Map::default();
+    for i in 0..10u32 {
+        let prices = StoragePrices { utime_since: i * 100, bit_price_ps: i as u64, ..Default::default() };
+        dict.set(&i, &prices).unwrap();
+        map.set(&i, &prices).unwrap();
+    }
+    assert_eq!(dict.root(), map.root());
+    assert_eq!(dict.write_to_new_cell().unwrap(), map.write_to_new_cell().unwrap());
+    let map = ConfigParam18Map::construct_from_cell(dict.serialize().unwrap()).unwrap();
+    assert_eq!(map.get(&7u32).unwrap().unwrap().utime_since, 700);
+}
+
+#[test]
+fn test_dict_key_width() {
+    let mut dict = Dict::<AccountId, u8>::new();
+    dict.set(&AccountId::from([1; 32]), &1).unwrap();
+    assert_eq!(dict.get(&AccountId::from([1; 32])).unwrap(), Some(1));
+    let short_key = SliceData::new(vec![0x55, 0x80]);
+    assert!(dict.get(&short_key).is_err());
+    assert!(dict.set(&short_key, &2).is_err());
+
+    let mut dict = Dict::<OutMsgQueueKey, u8>::new();
+    let key = OutMsgQueueKey::with_workchain_id_and_prefix(-1, 0x8000_0000_0000_0000, UInt256::from([2; 32]));
+    dict.set(&key, &3).unwrap();
+    assert_eq!(dict.as_hashmap().bit_len(), 352);
+    assert_eq!(dict.iter().next().unwrap().unwrap(), (key, 3));
+}
+
+#[test]
+fn test_aug_dict() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let accounts = state.read_accounts().unwrap();
+    let mut dict = AugDict::<UInt256, ShardAccount, DepthBalanceInfo>::new();
+    let mut rebuilt = ShardAccounts::default();
+    accounts.iterate_with_keys(|key, account| {
+        dict.set_augmentable(&key, &account)?;
+        rebuilt.set_augmentable(&key, &account)?;
+        Ok(true)
+    }).unwrap();
+    assert_eq!(dict.root(), rebuilt.data());
+    assert_eq!(dict.root_extra(), rebuilt.root_extra());
+    assert_eq!(dict.len().unwrap(), accounts.len().unwrap());
+
+    let items = dict.iter().collect::<Result<Vec<_>>>().unwrap();
+    let (key, account) = items.first().unwrap();
+    assert_eq!(&accounts.get(key).unwrap().unwrap(), account);
+    assert_eq!(&dict.get(key).unwrap().unwrap(), account);
+
+    let cell = dict.serialize().unwrap();
+    assert_eq!(cell, rebuilt.serialize().unwrap());
+    let mut dict = AugDict::<UInt256, ShardAccount, DepthBalanceInfo>::construct_from_cell(cell).unwrap();
+    assert!(dict.remove(key).unwrap());
+    assert!(!dict.contains_key(key).unwrap());
+    rebuilt.del(key).unwrap();
+    assert_eq!(dict.root(), rebuilt.data());
+    assert_eq!(dict.root_extra(), rebuilt.root_extra());
+}
+
+#[test]
+fn test_dict_signed_keys_order() {
+    let keys = [-100, -5, -1, 0, 3, 100];
+    let mut dict = Dict::<i32, u32>::new();
+    for key in keys.iter().rev() {
+        dict.set(key, &(*key as u32)).unwrap();
+    }
+    let items = dict.range(Bound::Unbounded, Bound::Unbounded, false).unwrap()
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(items, keys);
+    let items = dict.range(Bound::Included(&-5), Bound::Excluded(&3), true).unwrap()
+        .map(|item| item.map(|(key, _)| key))
+        .collect::<Result<Vec<_>>>().unwrap();
+    assert_eq!(items, [0, -1, -5]);
+
+    let mut cursor = dict.cursor();
+    assert_eq!(cursor.seek_first().unwrap().unwrap().0, -100);
+    assert_eq!(cursor.seek_last().unwrap().unwrap().0, 100);
+    assert_eq!(cursor.seek(&0, false, false).unwrap().unwrap().0, -1);
+}
@@ -12,6 +12,12 @@
 */
 
 use super::*;
+use crate::{
+    define_HashmapAugE, AddSub, Augmentable, Augmentation, Deserializable, Grams, HashmapAugType,
+    HashmapFilterResult, HashmapFilterSplitResult, HashmapSubtree, LabelReader, Serializable,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::BTreeMap;
 
 #[test]
 fn set_get_simple() {
@@ -1595,3 +1601,371 @@ fn subtree_wrong_prefix() {
     tree.subtree_root_cell(&wrong_prefix).unwrap_err();
 }
 
+// Differential tests of dictionaries against BTreeMap model: random sequences
+// of operations are applied to both and after every step tree must have the same
+// items and the same root hash as the tree built from scratch by model items
+
+type Model = BTreeMap<u64, u32>;
+
+fn model_mask(bit_len: usize) -> u64 {
+    if bit_len == 64 { u64::MAX } else { (1 << bit_len) - 1 }
+}
+
+fn model_slice(key: u64, bit_len: usize, len: usize) -> SliceData {
+    SliceData::from_raw((key << (64 - bit_len)).to_be_bytes().to_vec(), len)
+}
+
+fn model_key(key: u64, bit_len: usize) -> SliceData {
+    model_slice(key, bit_len, bit_len)
+}
+
+fn model_key_from_slice(key: &BuilderData, bit_len: usize) -> u64 {
+    let mut slice = SliceData::load_bitstring(key.clone()).unwrap();
+    slice.get_next_int(bit_len).unwrap()
+}
+
+fn model_value(value: u32) -> SliceData {
+    SliceData::load_builder(value.write_to_new_cell().unwrap()).unwrap()
+}
+
+fn model_has_prefix(key: u64, bit_len: usize, prefix: u64, len: usize) -> bool {
+    len == 0 || key >> (bit_len - len) == prefix >> (bit_len - len)
+}
+
+// existing key, neighbour of existing key or random key with some leading zeros
+fn random_model_key(rng: &mut StdRng, model: &Model, bit_len: usize) -> u64 {
+    let existing = model.keys().nth(rng.gen_range(0..model.len().max(1))).copied();
+    match (rng.gen_range(0..3), existing) {
+        (0, Some(key)) => key,
+        (1, Some(key)) => key ^ (1 << rng.gen_range(0..bit_len)),
+        _ => (rng.gen::<u64>() & model_mask(bit_len)) >> rng.gen_range(0..bit_len)
+    }
+}
+
+// length of common prefix of all the keys - label of root for two and more items
+fn model_common_prefix(model: &Model, bit_len: usize) -> Option<usize> {
+    let min = model.keys().next()?;
+    let max = model.keys().next_back()?;
+    if min == max {
+        return None
+    }
+    Some((min ^ max).leading_zeros() as usize - (64 - bit_len))
+}
+
+// decisions for items of filter in ascending order of keys with occasional stop or cancel
+fn random_filter_decisions(rng: &mut StdRng, len: usize) -> Vec<HashmapFilterResult> {
+    let mut decisions = (0..len)
+        .map(|_| match rng.gen_range(0..8) {
+            0 => HashmapFilterResult::Remove,
+            _ => HashmapFilterResult::Accept
+        })
+        .collect::<Vec<_>>();
+    if len != 0 && rng.gen_range(0..4) == 0 {
+        decisions[rng.gen_range(0..len)] = match rng.gen::<bool>() {
+            true => HashmapFilterResult::Stop,
+            false => HashmapFilterResult::Cancel
+        };
+    }
+    decisions
+}
+
+fn apply_filter_decisions(model: &mut Model, decisions: &[HashmapFilterResult]) {
+    let mut new_model = model.clone();
+    for (key, decision) in model.keys().zip(decisions) {
+        match decision {
+            HashmapFilterResult::Remove => { new_model.remove(key); }
+            HashmapFilterResult::Accept => (),
+            HashmapFilterResult::Stop => break,
+            HashmapFilterResult::Cancel => return
+        }
+    }
+    *model = new_model;
+}
+
+fn check_hashmap_with_model(tree: &HashmapE, model: &Model) {
+    let bit_len = tree.bit_len();
+    let mut items = vec![];
+    tree.iterate_slices(|key, mut value| {
+        items.push((key.clone().get_next_int(bit_len)?, value.get_next_u32()?));
+        Ok(true)
+    }).unwrap();
+    assert_eq!(items, model.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>());
+    assert_eq!(tree.len().unwrap(), model.len());
+
+    let mut expected = HashmapE::with_bit_len(bit_len);
+    for (key, value) in model {
+        expected.set(model_key(*key, bit_len), &model_value(*value)).unwrap();
+    }
+    assert_eq!(tree.data().map(|root| root.repr_hash()), expected.data().map(|root| root.repr_hash()));
+}
+
+fn run_hashmap_model(seed: u64, bit_len: usize, steps: usize) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut tree = HashmapE::with_bit_len(bit_len);
+    let mut model = Model::new();
+    for _ in 0..steps {
+        let key = random_model_key(&mut rng, &model, bit_len);
+        let value = rng.gen::<u32>();
+        match rng.gen_range(0..32) {
+            0..=11 => {
+                let prev = tree.set(model_key(key, bit_len), &model_value(value)).unwrap();
+                assert_eq!(prev, model.insert(key, value).map(model_value));
+            }
+            12..=13 => {
+                let prev = tree.replace_with_gas(model_key(key, bit_len), &model_value(value), &mut 0).unwrap();
+                let expected = model.get_mut(&key).map(|old| std::mem::replace(old, value));
+                assert_eq!(prev, expected.map(model_value));
+            }
+            14..=15 => {
+                let prev = tree.add_with_gas(model_key(key, bit_len), &model_value(value), &mut 0).unwrap();
+                let expected = model.get(&key).copied();
+                model.entry(key).or_insert(value);
+                assert_eq!(prev, expected.map(model_value));
+            }
+            16..=21 => {
+                let prev = tree.remove(model_key(key, bit_len)).unwrap();
+                assert_eq!(prev, model.remove(&key).map(model_value));
+            }
+            22..=25 => if let Some(common) = model_common_prefix(&model, bit_len) {
+                // split by some part of root label and merge back
+                let len = rng.gen_range(0..=common);
+                let split_key = model_slice(key, bit_len, 0);
+                let split_key = match len {
+                    0 => split_key,
+                    _ => model_slice(*model.keys().next().unwrap(), bit_len, len)
+                };
+                let (mut left, right) = tree.split(&split_key).unwrap();
+                let bit = |key: &u64| (key >> (bit_len - len - 1)) & 1 == 1;
+                check_hashmap_with_model(&left, &model.iter().filter(|(k, _)| !bit(k)).map(|(k, v)| (*k, *v)).collect());
+                check_hashmap_with_model(&right, &model.iter().filter(|(k, _)| bit(k)).map(|(k, v)| (*k, *v)).collect());
+                left.merge(&right, &split_key).unwrap();
+                assert_eq!(left, tree);
+                tree = left;
+            }
+            26..=27 => {
+                let decisions = random_filter_decisions(&mut rng, model.len());
+                let mut keys = model.keys();
+                let mut index = 0;
+                tree.hashmap_filter(|key, _| {
+                    assert_eq!(Some(&model_key_from_slice(key, bit_len)), keys.next());
+                    index += 1;
+                    Ok(decisions[index - 1].clone())
+                }).unwrap();
+                apply_filter_decisions(&mut model, &decisions);
+            }
+            28 => {
+                // move some items to other tree and combine them back
+                let moved_keys = model.keys().filter(|_| rng.gen::<bool>()).copied().collect::<Vec<_>>();
+                let moved = tree.hashmap_filter_split(|key, _| {
+                    match moved_keys.contains(&model_key_from_slice(key, bit_len)) {
+                        true => Ok(HashmapFilterSplitResult::Move),
+                        false => Ok(HashmapFilterSplitResult::Stay)
+                    }
+                }).unwrap();
+                let (moved_model, stayed_model) = model.iter().partition(|(k, _)| moved_keys.contains(k));
+                check_hashmap_with_model(&tree, &stayed_model);
+                check_hashmap_with_model(&moved, &moved_model);
+                tree.combine_with(&moved).unwrap();
+            }
+            29..=30 => {
+                let len = rng.gen_range(1..=bit_len);
+                let subtree = tree.subtree_with_prefix(&model_slice(key, bit_len, len), &mut 0).unwrap();
+                let expected = model.iter()
+                    .filter(|(k, _)| model_has_prefix(**k, bit_len, key, len))
+                    .map(|(k, v)| (*k, *v))
+                    .collect();
+                check_hashmap_with_model(&subtree, &expected);
+            }
+            _ => {
+                // cut subtree rarely not to empty the tree often
+                let len = rng.gen_range(bit_len / 2..=bit_len).max(1);
+                let slice = tree.hashmap_slice(model_slice(key, bit_len, len), &mut 0).unwrap();
+                let (sliced, rest) = model.iter().partition(|(k, _)| model_has_prefix(**k, bit_len, key, len));
+                check_hashmap_with_model(&slice, &sliced);
+                model = rest;
+            }
+        }
+        check_hashmap_with_model(&tree, &model);
+    }
+}
+
+#[test]
+fn test_hashmap_with_model() {
+    for seed in 0..4 {
+        run_hashmap_model(seed, 8, 400);
+        run_hashmap_model(seed, 32, 400);
+    }
+    run_hashmap_model(100, 1, 100);
+    run_hashmap_model(101, 64, 400);
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelValue(u32);
+
+impl Serializable for ModelValue {
+    fn write_to(&self, cell: &mut BuilderData) -> Result<()> {
+        self.0.write_to(cell)
+    }
+}
+
+impl Deserializable for ModelValue {
+    fn read_from(&mut self, slice: &mut SliceData) -> Result<()> {
+        self.0.read_from(slice)
+    }
+}
+
+impl Augmentation<Grams> for ModelValue {
+    fn aug(&self) -> Result<Grams> {
+        Ok(Grams::from(self.0 as u64))
+    }
+}
+
+mod model_aug {
+    #![allow(dead_code)]
+    use super::*;
+    use crate::{hashmap_subtree_with_prefix, GasConsumer};
+    define_HashmapAugE!(ModelAugHashmap, 32, u32, ModelValue, Grams);
+    impl HashmapSubtree for ModelAugHashmap {
+        fn subtree_with_prefix(&self, prefix: &SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Self> {
+            let mut subtree = hashmap_subtree_with_prefix(self, prefix, gas_consumer)?;
+            subtree.update_root_extra()?;
+            Ok(subtree)
+        }
+    }
+}
+use model_aug::ModelAugHashmap;
+
+// checks augmentation of every node and returns it
+fn check_model_aug_node(cell: &Cell, bit_len: usize) -> Grams {
+    let mut slice = SliceData::load_cell_ref(cell).unwrap();
+    let label = LabelReader::read_label(&mut slice, bit_len).unwrap();
+    let bit_len = bit_len - label.remaining_bits();
+    let extra = Grams::construct_from(&mut slice).unwrap();
+    if bit_len == 0 {
+        let value = ModelValue::construct_from(&mut slice).unwrap();
+        assert_eq!(extra, value.aug().unwrap());
+    } else {
+        let mut expected = check_model_aug_node(&slice.reference(0).unwrap(), bit_len - 1);
+        expected.calc(&check_model_aug_node(&slice.reference(1).unwrap(), bit_len - 1)).unwrap();
+        assert_eq!(extra, expected);
+    }
+    extra
+}
+
+fn check_aug_hashmap_with_model(tree: &ModelAugHashmap, model: &Model) {
+    let mut items = vec![];
+    tree.iterate_with_keys(|key: u32, value| {
+        items.push((key as u64, value.0));
+        Ok(true)
+    }).unwrap();
+    assert_eq!(items, model.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>());
+
+    let mut expected = ModelAugHashmap::default();
+    let mut total = Grams::default();
+    for (key, value) in model {
+        expected.set_augmentable(&(*key as u32), &ModelValue(*value)).unwrap();
+        total.add(&Grams::from(*value as u64)).unwrap();
+    }
+    assert_eq!(tree.data().map(|root| root.repr_hash()), expected.data().map(|root| root.repr_hash()));
+    assert_eq!(tree.root_extra(), &total);
+    if let Some(root) = tree.data() {
+        assert_eq!(check_model_aug_node(root, 32), total);
+    }
+}
+
+fn run_aug_hashmap_model(seed: u64, steps: usize) {
+    let bit_len = 32;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut tree = ModelAugHashmap::default();
+    let mut model = Model::new();
+    for _ in 0..steps {
+        let key = random_model_key(&mut rng, &model, bit_len);
+        let value = rng.gen::<u32>();
+        match rng.gen_range(0..32) {
+            0..=7 => {
+                let prev = tree.get(&(key as u32)).unwrap().map(|value| value.0);
+                assert_eq!(prev, model.insert(key, value));
+                tree.set_augmentable(&(key as u32), &ModelValue(value)).unwrap();
+            }
+            8..=11 => if let Some(key) = model.keys().nth(rng.gen_range(0..model.len().max(1))).copied() {
+                // replace value of existing item, augmentation is recalculated up to the root
+                let (prev, prev_aug) = tree.get_with_aug(&(key as u32)).unwrap().unwrap();
+                assert_eq!(Some(prev.0), model.insert(key, value));
+                assert_eq!(prev_aug, prev.aug().unwrap());
+                let value = ModelValue(value);
+                assert!(tree.set_return_prev(&(key as u32), &value, &value.aug().unwrap()).unwrap().is_some());
+            }
+            12..=15 => if !model.contains_key(&key) {
+                // add new item
+                let value = ModelValue(value);
+                assert!(tree.set_return_prev(&(key as u32), &value, &value.aug().unwrap()).unwrap().is_none());
+                model.insert(key, value.0);
+            }
+            16..=21 => {
+                let prev = tree.get(&(key as u32)).unwrap().map(|value| value.0);
+                assert_eq!(prev, model.remove(&key));
+                tree.del(&(key as u32)).unwrap();
+            }
+            22..=25 => if let Some(common) = model_common_prefix(&model, bit_len) {
+                let len = rng.gen_range(0..=common);
+                let split_key = model_slice(*model.keys().next().unwrap(), bit_len, len);
+                let (mut left, right) = tree.split(&split_key).unwrap();
+                let bit = |key: &u64| (key >> (bit_len - len - 1)) & 1 == 1;
+                check_aug_hashmap_with_model(&left, &model.iter().filter(|(k, _)| !bit(k)).map(|(k, v)| (*k, *v)).collect());
+                check_aug_hashmap_with_model(&right, &model.iter().filter(|(k, _)| bit(k)).map(|(k, v)| (*k, *v)).collect());
+                left.merge(&right, &split_key).unwrap();
+                assert_eq!(left, tree);
+                tree = left;
+            }
+            26..=27 => {
+                let decisions = random_filter_decisions(&mut rng, model.len());
+                let mut keys = model.keys();
+                let mut index = 0;
+                tree.filter(|key, value, aug| {
+                    assert_eq!(Some(&model_key_from_slice(key, bit_len)), keys.next());
+                    assert_eq!(aug, value.aug()?);
+                    index += 1;
+                    Ok(decisions[index - 1].clone())
+                }).unwrap();
+                apply_filter_decisions(&mut model, &decisions);
+            }
+            28 => {
+                // move some items to other tree and merge them back
+                let original = tree.clone();
+                let moved_keys = model.keys().filter(|_| rng.gen_range(0..4) == 0).copied().collect::<Vec<_>>();
+                let moved = tree.filter_with_split(|key, _, _| {
+                    match moved_keys.contains(&model_key_from_slice(key, bit_len)) {
+                        true => Ok(HashmapFilterSplitResult::Move),
+                        false => Ok(HashmapFilterSplitResult::Stay)
+                    }
+                }).unwrap();
+                let (moved_model, stayed_model) = model.iter().partition(|(k, _)| moved_keys.contains(k));
+                check_aug_hashmap_with_model(&tree, &stayed_model);
+                check_aug_hashmap_with_model(&moved, &moved_model);
+                moved.iterate_with_keys_and_aug(|key: u32, value, aug| {
+                    tree.set(&key, &value, &aug)?;
+                    Ok(true)
+                }).unwrap();
+                assert_eq!(tree, original);
+            }
+            _ => {
+                let len = rng.gen_range(1..=bit_len);
+                let subtree = tree.subtree_with_prefix(&model_slice(key, bit_len, len), &mut 0).unwrap();
+                let expected = model.iter()
+                    .filter(|(k, _)| model_has_prefix(**k, bit_len, key, len))
+                    .map(|(k, v)| (*k, *v))
+                    .collect();
+                check_aug_hashmap_with_model(&subtree, &expected);
+            }
+        }
+        check_aug_hashmap_with_model(&tree, &model);
+    }
+}
+
+#[test]
+fn test_aug_hashmap_with_model() {
+    for seed in 0..3 {
+        run_aug_hashmap_model(seed, 400);
+    }
+}
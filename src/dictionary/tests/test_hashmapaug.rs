@@ -23,6 +23,7 @@ use crate::{
 use std::fmt;
 
 #[derive(Eq, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct GramStruct (Grams);
 
 impl GramStruct {
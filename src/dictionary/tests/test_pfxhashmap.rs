@@ -12,6 +12,8 @@
 */
 
 use super::*;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
 
 fn run_prefix_hashmap(table: &[(u8, usize)]) {
     let mut tree = PfxHashmapE::with_bit_len(7);
@@ -74,3 +76,180 @@ fn test_prefix_hashmap() {
         (0b11111011, 7),
     ]);
 }
+
+fn bits_to_slice(bits: &str) -> SliceData {
+    let mut builder = BuilderData::new();
+    for bit in bits.chars() {
+        builder.append_bit_bool(bit == '1').unwrap();
+    }
+    SliceData::load_bitstring(builder).unwrap()
+}
+
+fn slice_to_bits(slice: &SliceData) -> String {
+    (0..slice.remaining_bits())
+        .map(|i| if slice.get_bit(i).unwrap() { '1' } else { '0' })
+        .collect()
+}
+
+fn random_bits(rng: &mut impl rand::Rng, bit_len: usize) -> String {
+    let len = rng.gen_range(1..=bit_len);
+    (0..len).map(|_| if rng.gen::<bool>() { '1' } else { '0' }).collect()
+}
+
+fn value_slice(value: u8) -> SliceData {
+    SliceData::new(vec![value, 0x80])
+}
+
+// checks all the read methods of tree against the model of prefix-free set of keys
+fn check_with_model(tree: &PfxHashmapE, model: &BTreeMap<String, u8>, queries: &[String]) {
+    assert_eq!(tree.len().unwrap(), model.len());
+    assert_eq!(tree.is_empty(), model.is_empty());
+    assert_eq!(tree.count(3).unwrap(), model.len().min(3));
+
+    // keys are prefix-free so the order of bit strings is the order of the tree
+    let items = tree.iter()
+        .map(|item| item.map(|(key, value)| (slice_to_bits(&SliceData::load_bitstring(key).unwrap()), value)))
+        .collect::<Result<Vec<_>>>().unwrap();
+    let expected = model.iter().map(|(key, value)| (key.clone(), value_slice(*value))).collect::<Vec<_>>();
+    assert_eq!(items, expected);
+
+    let items = tree.export_items::<u8>().unwrap().into_iter()
+        .map(|(key, value)| (slice_to_bits(&key), value))
+        .collect::<Vec<_>>();
+    assert_eq!(items, model.iter().map(|(key, value)| (key.clone(), *value)).collect::<Vec<_>>());
+
+    let min = tree.get_min(&mut 0).unwrap().map(|(key, value)| (slice_to_bits(&SliceData::load_bitstring(key).unwrap()), value));
+    assert_eq!(min, model.iter().next().map(|(key, value)| (key.clone(), value_slice(*value))));
+    let max = tree.get_max(&mut 0).unwrap().map(|(key, value)| (slice_to_bits(&SliceData::load_bitstring(key).unwrap()), value));
+    assert_eq!(max, model.iter().next_back().map(|(key, value)| (key.clone(), value_slice(*value))));
+
+    for query in queries {
+        let key = bits_to_slice(query);
+        assert_eq!(tree.get(key.clone()).unwrap(), model.get(query).map(|value| value_slice(*value)), "get {}", query);
+
+        let longest = tree.get_longest_prefix(key.clone()).unwrap()
+            .map(|(prefix, value)| (slice_to_bits(&prefix), value));
+        let expected = model.iter()
+            .filter(|(prefix, _)| query.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, value)| (prefix.clone(), value_slice(*value)));
+        assert_eq!(longest, expected, "longest prefix of {}", query);
+
+        let is_prefix = model.keys().any(|k| k.starts_with(query.as_str()) && k != query);
+        assert_eq!(tree.is_prefix(key.clone()).unwrap(), is_prefix, "is prefix {}", query);
+
+        let subtree = tree.subtree_with_prefix(&key, &mut 0).unwrap();
+        let items = subtree.export_items::<u8>().unwrap().into_iter()
+            .map(|(key, value)| (slice_to_bits(&key), value))
+            .collect::<Vec<_>>();
+        let expected = model.iter()
+            .filter(|(k, _)| k.starts_with(query.as_str()))
+            .map(|(key, value)| (key.clone(), *value))
+            .collect::<Vec<_>>();
+        assert_eq!(items, expected, "subtree with prefix {}", query);
+        if let Some((key, value)) = expected.first() {
+            assert_eq!(subtree.get(bits_to_slice(key)).unwrap(), Some(value_slice(*value)));
+        }
+    }
+}
+
+fn run_random_model(seed: u64, bit_len: usize, steps: usize) {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut tree = PfxHashmapE::with_bit_len(bit_len);
+    let mut model = BTreeMap::<String, u8>::new();
+    for step in 0..steps {
+        let key = match model.keys().nth(rng.gen_range(0..=model.len())) {
+            Some(key) if rng.gen_range(0..3) == 0 => key.clone(),
+            _ => random_bits(&mut rng, bit_len)
+        };
+        if rng.gen_range(0..4) == 0 {
+            let result = tree.remove(bits_to_slice(&key)).unwrap();
+            assert_eq!(result, model.remove(&key).map(value_slice));
+        } else {
+            let value = rng.gen::<u8>();
+            let conflict = model.keys()
+                .any(|k| k != &key && (k.starts_with(key.as_str()) || key.starts_with(k.as_str())));
+            let root = tree.data().cloned();
+            match tree.set(bits_to_slice(&key), &value_slice(value)) {
+                // key with longer or shorter one is not added
+                _ if conflict => assert_eq!(tree.data(), root.as_ref()),
+                result => assert_eq!(result.unwrap(), model.insert(key.clone(), value).map(value_slice)),
+            }
+        }
+        if step % 10 == 0 || step == steps - 1 {
+            let mut queries = model.keys().take(20).cloned().collect::<Vec<_>>();
+            queries.extend(model.keys().filter(|k| k.len() > 1).take(20).map(|k| k[..k.len() / 2].to_string()));
+            queries.extend((0..20).map(|_| random_bits(&mut rng, bit_len)));
+            check_with_model(&tree, &model, &queries);
+        }
+    }
+}
+
+#[test]
+fn test_prefix_hashmap_with_model() {
+    for seed in 0..4 {
+        run_random_model(seed, 4, 200);
+        run_random_model(seed, 12, 600);
+    }
+    run_random_model(10, 40, 600);
+}
+
+#[test]
+fn test_prefix_hashmap_empty_and_root_cases() {
+    let mut tree = PfxHashmapE::with_bit_len(8);
+    let empty = BTreeMap::new();
+    check_with_model(&tree, &empty, &["0".to_string(), "1011".to_string()]);
+    assert!(tree.subtree_with_prefix(&SliceData::default(), &mut 0).unwrap().is_empty());
+    assert!(tree.subtree_with_prefix(&bits_to_slice("101010101"), &mut 0).is_err());
+
+    let mut model = BTreeMap::new();
+    tree.set(bits_to_slice("10110"), &value_slice(1)).unwrap();
+    model.insert("10110".to_string(), 1);
+    tree.set(bits_to_slice("10111010"), &value_slice(2)).unwrap();
+    model.insert("10111010".to_string(), 2);
+    // the whole tree has the common prefix
+    let subtree = tree.subtree_with_prefix(&bits_to_slice("101"), &mut 0).unwrap();
+    assert_eq!(subtree.data(), tree.data());
+    // leaf key is shorter than prefix
+    let subtree = tree.subtree_with_prefix(&bits_to_slice("101101"), &mut 0).unwrap();
+    assert!(subtree.is_empty());
+    assert_eq!(
+        tree.get_longest_prefix(bits_to_slice("1011011")).unwrap(),
+        Some((bits_to_slice("10110"), value_slice(1)))
+    );
+    check_with_model(&tree, &model, &["1".to_string(), "1011".to_string(), "101110".to_string(), "10111010".to_string()]);
+}
+
+// phmn_leaf$0 {n:#} {X:Type} value:X = PfxHashmapNode 0 X;
+// phm_edge#_ {n:#} {X:Type} {l:#} {m:#} label:(HmLabel ~l n) {n = (~m) + l}
+//            node:(PfxHashmapNode m X) = PfxHashmap n X;
+fn check_leaf_layout(root: &Cell, key: &str, bit_len: usize, value: &SliceData) {
+    let mut slice = SliceData::load_cell_ref(root).unwrap();
+    let label = LabelReader::read_label(&mut slice, bit_len).unwrap();
+    assert_eq!(slice_to_bits(&label), key);
+    assert!(!slice.get_next_bit().unwrap(), "leaf tag must follow the label");
+    assert_eq!(&slice, value);
+}
+
+#[test]
+fn test_prefix_hashmap_leaf_layout() {
+    let mut tree = PfxHashmapE::with_bit_len(8);
+    tree.set(bits_to_slice("1011"), &value_slice(1)).unwrap();
+    check_leaf_layout(tree.data().unwrap(), "1011", 8, &value_slice(1));
+    let single = tree.data().cloned();
+    let leaf = PfxHashmapE::make_leaf(&bits_to_slice("1011"), 8, &value_slice(1)).unwrap().into_cell().unwrap();
+    check_leaf_layout(&leaf, "1011", 8, &value_slice(1));
+    assert_eq!(Some(leaf), single);
+
+    // leaf rebuilt by remove
+    tree.set(bits_to_slice("0"), &value_slice(2)).unwrap();
+    tree.remove(bits_to_slice("0")).unwrap();
+    check_leaf_layout(tree.data().unwrap(), "1011", 8, &value_slice(1));
+    assert_eq!(tree.data().cloned(), single);
+
+    // leaf rebuilt by subtree
+    tree.set(bits_to_slice("11"), &value_slice(3)).unwrap();
+    let subtree = tree.subtree_with_prefix(&bits_to_slice("10"), &mut 0).unwrap();
+    check_leaf_layout(subtree.data().unwrap(), "1011", 8, &value_slice(1));
+    assert_eq!(subtree.data().cloned(), single);
+}
@@ -0,0 +1,384 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use std::{collections::{BTreeMap, HashSet}, fmt::Debug};
+
+use crate::{
+    accounts::{Account, ShardAccount},
+    bintree::{BinTree, BinTreeType},
+    config_params::{ConfigParamEnum, ConfigParams},
+    master::{McStateExtra, ShardDescr, ShardHashes},
+    miscellaneous::{IhrPendingSince, ProcessedInfoKey},
+    outbound_messages::OutMsgQueueInfo,
+    shard::{ShardIdent, ShardStateUnsplit},
+    types::{CurrencyCollection, InRefValue},
+    Cell, Deserializable, HashmapType, Result, Serializable, SliceData, UInt256,
+};
+
+#[cfg(test)]
+#[path = "tests/test_diff.rs"]
+mod tests;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+///
+/// Difference of two cell trees at `path` - indexes of references from the root.
+/// `Changed` means the cell itself differs (data, type or references count),
+/// its children are compared further.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CellDiff {
+    pub path: Vec<usize>,
+    pub kind: DiffKind,
+    pub old: Option<Cell>,
+    pub new: Option<Cell>,
+}
+
+/// Compares two cell trees and reports the topmost differing subtrees.
+/// Pairs of cells met several times in the DAG are reported only once.
+pub fn diff_cells(old: &Cell, new: &Cell) -> Vec<CellDiff> {
+    fn walker(
+        old: &Cell,
+        new: &Cell,
+        path: &mut Vec<usize>,
+        visited: &mut HashSet<(UInt256, UInt256)>,
+        diffs: &mut Vec<CellDiff>
+    ) {
+        let (old_hash, new_hash) = (old.repr_hash(), new.repr_hash());
+        if old_hash == new_hash || !visited.insert((old_hash, new_hash)) {
+            return
+        }
+        if old.cell_type() != new.cell_type()
+            || old.data() != new.data()
+            || old.references_count() != new.references_count() {
+            diffs.push(CellDiff {
+                path: path.clone(),
+                kind: DiffKind::Changed,
+                old: Some(old.clone()),
+                new: Some(new.clone()),
+            });
+        }
+        for i in 0..old.references_count().max(new.references_count()) {
+            path.push(i);
+            match (old.reference(i).ok(), new.reference(i).ok()) {
+                (Some(old), Some(new)) => walker(&old, &new, path, visited, diffs),
+                (Some(old), None) => diffs.push(CellDiff {
+                    path: path.clone(), kind: DiffKind::Removed, old: Some(old), new: None
+                }),
+                (None, Some(new)) => diffs.push(CellDiff {
+                    path: path.clone(), kind: DiffKind::Added, old: None, new: Some(new)
+                }),
+                (None, None) => ()
+            }
+            path.pop();
+        }
+    }
+    let mut diffs = Vec::new();
+    walker(old, new, &mut vec![], &mut HashSet::new(), &mut diffs);
+    diffs
+}
+
+///
+/// Difference of one field of a typed structure. The field is a dotted path,
+/// dictionary keys are in square brackets, e.g. `accounts[<id>].balance.grams`.
+/// Values are in debug format, cells are shown by their hashes.
+///
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub kind: DiffKind,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Default)]
+struct FieldDiffs {
+    prefix: String,
+    diffs: Vec<FieldDiff>,
+}
+
+impl FieldDiffs {
+    fn field(&self, name: &str) -> String {
+        match self.prefix.is_empty() {
+            true => name.to_string(),
+            false if name.starts_with('[') => format!("{}{}", self.prefix, name),
+            false => format!("{}.{}", self.prefix, name),
+        }
+    }
+    fn nested(&self, name: &str) -> Self {
+        Self { prefix: self.field(name), diffs: Vec::new() }
+    }
+    fn append(&mut self, other: FieldDiffs) {
+        self.diffs.extend(other.diffs)
+    }
+    fn push<T: Debug>(&mut self, name: &str, old: Option<T>, new: Option<T>) {
+        let kind = match (&old, &new) {
+            (Some(_), Some(_)) => DiffKind::Changed,
+            (Some(_), None) => DiffKind::Removed,
+            (None, Some(_)) => DiffKind::Added,
+            (None, None) => return
+        };
+        self.diffs.push(FieldDiff {
+            field: self.field(name),
+            kind,
+            old: old.map(|value| format!("{:?}", value)),
+            new: new.map(|value| format!("{:?}", value)),
+        })
+    }
+    fn compare<T: Debug + PartialEq>(&mut self, name: &str, old: T, new: T) {
+        if old != new {
+            self.push(name, Some(old), Some(new))
+        }
+    }
+    fn compare_opt<T: Debug + PartialEq>(&mut self, name: &str, old: Option<T>, new: Option<T>) {
+        if old != new {
+            self.push(name, old, new)
+        }
+    }
+    fn compare_hash<T: Serializable>(&mut self, name: &str, old: &T, new: &T) -> Result<()> {
+        self.compare(name, old.serialize()?.repr_hash(), new.serialize()?.repr_hash());
+        Ok(())
+    }
+    fn compare_currency(&mut self, name: &str, old: &CurrencyCollection, new: &CurrencyCollection) -> Result<()> {
+        self.compare(&format!("{}.grams", name), &old.grams, &new.grams);
+        old.other.scan_diff(&new.other, |key: u32, old, new| {
+            self.push(&format!("{}.other[{}]", name, key), old, new);
+            Ok(true)
+        })?;
+        Ok(())
+    }
+}
+
+/// Compares two accounts field by field, libraries are compared per key
+pub fn diff_accounts(old: &Account, new: &Account) -> Result<Vec<FieldDiff>> {
+    let mut diffs = FieldDiffs::default();
+    diff_accounts_impl(&mut diffs, old, new)?;
+    Ok(diffs.diffs)
+}
+
+fn diff_accounts_impl(diffs: &mut FieldDiffs, old: &Account, new: &Account) -> Result<()> {
+    diffs.compare("status", old.status(), new.status());
+    if old.is_none() || new.is_none() {
+        return Ok(())
+    }
+    diffs.compare_opt("addr", old.get_addr(), new.get_addr());
+    if let (Some(old), Some(new)) = (old.storage_info(), new.storage_info()) {
+        diffs.compare("storage_stat.used", old.used(), new.used());
+        diffs.compare("storage_stat.last_paid", old.last_paid(), new.last_paid());
+        diffs.compare_opt("storage_stat.due_payment", old.due_payment(), new.due_payment());
+    }
+    diffs.compare_opt("last_trans_lt", old.last_tr_time(), new.last_tr_time());
+    if let (Some(old), Some(new)) = (old.balance(), new.balance()) {
+        diffs.compare_currency("balance", old, new)?;
+    }
+    diffs.compare_opt("init_code_hash", old.init_code_hash(), new.init_code_hash());
+    diffs.compare_opt("state.frozen_hash", old.frozen_hash(), new.frozen_hash());
+    diffs.compare_opt("state.split_depth", old.split_depth(), new.split_depth());
+    diffs.compare_opt("state.special", old.get_tick_tock(), new.get_tick_tock());
+    diffs.compare_opt("state.code", old.code(), new.code());
+    diffs.compare_opt("state.data", old.data(), new.data());
+    old.libraries().scan_diff(&new.libraries(), |key: UInt256, old, new| {
+        diffs.push(&format!("state.library[{:x}]", key), old, new);
+        Ok(true)
+    })?;
+    Ok(())
+}
+
+/// Compares two shard states field by field, dictionaries (accounts, libraries,
+/// output queues, shards, previous blocks etc) are compared per key,
+/// config of the masterchain state per param
+pub fn diff_shard_states(old: &ShardStateUnsplit, new: &ShardStateUnsplit) -> Result<Vec<FieldDiff>> {
+    let mut diffs = FieldDiffs::default();
+    diffs.compare("global_id", old.global_id(), new.global_id());
+    diffs.compare("shard", old.shard(), new.shard());
+    diffs.compare("seq_no", old.seq_no(), new.seq_no());
+    diffs.compare("vert_seq_no", old.vert_seq_no(), new.vert_seq_no());
+    diffs.compare("gen_time_ms", old.gen_time_ms(), new.gen_time_ms());
+    diffs.compare("gen_lt", old.gen_lt(), new.gen_lt());
+    diffs.compare("min_ref_mc_seqno", old.min_ref_mc_seqno(), new.min_ref_mc_seqno());
+    if old.out_msg_queue_info_cell() != new.out_msg_queue_info_cell() {
+        diff_out_msg_queues_info(&mut diffs, old, new)?;
+    }
+    diffs.compare("before_split", old.before_split(), new.before_split());
+    diffs.compare("overload_history", old.overload_history(), new.overload_history());
+    diffs.compare("underload_history", old.underload_history(), new.underload_history());
+    diffs.compare_currency("total_balance", old.total_balance(), new.total_balance())?;
+    diffs.compare_currency("total_validator_fees", old.total_validator_fees(), new.total_validator_fees())?;
+    old.libraries().scan_diff(new.libraries(), |key: UInt256, old, new| {
+        diffs.push(&format!("libraries[{:x}]", key), old, new);
+        Ok(true)
+    })?;
+    diffs.compare_opt("master_ref", old.master_ref(), new.master_ref());
+    diffs.compare_opt("ref_shard_blocks", old.ref_shard_blocks(), new.ref_shard_blocks());
+    if old.accounts_cell() != new.accounts_cell() {
+        old.read_accounts()?.scan_diff_with_aug(&new.read_accounts()?, |key: UInt256, old, new| {
+            let name = format!("accounts[{:x}]", key);
+            match (old, new) {
+                (Some((old, _)), Some((new, _))) => diff_shard_accounts(&mut diffs, &name, &old, &new)?,
+                (old, new) => diffs.push(&name, old.map(|(old, _)| old), new.map(|(new, _)| new))
+            }
+            Ok(true)
+        })?;
+    }
+    match (old.custom_cell(), new.custom_cell()) {
+        (Some(old_custom), Some(new_custom)) if old_custom != new_custom => {
+            diff_mc_state_extra(&mut diffs, &old.read_custom()?.unwrap_or_default(), &new.read_custom()?.unwrap_or_default())?
+        }
+        (old, new) => diffs.compare_opt("custom", old, new)
+    }
+    Ok(diffs.diffs)
+}
+
+fn diff_shard_accounts(diffs: &mut FieldDiffs, name: &str, old: &ShardAccount, new: &ShardAccount) -> Result<()> {
+    let mut account_diffs = diffs.nested(name);
+    account_diffs.compare("last_trans_hash", old.last_trans_hash(), new.last_trans_hash());
+    account_diffs.compare("last_trans_lt", old.last_trans_lt(), new.last_trans_lt());
+    if old.account_cell() != new.account_cell() {
+        diff_accounts_impl(&mut account_diffs, &old.read_account()?, &new.read_account()?)?;
+    }
+    diffs.append(account_diffs);
+    Ok(())
+}
+
+fn diff_out_msg_queues_info(diffs: &mut FieldDiffs, old: &ShardStateUnsplit, new: &ShardStateUnsplit) -> Result<()> {
+    let (old_queue, old_mesh) = old.read_out_msg_queues_info()?;
+    let (new_queue, new_mesh) = new.read_out_msg_queues_info()?;
+    let mut queue_diffs = diffs.nested("out_msg_queues_info");
+    diff_out_msg_queue_info(&mut queue_diffs, &old_queue, &new_queue)?;
+    old_mesh.scan_diff(&new_mesh, |nw_id: u32, old, new| {
+        let name = format!("mesh[{}]", nw_id);
+        match (old, new) {
+            (Some(InRefValue(old)), Some(InRefValue(new))) => {
+                let mut mesh_diffs = queue_diffs.nested(&name);
+                diff_out_msg_queue_info(&mut mesh_diffs, &old, &new)?;
+                queue_diffs.append(mesh_diffs);
+            }
+            (old, new) => queue_diffs.push(&name, old, new)
+        }
+        Ok(true)
+    })?;
+    diffs.append(queue_diffs);
+    Ok(())
+}
+
+fn diff_out_msg_queue_info(diffs: &mut FieldDiffs, old: &OutMsgQueueInfo, new: &OutMsgQueueInfo) -> Result<()> {
+    old.out_queue().scan_diff_with_aug(new.out_queue(), |key, old, new| {
+        diffs.push(&format!("out_queue[{}:{:016x}:{:x}]", key.workchain_id, key.prefix, key.hash), old, new);
+        Ok(true)
+    })?;
+    old.proc_info().scan_diff(new.proc_info(), |key: ProcessedInfoKey, old, new| {
+        diffs.push(&format!("proc_info[{:016x}:{}]", key.shard, key.mc_seqno), old, new);
+        Ok(true)
+    })?;
+    old.ihr_pending().as_hashmap().scan_diff(&new.ihr_pending().as_hashmap(), |key, old, new| {
+        let old = old.map(|mut slice| IhrPendingSince::construct_from(&mut slice)).transpose()?;
+        let new = new.map(|mut slice| IhrPendingSince::construct_from(&mut slice)).transpose()?;
+        diffs.push(&format!("ihr_pending[{:x}]", key), old, new);
+        Ok(true)
+    })?;
+    Ok(())
+}
+
+fn diff_mc_state_extra(diffs: &mut FieldDiffs, old: &McStateExtra, new: &McStateExtra) -> Result<()> {
+    let mut custom_diffs = diffs.nested("custom");
+    diff_shard_hashes(&mut custom_diffs, &old.shards, &new.shards)?;
+    old.mesh.scan_diff(&new.mesh, |nw_id: u32, old, new| {
+        custom_diffs.push(&format!("mesh[{}]", nw_id), old, new);
+        Ok(true)
+    })?;
+    custom_diffs.compare("validator_info", &old.validator_info, &new.validator_info);
+    old.prev_blocks.scan_diff_with_aug(&new.prev_blocks, |seq_no, old, new| {
+        custom_diffs.push(&format!("prev_blocks[{}]", seq_no), old, new);
+        Ok(true)
+    })?;
+    custom_diffs.compare("after_key_block", old.after_key_block, new.after_key_block);
+    custom_diffs.compare_opt("last_key_block", old.last_key_block.as_ref(), new.last_key_block.as_ref());
+    match (&old.block_create_stats, &new.block_create_stats) {
+        (Some(old), Some(new)) => {
+            old.counters.scan_diff(&new.counters, |key: UInt256, old, new| {
+                custom_diffs.push(&format!("block_create_stats[{:x}]", key), old, new);
+                Ok(true)
+            })?;
+        }
+        (old, new) => custom_diffs.compare_hash("block_create_stats", old, new)?
+    }
+    custom_diffs.compare_currency("global_balance", &old.global_balance, &new.global_balance)?;
+    custom_diffs.compare_hash("state_copyleft_rewards", &old.state_copyleft_rewards, &new.state_copyleft_rewards)?;
+    custom_diffs.compare_hash("validators_stat", &old.validators_stat, &new.validators_stat)?;
+    let mut config_diffs = custom_diffs.nested("config");
+    diff_config_params_impl(&mut config_diffs, &old.config, &new.config)?;
+    custom_diffs.append(config_diffs);
+    diffs.append(custom_diffs);
+    Ok(())
+}
+
+// shards are compared by their descriptions, workchain trees may be split or merged
+fn diff_shard_hashes(diffs: &mut FieldDiffs, old: &ShardHashes, new: &ShardHashes) -> Result<()> {
+    fn read_shards(wc_id: i32, shards: Option<InRefValue<BinTree<ShardDescr>>>) -> Result<BTreeMap<ShardIdent, ShardDescr>> {
+        let mut descrs = BTreeMap::new();
+        if let Some(InRefValue(shards)) = shards {
+            shards.iterate(|prefix, descr| {
+                descrs.insert(ShardIdent::with_prefix_slice(wc_id, prefix)?, descr);
+                Ok(true)
+            })?;
+        }
+        Ok(descrs)
+    }
+    old.scan_diff(new, |wc_id: i32, old, new| {
+        let mut old = read_shards(wc_id, old)?;
+        for (shard, new) in read_shards(wc_id, new)? {
+            diffs.compare_opt(&format!("shards[{}]", shard), old.remove(&shard), Some(new));
+        }
+        for (shard, old) in old {
+            diffs.push(&format!("shards[{}]", shard), Some(old), None);
+        }
+        Ok(true)
+    })?;
+    Ok(())
+}
+
+/// Compares two configs per param. Params are shown parsed or by cell hash if they can't be parsed.
+pub fn diff_config_params(old: &ConfigParams, new: &ConfigParams) -> Result<Vec<FieldDiff>> {
+    let mut diffs = FieldDiffs::default();
+    diff_config_params_impl(&mut diffs, old, new)?;
+    Ok(diffs.diffs)
+}
+
+fn diff_config_params_impl(diffs: &mut FieldDiffs, old: &ConfigParams, new: &ConfigParams) -> Result<()> {
+    fn read_param(index: u32, slice: Option<SliceData>) -> Option<String> {
+        let cell = slice?.reference_opt(0)?;
+        match ConfigParamEnum::construct_from_cell_and_number(cell.clone(), index) {
+            Ok(param) => Some(format!("{:?}", param)),
+            Err(_) => Some(format!("{:x}", cell.repr_hash()))
+        }
+    }
+    diffs.compare("config_addr", &old.config_addr, &new.config_addr);
+    old.config_params.scan_diff(&new.config_params, |mut key, old, new| {
+        let index = key.get_next_u32()?;
+        let (old, new) = (read_param(index, old), read_param(index, new));
+        // values are strings already, so push them as is
+        let kind = match (&old, &new) {
+            (Some(_), Some(_)) => DiffKind::Changed,
+            (Some(_), None) => DiffKind::Removed,
+            _ => DiffKind::Added
+        };
+        diffs.diffs.push(FieldDiff { field: diffs.field(&format!("param{}", index)), kind, old, new });
+        Ok(true)
+    })?;
+    Ok(())
+}
@@ -46,6 +46,7 @@ by a cell reference.
 /// 
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntermediateAddress {
     Regular(IntermediateAddressRegular),
     Simple(IntermediateAddressSimple),
@@ -172,6 +173,7 @@ impl Deserializable for IntermediateAddress{
 /// 
 
 #[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntermediateAddressRegular {
     use_dest_bits: u8,
 }
@@ -239,6 +241,7 @@ impl Deserializable for IntermediateAddressRegular{
 
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntermediateAddressSimple{
     pub workchain_id: i8,
     pub addr_pfx: u64,
@@ -292,6 +295,7 @@ impl Deserializable for IntermediateAddressSimple{
 
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntermediateAddressExt{
     pub workchain_id: i32,
     pub addr_pfx: u64,
@@ -344,6 +348,7 @@ const MSG_ENVELOPE_TAG_2: usize = 0x5;
 // msg_envelope#4
 //   cur_addr:IntnveloMsgEnvelope; 
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct MsgEnvelope {
     cur_addr: IntermediateAddress,
     next_addr: IntermediateAddress,
@@ -62,4 +62,7 @@ pub enum BlockError {
     UnsupportedSerdeOptions(String, usize),
     #[error("Mismatched serde options: {0} exp={1} real={2}")]
     MismatchedSerdeOptions(String, usize, usize),
+    /// BOC exceeds a limit set for the reader.
+    #[error("BOC {0} {1} exceeds the limit {2}")]
+    BocLimitExceeded(String, usize, usize),
 }
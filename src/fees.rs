@@ -0,0 +1,248 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{
+    accounts::{Account, StorageUsedShort},
+    config_params::{
+        ConfigParams, FundamentalSmcAddresses, GasLimitsPrices, GlobalCapabilities,
+        MsgForwardPrices, StoragePrices,
+    },
+    messages::{CommonMsgInfo, Message, MsgAddressInt},
+    error::BlockError, fail, Cell, Deserializable, Result, Serializable, UInt256,
+};
+
+#[cfg(test)]
+#[path = "tests/test_fees.rs"]
+mod tests;
+
+///
+/// Fees of forwarding one message
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MsgFwdFees {
+    /// full forward fee
+    pub fwd_fee: u128,
+    /// fee for instant hypercube routing (zero if it is disabled)
+    pub ihr_fee: u128,
+    /// part of `fwd_fee` collected by the sender's shard
+    pub mine_fee: u128,
+    /// part of `fwd_fee` stored in the message header for the next hops
+    pub remaining_fee: u128,
+}
+
+///
+/// Fees breakdown of processing a message by an account
+///
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FeesBreakdown {
+    /// storage fee due since the last payment including debt
+    pub storage_fee: u128,
+    /// fee for importing an external inbound message
+    pub import_fee: u128,
+    /// forward fee put into the header of an internal message
+    pub fwd_fee: u128,
+    /// IHR fee of an internal message
+    pub ihr_fee: u128,
+    /// fee collected in the action phase for sending a message
+    pub action_fee: u128,
+}
+
+impl FeesBreakdown {
+    pub fn total(&self) -> u128 {
+        self.storage_fee + self.import_fee + self.fwd_fee + self.ihr_fee + self.action_fee
+    }
+}
+
+///
+/// Calculates fees the same way the node does using prices from config params
+/// 18 (storage), 20-21 (gas) and 24-25 (forwarding).
+/// If `CapFeeInGasUnits` is set, storage and forward prices are in gas units
+/// and resulting fees are converted with gas prices.
+///
+#[derive(Clone, Debug)]
+pub struct FeesCalculator {
+    storage_prices: Vec<StoragePrices>,
+    gas_prices_mc: GasLimitsPrices,
+    gas_prices_wc: GasLimitsPrices,
+    fwd_prices_mc: MsgForwardPrices,
+    fwd_prices_wc: MsgForwardPrices,
+    config_addr: UInt256,
+    special_accounts: FundamentalSmcAddresses,
+    fee_in_gas_units: bool,
+}
+
+impl FeesCalculator {
+    pub fn with_config(config: &ConfigParams) -> Result<Self> {
+        Ok(Self {
+            storage_prices: config.storage_prices()?.prices()?,
+            gas_prices_mc: config.gas_prices(true)?,
+            gas_prices_wc: config.gas_prices(false)?,
+            fwd_prices_mc: config.fwd_prices(true)?,
+            fwd_prices_wc: config.fwd_prices(false)?,
+            config_addr: config.config_address().unwrap_or_default(),
+            special_accounts: config.fundamental_smc_addr().unwrap_or_default(),
+            fee_in_gas_units: config.has_capability(GlobalCapabilities::CapFeeInGasUnits),
+        })
+    }
+
+    pub fn gas_prices(&self, is_masterchain: bool) -> &GasLimitsPrices {
+        if is_masterchain { &self.gas_prices_mc } else { &self.gas_prices_wc }
+    }
+
+    pub fn fwd_prices(&self, is_masterchain: bool) -> &MsgForwardPrices {
+        if is_masterchain { &self.fwd_prices_mc } else { &self.fwd_prices_wc }
+    }
+
+    /// Checks if account is free of storage fees
+    pub fn is_special_account(&self, address: &MsgAddressInt) -> Result<bool> {
+        if !address.is_masterchain() {
+            return Ok(false)
+        }
+        let account_id = UInt256::construct_from(&mut address.address())?;
+        Ok(account_id == self.config_addr || self.special_accounts.get(&account_id)?.is_some())
+    }
+
+    /// Calculates storage fee of `cells` and `bits` for the period from `last_paid` to `now`.
+    /// Prices are summed over all the intervals of the config param 18.
+    pub fn calc_storage_fee(
+        &self,
+        cells: u64,
+        bits: u64,
+        last_paid: u32,
+        now: u32,
+        is_masterchain: bool
+    ) -> u128 {
+        let (cells, bits) = (cells as u128, bits as u128);
+        let mut fee = 0u128;
+        let mut paid = last_paid;
+        if last_paid != 0 && now > last_paid {
+            for (i, prices) in self.storage_prices.iter().enumerate() {
+                let end = match self.storage_prices.get(i + 1) {
+                    Some(next) => next.utime_since.min(now),
+                    None => now
+                };
+                let begin = prices.utime_since.max(paid);
+                if end <= begin {
+                    continue
+                }
+                let delta = (end - begin) as u128;
+                fee += if is_masterchain {
+                    (cells * prices.mc_cell_price_ps as u128 + bits * prices.mc_bit_price_ps as u128) * delta
+                } else {
+                    (cells * prices.cell_price_ps as u128 + bits * prices.bit_price_ps as u128) * delta
+                };
+                paid = end;
+            }
+        }
+        // prices are shifted by 16 bits, round up
+        self.to_tokens((fee + 0xffff) >> 16, is_masterchain)
+    }
+
+    /// Calculates storage fee of the account accrued up to `now` plus its due payment
+    pub fn calc_account_storage_fee(&self, account: &Account, now: u32) -> Result<u128> {
+        let (addr, info) = match (account.get_addr(), account.storage_info()) {
+            (Some(addr), Some(info)) => (addr, info),
+            _ => return Ok(0)
+        };
+        let due = info.due_payment().map_or(0, |due| due.as_u128());
+        if self.is_special_account(addr)? {
+            return Ok(due)
+        }
+        let fee = self.calc_storage_fee(
+            info.used().cells(),
+            info.used().bits(),
+            info.last_paid(),
+            now,
+            addr.is_masterchain()
+        );
+        Ok(fee + due)
+    }
+
+    /// Calculates gas fee of the compute phase
+    pub fn calc_gas_fee(&self, gas_used: u64, is_masterchain: bool) -> u128 {
+        self.gas_prices(is_masterchain).calc_gas_fee(gas_used)
+    }
+
+    /// Calculates full forward fee of the message cell. The root cell is paid by lump price.
+    pub fn calc_fwd_fee(&self, msg_cell: &Cell, is_masterchain: bool) -> u128 {
+        let mut storage = StorageUsedShort::default();
+        storage.append(msg_cell);
+        let bits = (storage.bits() - msg_cell.bit_length() as u64) as u128;
+        let cells = (storage.cells() - 1) as u128;
+        let prices = self.fwd_prices(is_masterchain);
+        let fee = prices.lump_price as u128
+            + ((cells * prices.cell_price as u128 + bits * prices.bit_price as u128 + 0xffff) >> 16);
+        self.to_tokens(fee, is_masterchain)
+    }
+
+    /// Calculates fee of importing an external inbound message
+    pub fn calc_import_fee(&self, msg: &Message) -> Result<u128> {
+        match msg.header() {
+            CommonMsgInfo::ExtInMsgInfo(header) => {
+                Ok(self.calc_fwd_fee(&msg.serialize()?, header.dst.is_masterchain()))
+            }
+            _ => fail!(BlockError::InvalidArg("Message is not an external inbound one".to_string()))
+        }
+    }
+
+    /// Calculates fees of sending an outbound message. For external messages
+    /// the whole forward fee is collected by the sender.
+    pub fn calc_msg_fwd_fees(&self, msg: &Message) -> Result<MsgFwdFees> {
+        let is_masterchain = msg.is_masterchain();
+        let fwd_fee = self.calc_fwd_fee(&msg.serialize()?, is_masterchain);
+        match msg.header() {
+            CommonMsgInfo::IntMsgInfo(header) => {
+                let prices = self.fwd_prices(is_masterchain);
+                let ihr_fee = match header.ihr_disabled {
+                    true => 0,
+                    false => (fwd_fee * prices.ihr_price_factor as u128) >> 16
+                };
+                let mine_fee = (fwd_fee * prices.first_frac as u128) >> 16;
+                Ok(MsgFwdFees { fwd_fee, ihr_fee, mine_fee, remaining_fee: fwd_fee - mine_fee })
+            }
+            CommonMsgInfo::ExtOutMsgInfo(_) => {
+                Ok(MsgFwdFees { fwd_fee, ihr_fee: 0, mine_fee: fwd_fee, remaining_fee: 0 })
+            }
+            CommonMsgInfo::ExtInMsgInfo(_) => {
+                fail!(BlockError::InvalidArg("External inbound message can't be sent".to_string()))
+            }
+        }
+    }
+
+    /// Calculates fees of processing the message by the account at `now`:
+    /// storage fee of the account, import fee of an external inbound message or
+    /// forward and action fees of a message sent by the account.
+    /// Forward fees of an internal inbound message are paid by its sender.
+    pub fn calc_fees(&self, account: &Account, msg: &Message, now: u32) -> Result<FeesBreakdown> {
+        let mut fees = FeesBreakdown {
+            storage_fee: self.calc_account_storage_fee(account, now)?,
+            ..Default::default()
+        };
+        if msg.is_inbound_external() {
+            fees.import_fee = self.calc_import_fee(msg)?;
+        } else if msg.src_ref().is_some() && msg.src_ref() == account.get_addr() {
+            let fwd_fees = self.calc_msg_fwd_fees(msg)?;
+            fees.fwd_fee = fwd_fees.remaining_fee;
+            fees.ihr_fee = fwd_fees.ihr_fee;
+            fees.action_fee = fwd_fees.mine_fee;
+        }
+        Ok(fees)
+    }
+
+    fn to_tokens(&self, fee: u128, is_masterchain: bool) -> u128 {
+        match self.fee_in_gas_units {
+            true => self.calc_gas_fee(fee.min(u64::MAX as u128) as u64, is_masterchain),
+            false => fee
+        }
+    }
+}
@@ -54,6 +54,7 @@ macro_rules! write_tag {
 
 //3.2.7. Augmentation of InMsgDescr
 #[derive(Default, PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ImportFees {
     pub fees_collected: Grams,
     pub value_imported: CurrencyCollection,
@@ -107,6 +108,7 @@ const COMMON_MSG_TAG: u8 = 0b1000_0000;
 /// blockchain spec 3.2.2. Descriptor of an inbound message.
 ///
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum InMsg {
     #[default]
     None,
@@ -503,6 +505,7 @@ impl Deserializable for InMsg {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InMsgExternal {
     msg: ChildCell<CommonMessage>,
     transaction: ChildCell<Transaction>,
@@ -560,6 +563,7 @@ impl Deserializable for InMsgExternal {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InMsgIHR {
     msg: ChildCell<CommonMessage>,
     transaction: ChildCell<Transaction>,
@@ -642,6 +646,7 @@ impl Deserializable for InMsgIHR {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InMsgFinal {
     in_msg: ChildCell<MsgEnvelope>,
     transaction: ChildCell<Transaction>,
@@ -708,6 +713,7 @@ impl Deserializable for InMsgFinal {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InMsgTransit {
     in_msg: ChildCell<MsgEnvelope>,
     out_msg: ChildCell<MsgEnvelope>,
@@ -774,6 +780,7 @@ impl Deserializable for InMsgTransit {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InMsgDiscardedFinal {
     in_msg: ChildCell<MsgEnvelope>,
     pub transaction_id: u64,
@@ -840,6 +847,7 @@ impl Deserializable for InMsgDiscardedFinal {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InMsgDiscardedTransit {
     in_msg: ChildCell<MsgEnvelope>,
     transaction_id: u64,
@@ -0,0 +1,609 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+//! Canonical JSON representation of blockchain structures.
+//!
+//! The representation is versioned: every document produced by [`to_json_value`]
+//! is wrapped into `{"version": JSON_FORMAT_VERSION, "data": ...}`.
+//! Structures are mapped field by field; leaf TL-B values use compact forms:
+//! * `UInt256` - 64 hex chars;
+//! * `Grams`, `VarUInteger32` - decimal string; `VarUInteger3/7`, `NumberN` - number;
+//! * `CurrencyCollection` - `{"grams": "..", "other": {"<id>": ".."}}`;
+//! * addresses - the same string form as `Display`/`FromStr` (`wc:hex`, `pfx:wc:hex`, `:hex`);
+//! * `SliceData` without references - hex bitstring with completion tag,
+//!   otherwise `{"boc": "<base64>"}`;
+//! * `Cell` - base64 BOC;
+//! * `ChildCell<T>`, `InRefValue<T>` - the JSON of `T` itself, `null` for unset child cell;
+//! * dictionaries - map of the key bitstring (the same hex form as `SliceData`) to the JSON
+//!   of the value, items of augmented dictionaries are `{"value": .., "aug": ..}`;
+//!   with [`JsonOptions::raw_dictionaries`] dictionaries are written as `{"boc": "<base64>"}`
+//!   of the root cell instead, both forms are accepted on reading;
+//! * `HashmapE` - `{"bit_len": .., "items": ..}` where `items` is the dictionary of
+//!   `SliceData` values;
+//! * structures without own mapping yet (e.g. `BinTree`, `ValidatorDescr`) - the same form
+//!   as `SliceData` of their serialized cell.
+//!
+//! Only structures serialized with `SERDE_OPTS_EMPTY` are supported by this version.
+
+use crate::{
+    base64_decode, base64_encode, error::BlockError, fail, read_single_root_boc, write_boc,
+    Augmentable, Augmentation, BinTree, Cell, CellType, ChildCell, ConnectedNwConfig,
+    CryptoSignature, CurrencyCollection, Deserializable, ExtraCurrencyCollection, Grams,
+    HashmapAugType, HashmapE, HashmapType, InRefValue, MsgAddress, MsgAddressExt,
+    MsgAddressInt, MsgAddressIntOrNone, Number12, Number13, Number16, Number32, Number5,
+    Number8, Number9, Result, Serializable, SliceData, TopBlockDescr, UInt256,
+    ValidatorDescr, ValidatorSignedTempKey, VarUInteger3, VarUInteger32, VarUInteger7,
+    WorkchainDescr, SERDE_OPTS_EMPTY,
+};
+use serde::{
+    de::{self, DeserializeOwned, MapAccess, Visitor}, ser::{self, SerializeMap, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{collections::BTreeMap, fmt, marker::PhantomData, str::FromStr};
+
+#[doc(hidden)]
+pub use serde;
+
+#[cfg(test)]
+#[path = "tests/test_json.rs"]
+mod tests;
+
+pub const JSON_FORMAT_VERSION: u32 = 2;
+
+/// Options of writing JSON representation
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct JsonOptions {
+    /// write dictionaries as base64 BOC of their root cell instead of maps
+    pub raw_dictionaries: bool,
+}
+
+thread_local! {
+    static RAW_DICTIONARIES: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+struct OptionsGuard {
+    raw_dictionaries: bool,
+}
+
+impl OptionsGuard {
+    fn set(options: &JsonOptions) -> Self {
+        let raw_dictionaries = RAW_DICTIONARIES.with(|raw| raw.replace(options.raw_dictionaries));
+        Self { raw_dictionaries }
+    }
+}
+
+impl Drop for OptionsGuard {
+    fn drop(&mut self) {
+        RAW_DICTIONARIES.with(|raw| raw.set(self.raw_dictionaries));
+    }
+}
+
+#[derive(Serialize)]
+struct VersionedRef<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+/// Converts structure to versioned JSON value
+pub fn to_json_value<T: Serialize>(value: &T) -> Result<serde_json::Value> {
+    to_json_value_with_options(value, &JsonOptions::default())
+}
+
+/// Converts structure to versioned JSON value with given options
+pub fn to_json_value_with_options<T: Serialize>(
+    value: &T,
+    options: &JsonOptions
+) -> Result<serde_json::Value> {
+    let _guard = OptionsGuard::set(options);
+    let versioned = VersionedRef { version: JSON_FORMAT_VERSION, data: value };
+    Ok(serde_json::to_value(versioned)?)
+}
+
+/// Converts structure to versioned JSON string
+pub fn to_json_string<T: Serialize>(value: &T, pretty: bool) -> Result<String> {
+    to_json_string_with_options(value, pretty, &JsonOptions::default())
+}
+
+/// Converts structure to versioned JSON string with given options
+pub fn to_json_string_with_options<T: Serialize>(
+    value: &T,
+    pretty: bool,
+    options: &JsonOptions
+) -> Result<String> {
+    let _guard = OptionsGuard::set(options);
+    let versioned = VersionedRef { version: JSON_FORMAT_VERSION, data: value };
+    if pretty {
+        Ok(serde_json::to_string_pretty(&versioned)?)
+    } else {
+        Ok(serde_json::to_string(&versioned)?)
+    }
+}
+
+/// Restores structure from versioned JSON value
+pub fn from_json_value<T: DeserializeOwned>(mut value: serde_json::Value) -> Result<T> {
+    let version = value.get("version").and_then(|version| version.as_u64());
+    match version {
+        Some(version) if version == JSON_FORMAT_VERSION as u64 => (),
+        Some(version) => fail!(
+            BlockError::InvalidData(format!("unsupported JSON format version {}", version))
+        ),
+        None => fail!(BlockError::InvalidData("JSON format version is not set".to_string()))
+    }
+    match value.get_mut("data") {
+        Some(data) => Ok(serde_json::from_value(data.take())?),
+        None => fail!(BlockError::InvalidData("JSON has no data field".to_string()))
+    }
+}
+
+/// Restores structure from versioned JSON string
+pub fn from_json_str<T: DeserializeOwned>(json: &str) -> Result<T> {
+    from_json_value(serde_json::from_str(json)?)
+}
+
+fn check_serde_opts<T, E: ser::Error>(opts: u8) -> std::result::Result<(), E> {
+    if opts != SERDE_OPTS_EMPTY {
+        let err = BlockError::UnsupportedSerdeOptions(
+            std::any::type_name::<T>().to_string(), opts as usize
+        );
+        return Err(E::custom(err))
+    }
+    Ok(())
+}
+
+impl Serialize for UInt256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.as_hex_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for UInt256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        UInt256::from_str(&string).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let boc = write_boc(self).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&base64_encode(boc))
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        let boc = base64_decode(string).map_err(de::Error::custom)?;
+        read_single_root_boc(boc).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SliceDataRepr {
+    Bitstring(String),
+    Cell { boc: Cell },
+}
+
+impl Serialize for SliceData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if self.remaining_references() == 0 && self.cell_type() == CellType::Ordinary {
+            serializer.serialize_str(&self.as_hex_string())
+        } else {
+            let mut repr = serializer.serialize_struct("SliceData", 1)?;
+            repr.serialize_field("boc", &self.clone().into_cell())?;
+            repr.end()
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SliceData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match SliceDataRepr::deserialize(deserializer)? {
+            SliceDataRepr::Bitstring(string) => SliceData::from_string(&string),
+            SliceDataRepr::Cell { boc } => SliceData::load_cell(boc),
+        }.map_err(de::Error::custom)
+    }
+}
+
+macro_rules! define_json_as_string {
+    ( $( $varname:ty ),* ) => {
+        $(
+            impl Serialize for $varname {
+                fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_str(&self.to_string())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $varname {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                    let string = String::deserialize(deserializer)?;
+                    <$varname>::from_str(&string).map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+define_json_as_string!(Grams, VarUInteger32, MsgAddressInt, MsgAddressExt, MsgAddress);
+
+macro_rules! define_json_as_number {
+    ( $( $varname:ty: $inner:ty ),* ) => {
+        $(
+            impl Serialize for $varname {
+                fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_u64(self.inner() as u64)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $varname {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                    <$varname>::new(<$inner>::deserialize(deserializer)?).map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+define_json_as_number!(VarUInteger3: u32, VarUInteger7: u64);
+
+macro_rules! define_json_for_number_n {
+    ( $( $varname:ty ),* ) => {
+        $(
+            impl Serialize for $varname {
+                fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_u32(self.as_u32())
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $varname {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                    <$varname>::new(u32::deserialize(deserializer)?).map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+define_json_for_number_n!(Number5, Number8, Number9, Number12, Number13, Number16, Number32);
+
+#[derive(Serialize, Deserialize)]
+struct CurrencyCollectionRepr {
+    grams: Grams,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    other: BTreeMap<u32, VarUInteger32>,
+}
+
+impl Serialize for CurrencyCollection {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        check_serde_opts::<ExtraCurrencyCollection, _>(self.other.serde_opts())?;
+        let mut other = BTreeMap::new();
+        self.other.iterate_with_keys(|key: u32, value| {
+            other.insert(key, value);
+            Ok(true)
+        }).map_err(ser::Error::custom)?;
+        CurrencyCollectionRepr { grams: self.grams, other }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCollection {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let repr = CurrencyCollectionRepr::deserialize(deserializer)?;
+        let mut result = CurrencyCollection::from_grams(repr.grams);
+        for (key, value) in repr.other {
+            result.set_other_ex(key, &value).map_err(de::Error::custom)?;
+        }
+        Ok(result)
+    }
+}
+
+impl Serialize for MsgAddressIntOrNone {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            MsgAddressIntOrNone::None => serializer.serialize_none(),
+            MsgAddressIntOrNone::Some(addr) => serializer.serialize_some(addr),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MsgAddressIntOrNone {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match Option::<MsgAddressInt>::deserialize(deserializer)? {
+            Some(addr) => Ok(MsgAddressIntOrNone::Some(addr)),
+            None => Ok(MsgAddressIntOrNone::None),
+        }
+    }
+}
+
+impl<T> Serialize for ChildCell<T>
+where
+    T: Serializable + Deserializable + Serialize
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        check_serde_opts::<Self, _>(self.serde_opts())?;
+        if self.empty() {
+            return serializer.serialize_none()
+        }
+        let value = self.read_struct().map_err(ser::Error::custom)?;
+        serializer.serialize_some(&value)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ChildCell<T>
+where
+    T: Serializable + Deserializable + DeserializeOwned
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        match Option::<T>::deserialize(deserializer)? {
+            Some(value) => ChildCell::with_struct(&value).map_err(de::Error::custom),
+            None => Ok(ChildCell::default())
+        }
+    }
+}
+
+struct HashmapEItems<'a>(&'a HashmapE);
+
+impl Serialize for HashmapEItems<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serialize_dictionary(self.0, SERDE_OPTS_EMPTY, |leaf| Ok(leaf.clone()), serializer)
+    }
+}
+
+#[derive(Deserialize)]
+struct HashmapERepr {
+    bit_len: usize,
+    items: DictionaryRepr<SliceData>,
+}
+
+impl Serialize for HashmapE {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut repr = serializer.serialize_struct("HashmapE", 2)?;
+        repr.serialize_field("bit_len", &self.bit_len())?;
+        repr.serialize_field("items", &HashmapEItems(self))?;
+        repr.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for HashmapE {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let repr = HashmapERepr::deserialize(deserializer)?;
+        match repr.items {
+            DictionaryRepr::Boc(root) => Ok(HashmapE::with_hashmap(repr.bit_len, root)),
+            DictionaryRepr::Items(items) => {
+                let mut map = HashmapE::with_bit_len(repr.bit_len);
+                for (key, value) in items {
+                    map.set(key, &value).map_err(de::Error::custom)?;
+                }
+                Ok(map)
+            }
+        }
+    }
+}
+
+impl<X> Serialize for InRefValue<X>
+where
+    X: Serializable + Deserializable + Serialize
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        Serialize::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, X> Deserialize<'de> for InRefValue<X>
+where
+    X: Serializable + Deserializable + DeserializeOwned
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Ok(InRefValue(X::deserialize(deserializer)?))
+    }
+}
+
+fn serialize_as_slice<T: Serializable, S: Serializer>(
+    value: &T,
+    serializer: S
+) -> std::result::Result<S::Ok, S::Error> {
+    let cell = Serializable::serialize(value).map_err(ser::Error::custom)?;
+    let slice = SliceData::load_cell(cell).map_err(ser::Error::custom)?;
+    Serialize::serialize(&slice, serializer)
+}
+
+fn deserialize_from_slice<'de, T: Deserializable, D: Deserializer<'de>>(
+    deserializer: D
+) -> std::result::Result<T, D::Error> {
+    let mut slice = SliceData::deserialize(deserializer)?;
+    T::construct_from(&mut slice).map_err(de::Error::custom)
+}
+
+macro_rules! define_json_as_slice {
+    ( $( $varname:ty ),* ) => {
+        $(
+            impl Serialize for $varname {
+                fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                    serialize_as_slice(self, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $varname {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                    deserialize_from_slice(deserializer)
+                }
+            }
+        )*
+    };
+}
+
+define_json_as_slice!(
+    ConnectedNwConfig, CryptoSignature, TopBlockDescr, ValidatorDescr, ValidatorSignedTempKey,
+    WorkchainDescr
+);
+
+impl<X> Serialize for BinTree<X>
+where
+    X: Default + Serializable + Deserializable
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serialize_as_slice(self, serializer)
+    }
+}
+
+impl<'de, X> Deserialize<'de> for BinTree<X>
+where
+    X: Default + Serializable + Deserializable
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserialize_from_slice(deserializer)
+    }
+}
+
+/// Item of augmented dictionary
+#[doc(hidden)]
+#[derive(Serialize, Deserialize)]
+pub struct AugItem<X, Y> {
+    pub value: X,
+    pub aug: Y,
+}
+
+enum DictionaryRepr<V> {
+    Boc(Option<Cell>),
+    Items(Vec<(SliceData, V)>),
+}
+
+struct DictionaryVisitor<V>(PhantomData<V>);
+
+impl<'de, V: DeserializeOwned> Visitor<'de> for DictionaryVisitor<V> {
+    type Value = DictionaryRepr<V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("map of dictionary items or {\"boc\": \"<base64>\"}")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "boc" {
+                let root = map.next_value::<Cell>()?;
+                if !items.is_empty() || map.next_key::<String>()?.is_some() {
+                    return Err(de::Error::custom("dictionary BOC is mixed with items"))
+                }
+                return Ok(DictionaryRepr::Boc(Some(root)))
+            }
+            let key = SliceData::from_string(&key).map_err(de::Error::custom)?;
+            items.push((key, map.next_value::<V>()?));
+        }
+        Ok(DictionaryRepr::Items(items))
+    }
+}
+
+impl<'de, V: DeserializeOwned> Deserialize<'de> for DictionaryRepr<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_map(DictionaryVisitor(PhantomData))
+    }
+}
+
+/// Writes dictionary items read from leaves by `read_value` as a map
+/// or the root cell if raw dictionaries are requested
+#[doc(hidden)]
+pub fn serialize_dictionary<T, V, F, S>(
+    dict: &T,
+    opts: u8,
+    mut read_value: F,
+    serializer: S
+) -> std::result::Result<S::Ok, S::Error>
+where
+    T: HashmapType,
+    V: Serialize,
+    F: FnMut(&mut SliceData) -> Result<V>,
+    S: Serializer
+{
+    check_serde_opts::<T, _>(opts)?;
+    let raw_dictionaries = RAW_DICTIONARIES.with(|raw| raw.get());
+    if raw_dictionaries && dict.data().is_some() {
+        let mut repr = serializer.serialize_struct("Dictionary", 1)?;
+        repr.serialize_field("boc", &dict.data())?;
+        return repr.end()
+    }
+    let mut map = serializer.serialize_map(None)?;
+    let mut error = None;
+    dict.iterate_slices(|key, mut leaf| {
+        let value = read_value(&mut leaf)?;
+        match map.serialize_entry(&key.as_hex_string(), &value) {
+            Ok(()) => Ok(true),
+            Err(err) => {
+                error = Some(err);
+                Ok(false)
+            }
+        }
+    }).map_err(ser::Error::custom)?;
+    match error {
+        Some(err) => Err(err),
+        None => map.end()
+    }
+}
+
+/// Restores root cell of dictionary with `bit_len` keys from map of items or BOC
+#[doc(hidden)]
+pub fn deserialize_dictionary<'de, X, D>(
+    bit_len: usize,
+    deserializer: D
+) -> std::result::Result<Option<Cell>, D::Error>
+where
+    X: Serializable + DeserializeOwned,
+    D: Deserializer<'de>
+{
+    match DictionaryRepr::<X>::deserialize(deserializer)? {
+        DictionaryRepr::Boc(root) => Ok(root),
+        DictionaryRepr::Items(items) => {
+            let mut map = HashmapE::with_bit_len(bit_len);
+            for (key, value) in items {
+                let value = value.write_to_new_cell().map_err(de::Error::custom)?;
+                map.set_builder(key, &value).map_err(de::Error::custom)?;
+            }
+            Ok(map.data().cloned())
+        }
+    }
+}
+
+/// Restores augmented dictionary from map of items or BOC
+#[doc(hidden)]
+pub fn deserialize_aug_dictionary<'de, T, K, X, Y, D>(
+    deserializer: D
+) -> std::result::Result<T, D::Error>
+where
+    T: HashmapAugType<K, X, Y> + Default,
+    K: Serializable + Deserializable,
+    X: Serializable + Deserializable + Augmentation<Y> + DeserializeOwned,
+    Y: Augmentable + DeserializeOwned,
+    D: Deserializer<'de>
+{
+    let mut dict = T::default();
+    match DictionaryRepr::<AugItem<X, Y>>::deserialize(deserializer)? {
+        DictionaryRepr::Boc(root) => {
+            *dict.data_mut() = root;
+            dict.update_root_extra().map_err(de::Error::custom)?;
+        }
+        DictionaryRepr::Items(items) => {
+            for (key, AugItem { value, aug }) in items {
+                let expected = value.aug().map_err(de::Error::custom)?;
+                let aug_cell = Serializable::serialize(&aug).map_err(de::Error::custom)?;
+                if Serializable::serialize(&expected).map_err(de::Error::custom)? != aug_cell {
+                    return Err(de::Error::custom(format!(
+                        "augmentation of item {} doesn't match its value", key.as_hex_string()
+                    )))
+                }
+                let value = value.write_to_new_cell().map_err(de::Error::custom)?;
+                dict.set_builder_serialized(key, &value, &expected).map_err(de::Error::custom)?;
+            }
+        }
+    }
+    Ok(dict)
+}
@@ -93,6 +93,18 @@ pub use self::signature::*;
 pub mod config_params;
 pub use self::config_params::*;
 
+pub mod fees;
+pub use self::fees::*;
+
+pub mod diff;
+pub use self::diff::*;
+
+pub mod validation;
+pub use self::validation::*;
+
+#[cfg(feature = "json")]
+pub mod json;
+
 use std::{collections::HashMap, hash::Hash};
 
 include!("../common/src/info.rs");
@@ -505,3 +517,10 @@ where
     }
     Ok(s2)
 }
+
+#[cfg(test)]
+pub fn read_test_config() -> ConfigParams {
+    let bytes = std::fs::read("src/tests/data/config.boc").unwrap();
+    let cell = read_single_root_boc(bytes).unwrap();
+    ConfigParams::with_address_and_params(UInt256::from([1; 32]), Some(cell))
+}
@@ -418,6 +418,7 @@ masterchain_block_extra#cca5
 = McBlockExtra;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct McBlockExtra {
     key_block: bool,
     shards: ShardHashes, // workchain_id of ShardIdent from all blocks
@@ -631,6 +632,7 @@ impl Serializable for McBlockExtra {
 
 // _ key:Bool max_end_lt:uint64 = KeyMaxLt;
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyMaxLt {
     pub key: bool,
     pub max_end_lt: u64
@@ -666,6 +668,7 @@ impl Augmentable for KeyMaxLt {
 
 // _ key:Bool blk_ref:ExtBlkRef = KeyExtBlkRef;
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyExtBlkRef {
     pub key: bool,
     pub blk_ref: ExtBlkRef
@@ -847,6 +850,7 @@ impl OldMcBlocksInfo {
 
 // _ fees:CurrencyCollection create:CurrencyCollection = ShardFeeCreated;
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardFeeCreated {
     pub fees: CurrencyCollection,
     pub create: CurrencyCollection,
@@ -894,6 +898,7 @@ pub fn umulnexps32(x : u64, k : u32, _trunc : bool) -> u64 {
 
 /// counters#_ last_updated:uint32 total:uint64 cnt2048:uint64 cnt65536:uint64 = Counters;
 #[derive(Clone, Debug, Default, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Counters {
     last_updated: u32,
     total: u64,
@@ -1006,6 +1011,7 @@ impl Serializable for Counters {
 
 /// creator_info#4 mc_blocks:Counters shard_blocks:Counters = CreatorStats;
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct CreatorStats {
     pub mc_blocks: Counters,
     pub shard_blocks: Counters,
@@ -1061,6 +1067,7 @@ define_HashmapE!{BlockCounters, 256, CreatorStats}
 
 /// block_create_stats#17 counters:(HashmapE 256 CreatorStats) = BlockCreateStats;
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockCreateStats {
     pub counters: BlockCounters,
 }
@@ -1104,6 +1111,7 @@ impl Serializable for BlockCreateStats {
 define_HashmapE!{MeshHashes, 32, ConnectedNwDescr}
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConnectedNwDescr {
     pub seq_no: u32,
     pub root_hash: UInt256,
@@ -1161,6 +1169,7 @@ masterchain_state_extra#cc26
 = McStateExtra;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct McStateExtra {
     pub shards: ShardHashes,
     pub mesh: MeshHashes,
@@ -1560,6 +1569,7 @@ impl Deserializable for ShardCollators {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardBlockRef {
     pub seq_no: u32,
     pub root_hash: UInt256,
@@ -1693,6 +1703,7 @@ define_HashmapE!(MeshHashesExt, 32, ConnectedNwDescrExt);
 const CONNECTED_NW_DESCR_EXT_TAG: u8 = 1; // 4 bits
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConnectedNwDescrExt {
     // Info about out queue from masterchain to connected network
     pub queue_descr: ConnectedNwOutDescr,
@@ -1730,6 +1741,7 @@ define_HashmapE!(MeshOutDescr, 32, ConnectedNwOutDescr);
 const CONNECTED_NW_QUEUE_DESCR_TAG: u8 = 1; // 4 bits
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConnectedNwOutDescr {
     pub out_queue_update: HashUpdate,
     pub exported: VarUInteger32,
@@ -1764,6 +1776,7 @@ impl Serializable for ConnectedNwOutDescr {
 const PACK_INFO_TAG: u8 = 1; // 4 bits
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct MsgPackProcessingInfo {
     pub round: u64,
     pub last_id: MsgPackId,
@@ -2099,6 +2112,7 @@ impl Serializable for ShardDescr {
 master_info$_ master:ExtBlkRef = BlkMasterInfo;
 */
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlkMasterInfo {
     pub master: ExtBlkRef
 }
@@ -2121,6 +2135,7 @@ define_HashmapE!(Publishers, 256, ());
 shared_lib_descr$00 lib:^Cell publishers:(Hashmap 256 True) = LibDescr;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct LibDescr {
     lib: Cell,
     publishers: Publishers
@@ -15,16 +15,20 @@ use crate::{
     dictionary::hashmapaug::HashmapAugType,
     merkle_update::MerkleUpdate,
     Serializable, Deserializable, GetRepresentationHash,
-    accounts::Account,
-    shard::ShardStateUnsplit,
+    accounts::{Account, ShardAccount},
+    shard::{ShardIdent, ShardStateUnsplit},
+    config_params::ConfigParamEnum,
+    inbound_messages::InMsg,
+    master::ShardDescr,
+    outbound_messages::{EnqueuedMsg, OutMsg, OutMsgQueueKey},
     error::BlockError,
-    blocks::{Block, BlockInfo, BlockSeqNoAndShard},
+    blocks::{Block, BlockIdExt, BlockInfo, BlockSeqNoAndShard},
     transactions::Transaction,
     messages::Message,
-    Cell, CellType, BuilderData, fail, IBitstring, SliceData, Result,
-    UsageTree, types::UInt256
+    AccountId, Cell, CellType, BuilderData, error, fail, IBitstring, SliceData, Result,
+    UsageTree, types::UInt256, HashmapE, HashmapType,
 };
-use std::{cmp::max, collections::{HashMap, HashSet}};
+use std::{cmp::max, collections::{HashMap, HashSet}, ops::Bound};
 
 #[cfg(test)]
 #[path = "tests/test_merkle_proof.rs"]
@@ -200,6 +204,176 @@ impl MerkleProof {
         let virt_root = self.proof.clone().virtualize(1);
         T::construct_from_cell(virt_root)
     }
+
+    /// Creates one proof for all given paths: every cell read while getting the values
+    /// from the full tree with `root` is included into the proof.
+    pub fn create_for_paths<P: ProofPath>(root: &Cell, paths: &[P]) -> Result<Self> {
+        let usage_tree = UsageTree::with_root(root.clone());
+        let usage_root = P::Root::construct_from_cell(usage_tree.root_cell())?;
+        for path in paths {
+            path.read(&usage_root)?;
+        }
+        MerkleProof::create_by_usage_tree(root, usage_tree)
+    }
+
+    /// Checks the proof is made for the tree with `root_hash` and reads values of the paths from it.
+    /// Fails if any of the values is pruned in the proof.
+    pub fn check_paths<P: ProofPath>(&self, root_hash: &UInt256, paths: &[P]) -> Result<Vec<P::Value>> {
+        if self.hash != *root_hash {
+            fail!(
+                BlockError::WrongMerkleProof("Proof hash is not equal given root hash".to_string())
+            )
+        }
+        let root: P::Root = self.virtualize()
+            .map_err(
+                |err| BlockError::WrongMerkleProof(
+                    format!("Error extracting root from proof: {}", err)
+                )
+            )?;
+        let mut values = Vec::with_capacity(paths.len());
+        for path in paths {
+            let value = path.read(&root)
+                .map_err(
+                    |err| BlockError::WrongMerkleProof(
+                        format!("Error extracting {:?} from proof: {}", path, err)
+                    )
+                )?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+/// Typed path to a value inside a tree of cells. Used to build and check
+/// Merkle proofs for any combination of values in one proof.
+pub trait ProofPath: std::fmt::Debug {
+    type Root: Deserializable;
+    type Value;
+    /// Reads the value. All cells needed to get it (including absence of the value) must be read.
+    fn read(&self, root: &Self::Root) -> Result<Self::Value>;
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StateProofPath {
+    Account(UInt256),
+    /// Config param from masterchain state
+    ConfigParam(u32),
+    OutQueueEntry(OutMsgQueueKey),
+    /// Shard description from masterchain state
+    ShardDescr(ShardIdent),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BlockProofPath {
+    Info,
+    /// Hash of the shard state after the block (from the state update)
+    StateHash,
+    Transaction {
+        account: UInt256,
+        lt: u64,
+    },
+    InMsg(UInt256),
+    OutMsg(UInt256),
+    /// Config param from key block
+    ConfigParam(u32),
+    /// Shard description from masterchain block
+    ShardDescr(ShardIdent),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProofValue {
+    Account(Option<ShardAccount>),
+    ConfigParam(Option<ConfigParamEnum>),
+    OutQueueEntry(Option<EnqueuedMsg>),
+    ShardDescr(Option<ShardDescr>),
+    BlockInfo(BlockInfo),
+    StateHash(UInt256),
+    Transaction(Option<Transaction>),
+    InMsg(Option<InMsg>),
+    OutMsg(Option<OutMsg>),
+}
+
+impl ProofPath for StateProofPath {
+    type Root = ShardStateUnsplit;
+    type Value = ProofValue;
+
+    fn read(&self, state: &ShardStateUnsplit) -> Result<ProofValue> {
+        match self {
+            StateProofPath::Account(id) => {
+                let account = state.read_accounts()?.account(&AccountId::from(id.clone()))?;
+                if let Some(account) = &account {
+                    account.read_account()?;
+                }
+                Ok(ProofValue::Account(account))
+            }
+            StateProofPath::ConfigParam(index) => {
+                let extra = state.read_custom()?
+                    .ok_or_else(|| error!("State doesn't contain masterchain extra"))?;
+                Ok(ProofValue::ConfigParam(extra.config().config(*index)?))
+            }
+            StateProofPath::OutQueueEntry(key) => {
+                let entry = state.read_out_msg_queue_info()?.out_queue().get(key)?;
+                if let Some(entry) = &entry {
+                    entry.read_out_msg()?;
+                }
+                Ok(ProofValue::OutQueueEntry(entry))
+            }
+            StateProofPath::ShardDescr(shard) => {
+                let extra = state.read_custom()?
+                    .ok_or_else(|| error!("State doesn't contain masterchain extra"))?;
+                let descr = extra.shards().get_shard(shard)?.map(|record| record.descr);
+                Ok(ProofValue::ShardDescr(descr))
+            }
+        }
+    }
+}
+
+impl ProofPath for BlockProofPath {
+    type Root = Block;
+    type Value = ProofValue;
+
+    fn read(&self, block: &Block) -> Result<ProofValue> {
+        match self {
+            BlockProofPath::Info => Ok(ProofValue::BlockInfo(block.read_info()?)),
+            BlockProofPath::StateHash => Ok(ProofValue::StateHash(block.read_state_update()?.new_hash)),
+            BlockProofPath::Transaction { account, lt } => {
+                let account_block = block.read_extra()?.read_account_blocks()?.get(account)?;
+                let transaction = match account_block {
+                    Some(account_block) => account_block.transactions().get(lt)?.map(|tr| tr.0),
+                    None => None
+                };
+                Ok(ProofValue::Transaction(transaction))
+            }
+            BlockProofPath::InMsg(hash) => {
+                let in_msg = block.read_extra()?.read_in_msg_descr()?.get(hash)?;
+                if let Some(in_msg) = &in_msg {
+                    in_msg.message_cell()?;
+                }
+                Ok(ProofValue::InMsg(in_msg))
+            }
+            BlockProofPath::OutMsg(hash) => {
+                let out_msg = block.read_extra()?.read_out_msg_descr()?.get(hash)?;
+                if let Some(out_msg) = &out_msg {
+                    out_msg.read_message_hash()?;
+                }
+                Ok(ProofValue::OutMsg(out_msg))
+            }
+            BlockProofPath::ConfigParam(index) => {
+                let extra = block.read_extra()?.read_custom()?
+                    .ok_or_else(|| error!("Block doesn't contain masterchain extra"))?;
+                let config = extra.config()
+                    .ok_or_else(|| error!("Block is not a key block"))?;
+                Ok(ProofValue::ConfigParam(config.config(*index)?))
+            }
+            BlockProofPath::ShardDescr(shard) => {
+                let extra = block.read_extra()?.read_custom()?
+                    .ok_or_else(|| error!("Block doesn't contain masterchain extra"))?;
+                let descr = extra.shards().get_shard(shard)?.map(|record| record.descr);
+                Ok(ProofValue::ShardDescr(descr))
+            }
+        }
+    }
 }
 
 // checks if proof contains correct block info
@@ -423,3 +597,156 @@ pub fn check_account_proof(proof: &MerkleProof, acc: &Account) -> Result<BlockSe
         fail!(BlockError::WrongMerkleProof("No account in proof".to_string()))
     }
 }
+
+/// Builds a proof of the config params stored in the key block.
+pub fn create_config_params_proof(block_root: &Cell, params: &[u32]) -> Result<MerkleProof> {
+    let mut paths = vec!(BlockProofPath::Info);
+    paths.extend(params.iter().map(|index| BlockProofPath::ConfigParam(*index)));
+    MerkleProof::create_for_paths(block_root, &paths)
+}
+
+/// checks proof made by `create_config_params_proof` against given key block id.
+/// Returns the params in the given order, `None` if param is absent in config.
+pub fn check_config_params_proof(
+    proof: &MerkleProof,
+    block_id: &BlockIdExt,
+    params: &[u32]
+) -> Result<Vec<Option<ConfigParamEnum>>> {
+    let mut paths = vec!(BlockProofPath::Info);
+    paths.extend(params.iter().map(|index| BlockProofPath::ConfigParam(*index)));
+    let mut values = proof.check_paths(&block_id.root_hash, &paths)?.into_iter();
+    match values.next() {
+        Some(ProofValue::BlockInfo(info)) => {
+            check_block_info_id(&info, block_id)?;
+            if !info.key_block() {
+                fail!(BlockError::WrongMerkleProof("Block is not a key block".to_string()))
+            }
+        }
+        _ => fail!(BlockError::WrongMerkleProof("No block info in proof".to_string()))
+    }
+    values.map(|value| match value {
+        ProofValue::ConfigParam(param) => Ok(param),
+        _ => fail!(BlockError::WrongMerkleProof("No config param in proof".to_string()))
+    }).collect()
+}
+
+/// Builds proofs of the config params of the masterchain state: the block proof
+/// contains hash of the state after the block, the state proof contains the params.
+pub fn create_state_config_params_proof(
+    block_root: &Cell,
+    state_root: &Cell,
+    params: &[u32]
+) -> Result<(MerkleProof, MerkleProof)> {
+    let block_proof = MerkleProof::create_for_paths(
+        block_root, &[BlockProofPath::Info, BlockProofPath::StateHash])?;
+    let paths = params.iter().map(|index| StateProofPath::ConfigParam(*index)).collect::<Vec<_>>();
+    let state_proof = MerkleProof::create_for_paths(state_root, &paths)?;
+    Ok((block_proof, state_proof))
+}
+
+/// checks proofs made by `create_state_config_params_proof` against given masterchain block id.
+/// Returns the params in the given order, `None` if param is absent in config.
+pub fn check_state_config_params_proof(
+    block_proof: &MerkleProof,
+    state_proof: &MerkleProof,
+    block_id: &BlockIdExt,
+    params: &[u32]
+) -> Result<Vec<Option<ConfigParamEnum>>> {
+    let values = block_proof.check_paths(
+        &block_id.root_hash, &[BlockProofPath::Info, BlockProofPath::StateHash])?;
+    let state_hash = match values.as_slice() {
+        [ProofValue::BlockInfo(info), ProofValue::StateHash(state_hash)] => {
+            check_block_info_id(info, block_id)?;
+            state_hash.clone()
+        }
+        _ => fail!(BlockError::WrongMerkleProof("No block info or state hash in proof".to_string()))
+    };
+    let paths = params.iter().map(|index| StateProofPath::ConfigParam(*index)).collect::<Vec<_>>();
+    state_proof.check_paths(&state_hash, &paths)?.into_iter().map(|value| match value {
+        ProofValue::ConfigParam(param) => Ok(param),
+        _ => fail!(BlockError::WrongMerkleProof("No config param in proof".to_string()))
+    }).collect()
+}
+
+fn check_block_info_id(info: &BlockInfo, block_id: &BlockIdExt) -> Result<()> {
+    if !block_id.shard().is_masterchain() {
+        fail!(BlockError::InvalidArg("Config params are stored only in masterchain".to_string()))
+    }
+    if info.shard() != block_id.shard() || info.seq_no() != block_id.seq_no() {
+        fail!(BlockError::WrongMerkleProof("Block info doesn't correspond to block id".to_string()))
+    }
+    Ok(())
+}
+
+/// Builds minimal proof of presence or absence of the key in the dictionary:
+/// the path from the root to the leaf or to the node where the key diverges from the labels.
+/// The proof is made for the root cell of the dictionary, so it must not be empty.
+pub fn create_dict_key_proof<T: HashmapType + ?Sized>(dict: &T, key: SliceData) -> Result<MerkleProof> {
+    let root = dict_root(dict)?;
+    let usage_tree = UsageTree::with_root(root.clone());
+    let map = HashmapE::with_hashmap(dict.bit_len(), Some(usage_tree.root_cell()));
+    map.get(key)?;
+    MerkleProof::create_by_usage_tree(root, usage_tree)
+}
+
+/// Checks proof made by `create_dict_key_proof` against root hash of the dictionary.
+/// Returns the leaf (with augmentation for augmented dictionaries) or `None` if the key is absent.
+pub fn check_dict_key_proof(
+    proof: &MerkleProof,
+    root_hash: &UInt256,
+    bit_len: usize,
+    key: SliceData
+) -> Result<Option<SliceData>> {
+    let map = virtualize_dict_proof(proof, root_hash, bit_len)?;
+    map.get(key).map_err(|err| error!(
+        BlockError::WrongMerkleProof(format!("Error reading key from proof: {}", err))
+    ))
+}
+
+/// Builds minimal proof of all the items of the dictionary with keys in the range:
+/// the subtrees out of the range are pruned.
+pub fn create_dict_range_proof<T: HashmapType + ?Sized>(
+    dict: &T,
+    from: Bound<SliceData>,
+    to: Bound<SliceData>,
+    signed: bool
+) -> Result<MerkleProof> {
+    let root = dict_root(dict)?;
+    let usage_tree = UsageTree::with_root(root.clone());
+    let map = HashmapE::with_hashmap(dict.bit_len(), Some(usage_tree.root_cell()));
+    for item in map.hashmap_range(from, to, signed, false)? {
+        item?;
+    }
+    MerkleProof::create_by_usage_tree(root, usage_tree)
+}
+
+/// Checks proof made by `create_dict_range_proof` against root hash of the dictionary.
+/// Returns all the items in the range in ascending order, fails if any of them is pruned.
+pub fn check_dict_range_proof(
+    proof: &MerkleProof,
+    root_hash: &UInt256,
+    bit_len: usize,
+    from: Bound<SliceData>,
+    to: Bound<SliceData>,
+    signed: bool
+) -> Result<Vec<(BuilderData, SliceData)>> {
+    let map = virtualize_dict_proof(proof, root_hash, bit_len)?;
+    map.hashmap_range(from, to, signed, false)?.collect::<Result<Vec<_>>>().map_err(|err| error!(
+        BlockError::WrongMerkleProof(format!("Error reading range from proof: {}", err))
+    ))
+}
+
+fn dict_root<T: HashmapType + ?Sized>(dict: &T) -> Result<&Cell> {
+    dict.data().ok_or_else(|| error!(
+        BlockError::InvalidArg("Empty dictionary has no root to prove".to_string())
+    ))
+}
+
+fn virtualize_dict_proof(proof: &MerkleProof, root_hash: &UInt256, bit_len: usize) -> Result<HashmapE> {
+    if proof.hash != *root_hash {
+        fail!(
+            BlockError::WrongMerkleProof("Proof hash is not equal given root hash".to_string())
+        )
+    }
+    Ok(HashmapE::with_hashmap(bit_len, Some(proof.proof.clone().virtualize(1))))
+}
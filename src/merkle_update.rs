@@ -44,6 +44,7 @@ impl CellsFactory for DefaultCellsFactory {
 old:^X new:^X = MERKLE_UPDATE X;
 */
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct MerkleUpdate {
     pub old_hash: UInt256,
     pub new_hash: UInt256,
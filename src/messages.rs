@@ -16,9 +16,9 @@ use crate::{
     error::BlockError, merkle_proof::MerkleProof, shard::MASTERCHAIN_ID, 
     types::{AddSub, CurrencyCollection, Grams, Number5, Number9, UnixTime32},
     AccountId, BuilderData, Cell, CommonMessage, CryptoSignature, Deserializable,
-    GetRepresentationHash, IBitstring, InRefValue, Result, Serializable, ShardIdent, SliceData,
+    GetRepresentationHash, IBitstring, InRefValue, LibraryResolver, Result, Serializable, ShardIdent, SliceData,
     UInt256, UsageTree, ValidatorDescr, MAX_DATA_BITS, MAX_REFERENCES_COUNT, SERDE_OPTS_EMPTY,
-    read_boc_root,
+    read_boc_root, base64_decode, base64_decode_url_safe, base64_encode, base64_encode_url_safe, crc16,
 };
 use std::{fmt, str::FromStr};
 
@@ -46,7 +46,22 @@ _ MsgAddressInt = MsgAddress;
 _ MsgAddressExt = MsgAddress;
  */
 
+/// Maximal depth of anycast address and split depth of account
+pub const MAX_ANYCAST_DEPTH: u8 = 30;
+
 impl AnycastInfo {
+    /// Anycast with first `depth` bits of account address as rewrite prefix
+    pub fn with_address(address: &SliceData, depth: u8) -> Result<Self> {
+        if !(1..=MAX_ANYCAST_DEPTH).contains(&depth) {
+            fail!(BlockError::InvalidArg(format!(
+                "anycast depth {} should be from 1 to {}", depth, MAX_ANYCAST_DEPTH
+            )))
+        }
+        Self::with_rewrite_pfx(address.get_slice(0, depth as usize)?)
+    }
+    pub fn depth(&self) -> u8 {
+        self.depth.as_u8()
+    }
     pub fn with_rewrite_pfx(pfx: SliceData) -> Result<Self> {
         Ok(Self {
             depth: Number5::new(pfx.remaining_bits() as u32)?,
@@ -258,6 +273,12 @@ impl MsgAddress {
 impl FromStr for MsgAddress {
     type Err = crate::Error;
     fn from_str(string: &str) -> Result<Self> {
+        if string.len() == USER_FRIENDLY_ADDRESS_LEN && !string.contains(':') {
+            return match MsgAddressInt::from_user_friendly(string)?.0 {
+                MsgAddressInt::AddrStd(addr) => Ok(MsgAddress::AddrStd(addr)),
+                MsgAddressInt::AddrVar(addr) => Ok(MsgAddress::AddrVar(addr)),
+            }
+        }
         let parts: Vec<&str> = string.split(':').take(4).collect();
         let len = parts.len();
         if len > 3 {
@@ -418,6 +439,62 @@ impl MsgAddressInt {
     pub fn is_masterchain(&self) -> bool {
         self.get_workchain_id() == MASTERCHAIN_ID
     }
+
+    /// Anycast address of account with split depth: instances of the account in different shards
+    /// differ only in the first `depth` bits replaced by the bits of the shard prefix
+    pub fn with_anycast(workchain_id: i32, address: AccountId, depth: u8) -> Result<Self> {
+        let anycast = AnycastInfo::with_address(&address, depth)?;
+        match i8::try_from(workchain_id) {
+            Ok(workchain_id) if address.remaining_bits() == 256 => {
+                Self::with_standart(Some(anycast), workchain_id, address)
+            }
+            _ => Self::with_variant(Some(anycast), workchain_id, address)
+        }
+    }
+
+    pub fn with_rewrite_pfx(&self, anycast: Option<AnycastInfo>) -> Self {
+        let mut address = self.clone();
+        match &mut address {
+            MsgAddressInt::AddrStd(addr_std) => addr_std.anycast = anycast,
+            MsgAddressInt::AddrVar(addr_var) => addr_var.anycast = anycast
+        }
+        address
+    }
+
+    /// Anycast address with rewrite prefix pointing to the instance of the account in the shard.
+    /// If shard is shallower than anycast depth the rest bits of rewrite prefix are not changed
+    pub fn anycast_in_shard(&self, shard: &ShardIdent) -> Result<Self> {
+        let anycast = match self.rewrite_pfx() {
+            Some(anycast) => anycast,
+            None => fail!(BlockError::InvalidArg(format!("address {} is not anycast", self)))
+        };
+        if shard.workchain_id() != self.workchain_id() {
+            fail!(BlockError::InvalidArg(format!(
+                "address {} is not in workchain of shard {}", self, shard
+            )))
+        }
+        let depth = anycast.depth() as usize;
+        let shard_bits = depth.min(shard.prefix_len() as usize);
+        let mut rewrite_pfx = BuilderData::new();
+        if shard_bits != 0 {
+            let shard_prefix = shard.shard_prefix_without_tag() >> (64 - shard_bits);
+            rewrite_pfx.append_bits(shard_prefix as usize, shard_bits)?;
+        }
+        rewrite_pfx.append_bytestring(&anycast.rewrite_pfx.get_slice(shard_bits, depth - shard_bits)?)?;
+        let anycast = AnycastInfo::with_rewrite_pfx(SliceData::load_bitstring(rewrite_pfx)?)?;
+        Ok(self.with_rewrite_pfx(Some(anycast)))
+    }
+
+    /// Effective address without anycast: account id has rewrite prefix instead of the first bits
+    pub fn rewritten_address(&self) -> Result<Self> {
+        let (workchain_id, address) = self.extract_std_address(true)?;
+        match self {
+            MsgAddressInt::AddrStd(addr_std) => {
+                Ok(MsgAddressInt::AddrStd(MsgAddrStd::with_address(None, addr_std.workchain_id, address)))
+            }
+            MsgAddressInt::AddrVar(_) => MsgAddressInt::with_variant(None, workchain_id, address)
+        }
+    }
 }
 
 impl Serializable for MsgAddressInt {
@@ -447,6 +524,117 @@ impl fmt::Display for MsgAddressInt {
     }
 }
 
+/*
+User-friendly address: 36 bytes in base64 form
+tag:uint8 workchain_id:int8 address:uint256 crc16:uint16
+tag is 0x11 for bounceable and 0x51 for non-bounceable address with 0x80 flag for testnet
+*/
+pub const USER_FRIENDLY_ADDRESS_LEN: usize = 48;
+const USER_FRIENDLY_BOUNCEABLE_TAG: u8 = 0x11;
+const USER_FRIENDLY_NON_BOUNCEABLE_TAG: u8 = 0x51;
+const USER_FRIENDLY_TESTNET_FLAG: u8 = 0x80;
+
+/// Flags of user-friendly address and alphabet of its base64 form
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UserFriendlyFlags {
+    pub bounceable: bool,
+    pub testnet: bool,
+    pub url_safe: bool,
+}
+
+impl UserFriendlyFlags {
+    pub fn with_params(bounceable: bool, testnet: bool, url_safe: bool) -> Self {
+        Self { bounceable, testnet, url_safe }
+    }
+}
+
+impl MsgAddressInt {
+    /// Encodes address without anycast with 256-bit account id and 8-bit workchain id
+    pub fn to_user_friendly(&self, flags: UserFriendlyFlags) -> Result<String> {
+        if self.rewrite_pfx().is_some() {
+            fail!(BlockError::InvalidArg(
+                "anycast address can't be user-friendly".to_string()
+            ))
+        }
+        let workchain_id = match i8::try_from(self.workchain_id()) {
+            Ok(workchain_id) => workchain_id,
+            Err(_) => fail!(BlockError::InvalidArg(format!(
+                "workchain {} is not supported in user-friendly address", self.workchain_id()
+            )))
+        };
+        let address = self.address();
+        if address.remaining_bits() != 256 {
+            fail!(BlockError::InvalidArg(format!(
+                "user-friendly address should be 256 bits long but it is {}", address.remaining_bits()
+            )))
+        }
+        let mut data = [0; 36];
+        data[0] = match flags.bounceable {
+            true => USER_FRIENDLY_BOUNCEABLE_TAG,
+            false => USER_FRIENDLY_NON_BOUNCEABLE_TAG
+        };
+        if flags.testnet {
+            data[0] |= USER_FRIENDLY_TESTNET_FLAG;
+        }
+        data[1] = workchain_id as u8;
+        data[2..34].copy_from_slice(&address.get_bytestring(0));
+        let crc = crc16(&data[..34]);
+        data[34..].copy_from_slice(&crc.to_be_bytes());
+        match flags.url_safe {
+            true => Ok(base64_encode_url_safe(data)),
+            false => Ok(base64_encode(data))
+        }
+    }
+
+    /// Decodes user-friendly address in url-safe or standard base64 form
+    pub fn from_user_friendly(string: &str) -> Result<(Self, UserFriendlyFlags)> {
+        if string.len() != USER_FRIENDLY_ADDRESS_LEN {
+            fail!(BlockError::InvalidArg(format!(
+                "user-friendly address should be {} characters long but it is {}",
+                USER_FRIENDLY_ADDRESS_LEN, string.len()
+            )))
+        }
+        let url_safe = string.contains(['-', '_']);
+        if url_safe && string.contains(['+', '/']) {
+            fail!(BlockError::InvalidArg(
+                "user-friendly address mixes url-safe and standard base64 alphabets".to_string()
+            ))
+        }
+        let data = match url_safe {
+            true => base64_decode_url_safe(string),
+            false => base64_decode(string)
+        }.map_err(|err| BlockError::InvalidArg(
+            format!("user-friendly address is not correct base64: {}", err)
+        ))?;
+        if data.len() != 36 {
+            fail!(BlockError::InvalidArg(format!(
+                "user-friendly address should be 36 bytes long but it is {}", data.len()
+            )))
+        }
+        let crc = crc16(&data[..34]);
+        if data[34..] != crc.to_be_bytes() {
+            fail!(BlockError::InvalidArg(format!(
+                "user-friendly address has wrong checksum {:04x}, expected {:04x}",
+                u16::from_be_bytes([data[34], data[35]]), crc
+            )))
+        }
+        let bounceable = match data[0] & !USER_FRIENDLY_TESTNET_FLAG {
+            USER_FRIENDLY_BOUNCEABLE_TAG => true,
+            USER_FRIENDLY_NON_BOUNCEABLE_TAG => false,
+            tag => fail!(BlockError::InvalidArg(format!(
+                "user-friendly address has wrong tag {:02x}", tag
+            )))
+        };
+        let flags = UserFriendlyFlags {
+            bounceable,
+            testnet: data[0] & USER_FRIENDLY_TESTNET_FLAG != 0,
+            url_safe,
+        };
+        let address = AccountId::from(UInt256::from_slice(&data[2..34]));
+        Ok((MsgAddressInt::standard(data[1] as i8, address), flags))
+    }
+}
+
 /*
 This file contains definitions for internal and external message headers
 as defined in Blockchain: 3.1.
@@ -558,6 +746,7 @@ impl Deserializable for MsgAddressIntOrNone {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct InternalMessageHeader {
     pub ihr_disabled: bool,
     pub bounce: bool,
@@ -699,6 +888,7 @@ impl fmt::Display for ExternalInboundMessageHeader {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExternalInboundMessageHeader {
     pub src: MsgAddressExt,
     pub dst: MsgAddressInt,
@@ -746,6 +936,7 @@ impl fmt::Display for ExtOutMessageHeader {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExtOutMessageHeader {
     pub src: MsgAddressIntOrNone,
     pub dst: MsgAddressExt,
@@ -824,6 +1015,7 @@ impl fmt::Display for CommonMsgInfo {
 
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 #[allow(clippy::large_enum_variant)]
 pub enum CommonMsgInfo{
     IntMsgInfo(InternalMessageHeader),
@@ -977,6 +1169,7 @@ pub type MessageId = UInt256;
 ///
 
 #[derive(Debug, Default, Clone, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     header: CommonMsgInfo,
     init: Option<StateInit>,
@@ -1601,6 +1794,7 @@ impl InternalMessageHeader {
 /// tick_tock$_ tick:Boolean tock:Boolean = TickTock;
 ///
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TickTock {
     pub tick: bool,
     pub tock: bool,
@@ -1656,7 +1850,8 @@ impl fmt::Display for TickTock {
 }
 
 /// simple_lib$_ public:Bool root:^Cell = SimpleLib;
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleLib {
     pub public: bool,
     pub root: Cell,
@@ -1690,6 +1885,12 @@ impl Deserializable for SimpleLib {
 // HashmapE 256 SimpleLib
 define_HashmapE!{StateInitLib, 256, SimpleLib}
 
+impl LibraryResolver for StateInitLib {
+    fn find_library(&self, hash: &UInt256) -> Result<Option<Cell>> {
+        Ok(self.get(hash)?.map(|lib| lib.root))
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 ///
 /// 3.1.7. Message layout.
@@ -1698,6 +1899,7 @@ define_HashmapE!{StateInitLib, 256, SimpleLib}
 /// library:(HashmapE 256 SimpleLib) = StateInit;
 ///
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct StateInit {
     pub split_depth: Option<Number5>,
     pub special: Option<TickTock>,
@@ -1752,6 +1954,29 @@ impl StateInit {
         self.library.set(&code.repr_hash(), &SimpleLib::new(code, public))?;
         Ok(())
     }
+
+    /// Checks that the state init can be deployed to the address: anycast depth of address
+    /// should be equal to split depth and account id should be equal to hash of the state init
+    /// except the first split depth bits
+    pub fn check_split_depth(&self, address: &MsgAddressInt) -> Result<()> {
+        let split_depth = self.split_depth.as_ref().map(|depth| depth.as_usize()).unwrap_or_default();
+        let depth = address.rewrite_pfx().map(|anycast| anycast.depth() as usize).unwrap_or_default();
+        if split_depth != depth {
+            fail!(BlockError::InvalidArg(format!(
+                "split depth {} of state init doesn't match anycast depth {} of address {}",
+                split_depth, depth, address
+            )))
+        }
+        let (_, account_id) = address.extract_std_address(true)?;
+        let hash = AccountId::from(self.hash()?);
+        if account_id.remaining_bits() != 256 ||
+            account_id.get_slice(depth, 256 - depth)? != hash.get_slice(depth, 256 - depth)? {
+            fail!(BlockError::InvalidArg(format!(
+                "address {} doesn't match hash {:x} of state init", address, hash
+            )))
+        }
+        Ok(())
+    }
 }
 
 impl Serializable for StateInit {
@@ -2122,6 +2347,7 @@ impl Deserializable for MsgPackProof {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct MsgPackId {
     pub shard: ShardIdent,
     pub seqno: u64,
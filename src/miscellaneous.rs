@@ -69,6 +69,7 @@ impl Deserializable for ProcessedInfoKey {
 /// Struct ProcessedUpto
 /// 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcessedUpto {
     pub last_msg_lt: u64,
     pub last_msg_hash: UInt256,
@@ -135,6 +136,7 @@ impl IhrPendingInfo {
 ///     import_lt:uint64
 /// = IhrPendingSince;
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct IhrPendingSince {
 	import_lt: u64,
 }
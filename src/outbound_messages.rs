@@ -15,7 +15,7 @@ use crate::{
     define_HashmapAugE, define_HashmapE,
     error::BlockError,
     envelope_message::MsgEnvelope,
-    dictionary::hashmapaug::{Augmentable, Augmentation, HashmapAugType},
+    dictionary::{DictKey, hashmapaug::{Augmentable, Augmentation, HashmapAugType}},
     inbound_messages::InMsg,
     messages::{CommonMsgInfo, Message},
     common_message::CommonMessage,
@@ -27,7 +27,7 @@ use crate::{
     error, fail, Result, SERDE_OPTS_EMPTY, SERDE_OPTS_COMMON_MESSAGE,
     AccountId, UInt256, InRefValue,
     BuilderData, Cell, SliceData, IBitstring,
-    HashmapType, HashmapSubtree, hm_label, UsageTree,
+    HashmapType, HashmapSubtree, hashmap_subtree_with_prefix, GasConsumer, hm_label, UsageTree,
 };
 use std::{fmt, collections::HashSet};
 
@@ -66,6 +66,7 @@ _ enqueued_lt:uint64 out_msg:^MsgEnvelope = EnqueuedMsg;
 /// EnqueuedMsg structure
 /// 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnqueuedMsg {
     pub enqueued_lt: u64,
     pub out_msg: ChildCell<MsgEnvelope>
@@ -190,7 +191,13 @@ impl OutMsgDescr {
 // _ (HashmapAugE 352 EnqueuedMsg uint64) = OutMsgQueue;
 // 352 = 32 - dest workchain_id, 64 - first 64 bit of dest account address, 256 - message hash
 define_HashmapAugE!(OutMsgQueue, 352, OutMsgQueueKey, EnqueuedMsg, MsgTime);
-impl HashmapSubtree for OutMsgQueue {}
+impl HashmapSubtree for OutMsgQueue {
+    fn subtree_with_prefix(&self, prefix: &SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Self> {
+        let mut subtree = hashmap_subtree_with_prefix(self, prefix, gas_consumer)?;
+        subtree.update_root_extra()?;
+        Ok(subtree)
+    }
+}
 
 pub type MsgTime = u64;
 
@@ -267,6 +274,10 @@ impl Deserializable for OutMsgQueueKey {
     }
 }
 
+impl DictKey for OutMsgQueueKey {
+    const BIT_LEN: usize = 352;
+}
+
 impl fmt::LowerHex for OutMsgQueueKey {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if f.alternate() {
@@ -279,6 +290,7 @@ impl fmt::LowerHex for OutMsgQueueKey {
 define_HashmapE!(MeshMsgQueuesInfo, 32, InRefValue<OutMsgQueueInfo>);
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct OutMsgQueuesInfo {
     pub local_queue: OutMsgQueueInfo,
     pub mesh_queues: MeshMsgQueuesInfo,
@@ -351,6 +363,7 @@ _ out_queue:OutMsgQueue proc_info:ProcessedInfo
 ihr_pending:IhrPendingInfo = OutMsgQueueInfo;
 */
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgQueueInfo {
     out_queue: OutMsgQueue,
     proc_info: ProcessedInfo,
@@ -574,6 +587,7 @@ impl Deserializable for OutMsgQueueInfo {
 /// blockchain spec 3.3.3. Descriptor of an outbound message
 /// 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum OutMsg {
     #[default]
     None,
@@ -1007,6 +1021,7 @@ impl Deserializable for OutMsg {
 /// msg_export_ext$000 msg:^Message transaction:^Transaction = OutMsg;
 /// 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgExternal {
     msg: ChildCell<CommonMessage>,
     transaction: ChildCell<Transaction>,
@@ -1070,6 +1085,7 @@ impl Deserializable for OutMsgExternal {
 /// 
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgImmediate {
     out_msg: ChildCell<MsgEnvelope>,
     transaction: ChildCell<Transaction>,
@@ -1136,6 +1152,7 @@ impl Deserializable for OutMsgImmediate {
 /// 
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgNew {
     out_msg: ChildCell<MsgEnvelope>,
     transaction: ChildCell<Transaction>,
@@ -1190,6 +1207,7 @@ impl Deserializable for OutMsgNew {
 /// 
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgTransit {
     out_msg: ChildCell<MsgEnvelope>,
     imported: ChildCell<InMsg>,
@@ -1244,6 +1262,7 @@ impl Deserializable for OutMsgTransit {
 /// 
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgDequeueImmediate {
     out_msg: ChildCell<MsgEnvelope>,
     reimport: ChildCell<InMsg>,
@@ -1298,6 +1317,7 @@ impl Deserializable for OutMsgDequeueImmediate {
 /// 
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgDequeue {
     out_msg: ChildCell<MsgEnvelope>,
     import_block_lt: u64,
@@ -1353,6 +1373,7 @@ impl Deserializable for OutMsgDequeue {
 ///
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgDequeueShort {
     pub msg_env_hash: UInt256,
     pub next_workchain: i32,
@@ -1385,6 +1406,7 @@ impl Deserializable for OutMsgDequeueShort {
 /// 
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutMsgTransitRequeued {
     out_msg: ChildCell<MsgEnvelope>,
     imported: ChildCell<InMsg>,
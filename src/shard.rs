@@ -26,7 +26,7 @@ use crate::{
     validators::ValidatorSet,
     CopyleftRewards, Deserializable, IntermediateAddress,
     Serializable, Account,
-    error, fail, AccountId, BuilderData, Cell, IBitstring, Result,
+    error, fail, AccountId, BuilderData, Cell, IBitstring, LibraryResolver, Result,
     SERDE_OPTS_COMMON_MESSAGE, SERDE_OPTS_EMPTY, SliceData, UInt256, MsgPackProcessingInfo,
 };
 use crate::RefShardBlocks;
@@ -228,6 +228,7 @@ shard_ident$00
 = ShardIdent;
 */
 #[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardIdent {
     workchain_id: i32,
     prefix: u64, // with terminated bit!
@@ -487,8 +488,10 @@ impl ShardIdent {
         self.workchain_id() >= BASE_WORKCHAIN_ID && self.workchain_id() <= 255
     }
 
+    /// Checks effective address of account, i.e. with rewrite prefix for anycast address
     pub fn contains_address(&self, addr: &MsgAddressInt) -> Result<bool> {
-        Ok(self.workchain_id == addr.workchain_id() && self.contains_account(addr.address())?)
+        let (workchain_id, account_id) = addr.extract_std_address(true)?;
+        Ok(self.workchain_id == workchain_id && self.contains_account(account_id)?)
     }
     pub fn contains_account(&self, mut acc_addr: AccountId) -> Result<bool> {
         Ok(
@@ -784,6 +787,12 @@ impl Serializable for ShardStateSplit {
 
 define_HashmapE!(Libraries, 256, LibDescr);
 
+impl LibraryResolver for Libraries {
+    fn find_library(&self, hash: &UInt256) -> Result<Option<Cell>> {
+        Ok(self.get(hash)?.map(|descr| descr.lib().clone()))
+    }
+}
+
 ///
 /// Struct ShardStateUnsplit
 ///
@@ -809,6 +818,7 @@ define_HashmapE!(Libraries, 256, LibDescr);
 //     custom:(Maybe ^McStateExtra)
 // = ShardStateUnsplit;
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShardStateUnsplit {
     global_id: i32,
     shard_id: ShardIdent,
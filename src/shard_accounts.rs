@@ -20,7 +20,7 @@ use crate::{
     error, fail, Result,
     AccountId, UInt256,
     BuilderData, Cell, IBitstring,
-    SliceData, hm_label, HashmapSubtree,
+    SliceData, hm_label, hashmap_subtree_with_prefix, GasConsumer, HashmapSubtree,
 };
 use std::fmt;
 
@@ -33,7 +33,13 @@ mod tests;
 // of the shardchain state (cf. 1.2.1 and 1.2.2) is given by (upd from Lite Client v11):
 // _ (HashmapAugE 256 ShardAccount DepthBalanceInfo) = ShardAccounts;
 define_HashmapAugE!(ShardAccounts, 256, UInt256, ShardAccount, DepthBalanceInfo);
-impl HashmapSubtree for ShardAccounts {}
+impl HashmapSubtree for ShardAccounts {
+    fn subtree_with_prefix(&self, prefix: &SliceData, gas_consumer: &mut dyn GasConsumer) -> Result<Self> {
+        let mut subtree = hashmap_subtree_with_prefix(self, prefix, gas_consumer)?;
+        subtree.update_root_extra()?;
+        Ok(subtree)
+    }
+}
 
 impl ShardAccounts {
     pub fn insert(&mut self, split_depth: u8, account: &Account, last_trans_hash: UInt256, last_trans_lt: u64) -> Result<Option<AccountId>> {
@@ -83,6 +89,7 @@ impl Augmentation<DepthBalanceInfo> for ShardAccount {
 
 /// depth_balance$_ split_depth:(#<= 30) balance:CurrencyCollection = DepthBalanceInfo;
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct DepthBalanceInfo {
     split_depth: Number5,
     balance: CurrencyCollection,
@@ -12,10 +12,12 @@
 */
 
 use crate::{
-    blocks::BlockIdExt, define_HashmapE, error::BlockError, validators::ValidatorBaseInfo,
-    validators::ValidatorDescr, Deserializable, Serializable,
-    fail, BuilderData, Cell, Ed25519KeyOption, HashmapE, HashmapType, IBitstring, KeyOption,
-    Result, SliceData, UInt256,
+    blocks::{Block, BlockIdExt, BlockInfo}, bls, config_params::{CatchainConfig, ConfigParamEnum},
+    define_HashmapE, error::BlockError, merkle_proof::{BlockProofPath, MerkleProof, ProofValue},
+    shard::{ShardStateUnsplit, MASTERCHAIN_ID, SHARD_FULL},
+    validators::{ValidatorBaseInfo, ValidatorDescr, ValidatorSet}, Deserializable, Serializable,
+    error, fail, BuilderData, Cell, CellType, Ed25519KeyOption, HashmapE, HashmapType, IBitstring,
+    KeyOption, Result, SliceData, UInt256,
     ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH
 };
 use std::{collections::{HashMap, HashSet}, str::FromStr, sync::Arc, convert::TryInto};
@@ -130,6 +132,7 @@ sig_pair$_ node_id_short:bits256 sign:CryptoSignature = CryptoSignaturePair;
 /// CryptoSignaturePair
 ///
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct CryptoSignaturePair {
     pub node_id_short: UInt256,
     pub sign: CryptoSignature,
@@ -476,6 +479,208 @@ impl Deserializable for BlockProof {
     }
 }
 
+///
+/// Verifies a chain of key block proofs starting from a trusted zerostate or key block.
+/// Every next key block must be signed by more than 2/3 of the weight of the masterchain
+/// validators taken from config param 34 of the previous one.
+///
+#[derive(Clone, Debug)]
+pub struct ProofChainVerifier {
+    block_id: BlockIdExt,
+    validator_set: ValidatorSet,
+    cc_config: CatchainConfig,
+}
+
+impl ProofChainVerifier {
+    /// Create verifier with already trusted block id and its validators
+    pub fn with_params(block_id: BlockIdExt, validator_set: ValidatorSet, cc_config: CatchainConfig) -> Self {
+        Self { block_id, validator_set, cc_config }
+    }
+
+    /// Create verifier from the trusted masterchain zerostate
+    pub fn with_zerostate(block_id: BlockIdExt, state_root: &Cell) -> Result<Self> {
+        if !block_id.shard().is_masterchain() || block_id.seq_no() != 0 {
+            fail!(BlockError::InvalidArg(format!("{} is not a masterchain zerostate", block_id)))
+        }
+        if state_root.repr_hash() != block_id.root_hash {
+            fail!(BlockError::WrongHash)
+        }
+        let state = ShardStateUnsplit::construct_from_cell(state_root.clone())?;
+        let config = &state.read_custom()?
+            .ok_or_else(|| error!(BlockError::InvalidData("Zerostate has no config".to_string())))?
+            .config;
+        let (validator_set, cc_config) = Self::read_validators(config.config(34)?, config.config(28)?)?;
+        Ok(Self { block_id, validator_set, cc_config })
+    }
+
+    /// Create verifier from the trusted key block. `block_root` is either the full block
+    /// or the Merkle proof of it containing config params 28 and 34.
+    pub fn with_key_block(block_id: BlockIdExt, block_root: &Cell) -> Result<Self> {
+        let (info, cur_validators, cc_config) = Self::read_key_block(&block_id, block_root)?;
+        if !info.key_block() {
+            fail!(BlockError::InvalidArg(format!("{} is not a key block", block_id)))
+        }
+        let (validator_set, cc_config) = Self::read_validators(cur_validators, cc_config)?;
+        Ok(Self { block_id, validator_set, cc_config })
+    }
+
+    /// Id of the last verified key block (or trusted one if nothing was verified)
+    pub fn block_id(&self) -> &BlockIdExt {
+        &self.block_id
+    }
+
+    /// Validator set of the last verified key block
+    pub fn validator_set(&self) -> &ValidatorSet {
+        &self.validator_set
+    }
+
+    /// Check the proof of the next key block signed with Ed25519 catchain signatures
+    pub fn check_proof(&mut self, proof: &BlockProof) -> Result<()> {
+        let (info, next) = self.check_next_key_block(proof)?;
+        let (validators, hash_short) = self.calc_signers(&info)?;
+        let signatures = proof.signatures.as_ref()
+            .ok_or_else(|| error!(BlockError::InvalidData(format!("Proof of {} has no signatures", proof.proof_for))))?;
+        if signatures.validator_info.catchain_seqno != info.gen_catchain_seqno() {
+            fail!(BlockError::InvalidData(format!(
+                "Bad catchain seqno {} in signatures of {}, expected {}",
+                signatures.validator_info.catchain_seqno, proof.proof_for, info.gen_catchain_seqno()
+            )))
+        }
+        if signatures.validator_info.validator_list_hash_short != hash_short {
+            fail!(BlockError::InvalidData(format!("Bad validator set hash in signatures of {}", proof.proof_for)))
+        }
+        let data = Block::build_data_for_sign(&proof.proof_for.root_hash, &proof.proof_for.file_hash);
+        let weight = signatures.pure_signatures.check_signatures(&validators, &data)?;
+        Self::check_weight(&validators, weight, &proof.proof_for)?;
+        self.apply(proof, next)
+    }
+
+    /// Check the proof of the next key block signed with aggregated BLS signature.
+    /// `signature` contains the signature with info about the signed validators,
+    /// indexes of them are the indexes in the masterchain validators subset.
+    pub fn check_proof_bls(&mut self, proof: &BlockProof, signature: &[u8]) -> Result<()> {
+        let (info, next) = self.check_next_key_block(proof)?;
+        let (validators, _) = self.calc_signers(&info)?;
+        let nodes_info_bytes = bls::get_nodes_info_from_sig(signature)?;
+        let nodes_info = bls::NodesInfo::deserialize(&nodes_info_bytes)?;
+        if nodes_info.total_num_of_nodes as usize != validators.len() {
+            fail!(BlockError::InvalidData(format!(
+                "BLS signature of {} is made for {} validators, but there are {}",
+                proof.proof_for, nodes_info.total_num_of_nodes, validators.len()
+            )))
+        }
+        let mut keys = Vec::with_capacity(validators.len());
+        for vd in &validators {
+            let key = vd.bls_public_key.as_ref()
+                .ok_or_else(|| error!(BlockError::InvalidData("Validator has no BLS public key".to_string())))?;
+            keys.push(key);
+        }
+        let aggregated_key = bls::aggregate_public_keys_based_on_nodes_info(&keys, &nodes_info_bytes)?;
+        let data = Block::build_data_for_sign(&proof.proof_for.root_hash, &proof.proof_for.file_hash);
+        if !bls::truncate_nodes_info_and_verify(signature, &aggregated_key, &data)? {
+            fail!(BlockError::BadSignature)
+        }
+        let weight = nodes_info.map.keys().map(|index| validators[*index as usize].weight).sum();
+        Self::check_weight(&validators, weight, &proof.proof_for)?;
+        self.apply(proof, next)
+    }
+
+    /// Check all proofs one by one and return the last verified key block id and its validator set
+    pub fn check_chain(mut self, proofs: &[BlockProof]) -> Result<(BlockIdExt, ValidatorSet)> {
+        for proof in proofs {
+            self.check_proof(proof)?;
+        }
+        Ok((self.block_id, self.validator_set))
+    }
+
+    fn check_next_key_block(&self, proof: &BlockProof) -> Result<(BlockInfo, (ValidatorSet, CatchainConfig))> {
+        let (info, cur_validators, cc_config) = Self::read_key_block(&proof.proof_for, &proof.root)?;
+        if !info.key_block() {
+            fail!(BlockError::InvalidData(format!("{} is not a key block", proof.proof_for)))
+        }
+        if proof.proof_for.seq_no() <= self.block_id.seq_no() {
+            fail!(BlockError::InvalidData(format!(
+                "{} is not newer than the last verified block {}", proof.proof_for, self.block_id
+            )))
+        }
+        if info.prev_key_block_seqno() != self.block_id.seq_no() {
+            fail!(BlockError::InvalidData(format!(
+                "Previous key block of {} is {}, but the last verified block is {}",
+                proof.proof_for, info.prev_key_block_seqno(), self.block_id
+            )))
+        }
+        let next = Self::read_validators(cur_validators, cc_config)?;
+        Ok((info, next))
+    }
+
+    fn read_key_block(
+        block_id: &BlockIdExt,
+        block_root: &Cell
+    ) -> Result<(BlockInfo, Option<ConfigParamEnum>, Option<ConfigParamEnum>)> {
+        if !block_id.shard().is_masterchain() {
+            fail!(BlockError::InvalidArg(format!("{} is not a masterchain block", block_id)))
+        }
+        let proof = if block_root.cell_type() == CellType::MerkleProof {
+            MerkleProof::construct_from_cell(block_root.clone())?
+        } else {
+            MerkleProof::create(block_root, |_| true)?
+        };
+        let paths = [BlockProofPath::Info, BlockProofPath::ConfigParam(28), BlockProofPath::ConfigParam(34)];
+        let mut values = proof.check_paths(&block_id.root_hash, &paths)?.into_iter();
+        match (values.next(), values.next(), values.next()) {
+            (
+                Some(ProofValue::BlockInfo(info)),
+                Some(ProofValue::ConfigParam(cc_config)),
+                Some(ProofValue::ConfigParam(cur_validators))
+            ) => {
+                if info.shard() != block_id.shard() || info.seq_no() != block_id.seq_no() {
+                    fail!(BlockError::WrongMerkleProof(format!("Block info doesn't correspond to {}", block_id)))
+                }
+                Ok((info, cur_validators, cc_config))
+            }
+            _ => fail!(BlockError::WrongMerkleProof(format!("Wrong proof of {}", block_id)))
+        }
+    }
+
+    fn read_validators(
+        cur_validators: Option<ConfigParamEnum>,
+        cc_config: Option<ConfigParamEnum>
+    ) -> Result<(ValidatorSet, CatchainConfig)> {
+        match (cur_validators, cc_config) {
+            (Some(ConfigParamEnum::ConfigParam34(param)), Some(ConfigParamEnum::ConfigParam28(cc_config))) => {
+                Ok((param.cur_validators, cc_config))
+            }
+            _ => fail!(BlockError::InvalidData("No validator set or catchain config in key block".to_string()))
+        }
+    }
+
+    fn calc_signers(&self, info: &BlockInfo) -> Result<(Vec<ValidatorDescr>, u32)> {
+        self.validator_set.calc_subset(
+            &self.cc_config,
+            SHARD_FULL,
+            MASTERCHAIN_ID,
+            info.gen_catchain_seqno(),
+            info.gen_utime()
+        )
+    }
+
+    fn check_weight(validators: &[ValidatorDescr], weight: u64, block_id: &BlockIdExt) -> Result<()> {
+        let total_weight: u64 = validators.iter().map(|vd| vd.weight).sum();
+        if weight * 3 <= total_weight * 2 {
+            fail!(BlockError::InvalidData(format!(
+                "Too small signatures weight {} of {} for {}", weight, total_weight, block_id
+            )))
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, proof: &BlockProof, next: (ValidatorSet, CatchainConfig)) -> Result<()> {
+        self.block_id = proof.proof_for.clone();
+        (self.validator_set, self.cc_config) = next;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 #[path = "tests/test_signature.rs"]
 mod tests;
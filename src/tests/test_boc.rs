@@ -15,7 +15,7 @@ use std::fs::read;
 use std::io::Cursor;
 use std::path::Path;
 use rand::{thread_rng, Rng};
-use crate::{BuilderData, IBitstring, create_big_cell, base64_decode, MAX_DEPTH, SliceData};
+use crate::{AccountId, BuilderData, HashmapE, HashmapType, Deserializable, HashmapAugType, IBitstring, create_big_cell, base64_decode, MAX_DEPTH, SliceData};
 
 use super::*;
 
@@ -816,4 +816,364 @@ fn test_bad_boc_12() {
     let elapsed = d1.elapsed().as_nanos();
     println!("Parse: {}nanos,", elapsed);
     assert!(elapsed < 1_000_000);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_boc_write_reference_compatible() -> Result<()> {
+    let files = [
+        "key_block.boc",
+        "shard_state.boc",
+        "3F7B3F53F9F0684E29D67B813E2197689FE725A77491BD50A5438EA66D4341E0.boc",
+        "6DD4CFAFD43CB7B38656379392764136A08CC260CBC9D00D8D92F4F3CDF9AB61.boc",
+        "wallet-query.boc",
+    ];
+    for name in files {
+        let orig_bytes = read(format!("src/tests/data/{}", name))?;
+        let rr = read_boc(&orig_bytes)?;
+        let options = BocWriteOptions {
+            has_index: rr.header.index_included,
+            has_crc32c: rr.header.has_crc,
+            has_cache_bits: rr.header.has_cache_bits,
+            ref_size: Some(rr.header.ref_size),
+            offset_size: Some(rr.header.offset_size),
+            cells_order: BocCellsOrder::Reference,
+            ..Default::default()
+        };
+        let mut bytes = Vec::with_capacity(orig_bytes.len());
+        BocWriter::with_roots(rr.roots)?.write_with_options(&mut bytes, &options)?;
+        assert_eq!(orig_bytes, bytes, "{}", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_boc_write_options() -> Result<()> {
+    let root = build_tree();
+    let proof = crate::MerkleProof::create(&root, |hash| *hash != root.reference(0).unwrap().repr_hash())?;
+    let proof = crate::Serializable::serialize(&proof)?;
+
+    for root in [root, proof] {
+        for store_hashes in [false, true] {
+            for has_cache_bits in [false, true] {
+                let options = BocWriteOptions {
+                    has_index: true,
+                    has_crc32c: true,
+                    has_cache_bits,
+                    store_hashes,
+                    ..Default::default()
+                };
+                let mut data = Vec::new();
+                BocWriter::with_root(&root)?.write_with_options(&mut data, &options)?;
+
+                let result = BocReader::new().read(&mut Cursor::new(&data))?;
+                assert!(result.header.index_included);
+                assert!(result.header.has_crc);
+                assert_eq!(result.header.has_cache_bits, has_cache_bits);
+                let restored = result.withdraw_single_root()?;
+                assert_eq!(root, restored);
+                assert_eq!(restored.store_hashes(), store_hashes);
+
+                let restored = BocReader::new().read_inmem(Arc::new(data))?.withdraw_single_root()?;
+                assert_eq!(root, restored);
+                assert_eq!(restored.reference(0)?.store_hashes(), store_hashes);
+            }
+        }
+    }
+
+    let options = BocWriteOptions { has_cache_bits: true, ..Default::default() };
+    BocWriter::with_root(&build_tree())?.write_with_options(&mut Vec::new(), &options).expect_err(
+        "cache bits can't be written without index"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_boc_write_roots_order() -> Result<()> {
+    let roots = [build_tree(), build_tree2(1), build_tree2(2)];
+    let options = BocWriteOptions {
+        roots_order: Some(vec![2, 0, 1]),
+        ..BocWriteOptions::with_index(true, false)
+    };
+    let mut data = Vec::new();
+    BocWriter::with_roots(roots.clone())?.write_with_options(&mut data, &options)?;
+    let restored = read_boc(&data)?.roots;
+    assert_eq!(restored, [roots[2].clone(), roots[0].clone(), roots[1].clone()]);
+
+    for order in [vec![0, 1], vec![0, 1, 1], vec![0, 1, 3]] {
+        let options = BocWriteOptions { roots_order: Some(order), ..Default::default() };
+        BocWriter::with_roots(roots.clone())?.write_with_options(&mut Vec::new(), &options).expect_err(
+            "roots order must be a permutation"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_boc_writer_stack_with_options() -> Result<()> {
+    let mut cells_count = 0;
+    let root = build_tree_with_params(0, 5, &mut cells_count)?;
+    let shared = build_tree3(1);
+    let root = c("1", [root, shared.clone(), shared])?;
+
+    for store_hashes in [false, true] {
+        let options = BocWriteOptions {
+            has_index: true,
+            has_crc32c: true,
+            has_cache_bits: true,
+            store_hashes,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        BocWriterStack::write_with_options(
+            &mut data,
+            Path::new("src/tests/data/"),
+            root.clone(),
+            MAX_DEPTH,
+            TestCellByHashStorage::with_root(root.clone()),
+            &|| false,
+            &options,
+        )?;
+
+        let mut expected = Vec::new();
+        BocWriter::with_root(&root)?.write_with_options(&mut expected, &options)?;
+        assert_eq!(data.len(), expected.len());
+
+        let result = BocReader::new().read_inmem(Arc::new(data))?;
+        assert!(result.header.index_included && result.header.has_crc && result.header.has_cache_bits);
+        assert_eq!(root, result.withdraw_single_root()?);
+    }
+    Ok(())
+}
+
+struct CountingSource {
+    data: Vec<u8>,
+    read: Arc<std::sync::atomic::AtomicUsize>,
+}
+
+impl BocSource for CountingSource {
+    fn size(&self) -> u64 {
+        self.data.size()
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Status {
+        self.read.fetch_add(buf.len(), std::sync::atomic::Ordering::Relaxed);
+        self.data.read_at(offset, buf)
+    }
+}
+
+// fails to read data beyond the limit
+struct FailingSource {
+    data: Vec<u8>,
+    limit: usize,
+}
+
+impl BocSource for FailingSource {
+    fn size(&self) -> u64 {
+        self.data.size()
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Status {
+        if offset as usize + buf.len() > self.limit {
+            fail!("read of {} bytes at {} is beyond the limit {}", buf.len(), offset, self.limit)
+        }
+        self.data.read_at(offset, buf)
+    }
+}
+
+#[test]
+fn test_boc_read_lazy() -> Result<()> {
+    let files = ["key_block.boc", "shard_state.boc", "medium.boc", "config.boc"];
+    for name in files {
+        let root = read_single_root_boc(read(format!("src/tests/data/{}", name))?)?;
+        let proof = crate::MerkleProof::create(&root, |hash| *hash != root.reference(0).unwrap().repr_hash())?;
+        let proof = crate::Serializable::serialize(&proof)?;
+        for root in [root, proof] {
+            for store_hashes in [false, true] {
+                for has_cache_bits in [false, true] {
+                    let options = BocWriteOptions {
+                        has_index: true,
+                        has_cache_bits,
+                        store_hashes,
+                        ..Default::default()
+                    };
+                    let mut data = Vec::new();
+                    BocWriter::with_root(&root)?.write_with_options(&mut data, &options)?;
+
+                    let lazy = BocReader::new().read_lazy(data.clone())?.withdraw_single_root()?;
+                    assert_eq!(lazy.repr_hash(), root.repr_hash(), "{}", name);
+                    assert_eq!(lazy.repr_depth(), root.repr_depth(), "{}", name);
+                    let inmem = BocReader::new().read_inmem(Arc::new(data.clone()))?.withdraw_single_root()?;
+                    assert_eq!(lazy.store_hashes(), inmem.store_hashes());
+                    assert_eq!(lazy.count_cells(usize::MAX)?, root.count_cells(usize::MAX)?);
+                    // re-serialization walks all the lazy cells
+                    let mut restored = Vec::new();
+                    BocWriter::with_root(&lazy)?.write_with_options(&mut restored, &options)?;
+                    assert_eq!(data, restored, "{}", name);
+
+                    let stream = BocStreamSource::new(Cursor::new(data))?;
+                    let lazy = BocReader::new().read_lazy(stream)?.withdraw_single_root()?;
+                    assert_eq!(lazy, root);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn build_lazy_test_map() -> Result<HashmapE> {
+    let mut map = HashmapE::with_bit_len(32);
+    for i in 0..1000_u32 {
+        map.set_builder(SliceData::from_raw(i.to_be_bytes().to_vec(), 32), &BuilderData::with_raw(i.to_be_bytes().repeat(16), 512)?)?;
+    }
+    Ok(map)
+}
+
+#[test]
+fn test_boc_read_lazy_loads_only_visited_cells() -> Result<()> {
+    let map = build_lazy_test_map()?;
+    let root = map.data().cloned().unwrap();
+    for store_hashes in [false, true] {
+        let options = BocWriteOptions { store_hashes, ..BocWriteOptions::with_index(true, false) };
+        let mut data = Vec::new();
+        BocWriter::with_root(&root)?.write_with_options(&mut data, &options)?;
+        let size = data.len();
+        let read = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let source = CountingSource { data, read: read.clone() };
+
+        let lazy = BocReader::new().read_lazy(source)?.withdraw_single_root()?;
+        let lazy_map = HashmapE::with_hashmap(32, Some(lazy));
+        let key = SliceData::from_raw(777_u32.to_be_bytes().to_vec(), 32);
+        assert_eq!(lazy_map.get(key.clone())?, map.get(key)?);
+        let read = read.load(std::sync::atomic::Ordering::Relaxed);
+        // hashes which are not stored are not calculated by the lookup
+        assert!(read < size / 10, "read {} of {} bytes", read, size);
+    }
+
+    // account lookup in a real shard state
+    let root = read_single_root_boc(read("src/tests/data/shard_state.boc")?)?;
+    let mut ids = Vec::new();
+    crate::ShardStateUnsplit::construct_from_cell(root.clone())?.read_accounts()?.iterate_with_keys(|id: UInt256, _| {
+        ids.push(id);
+        Ok(true)
+    })?;
+    let id = AccountId::from(ids[0].clone());
+    let mut data = Vec::new();
+    let options = BocWriteOptions { store_hashes: true, ..BocWriteOptions::with_index(true, false) };
+    BocWriter::with_root(&root)?.write_with_options(&mut data, &options)?;
+    let lazy = BocReader::new().read_lazy(data)?.withdraw_single_root()?;
+    let account = crate::ShardStateUnsplit::construct_from_cell(lazy)?.read_accounts()?.account(&id)?.unwrap();
+    let expected = crate::ShardStateUnsplit::construct_from_cell(root)?.read_accounts()?.account(&id)?.unwrap();
+    assert_eq!(account.account_cell(), expected.account_cell());
+    assert_eq!(account.last_trans_hash(), expected.last_trans_hash());
+    Ok(())
+}
+
+#[test]
+fn test_boc_read_lazy_hashes_calculated_once() -> Result<()> {
+    // cells of the map don't have stored hashes
+    let root = build_lazy_test_map()?.data().cloned().unwrap();
+    let mut data = Vec::new();
+    BocWriter::with_root(&root)?.write_with_options(&mut data, &BocWriteOptions::with_index(true, false))?;
+    let size = data.len();
+    let read = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let source = CountingSource { data: data.clone(), read: read.clone() };
+
+    // every cell is loaded once more to be visited, its hashes are taken from the calculated ones
+    let lazy = BocReader::new().read_lazy(source)?.withdraw_single_root()?;
+    let mut stack = vec!((lazy, root.clone()));
+    while let Some((lazy, cell)) = stack.pop() {
+        assert_eq!(lazy.repr_hash(), cell.repr_hash());
+        for i in 0..cell.references_count() {
+            stack.push((lazy.reference(i)?, cell.reference(i)?));
+        }
+    }
+    let read = read.load(std::sync::atomic::Ordering::Relaxed);
+    assert!(read < size * 3, "read {} of {} bytes", read, size);
+
+    // hashes are calculated on demand, errors of the source are reported by fallible hash requests
+    let source = FailingSource { data: data.clone(), limit: size / 2 };
+    let lazy = BocReader::new().read_lazy(source)?.withdraw_single_root()?;
+    assert!((0..lazy.references_count()).any(|i| lazy.reference_repr_hash(i).is_err()));
+    let mut data = Vec::new();
+    let options = BocWriteOptions { store_hashes: true, ..BocWriteOptions::with_index(true, false) };
+    BocWriter::with_root(&root)?.write_with_options(&mut data, &options)?;
+    let size = data.len();
+    let lazy = BocReader::new().read_lazy(FailingSource { data, limit: size / 2 })?.withdraw_single_root()?;
+    assert_eq!(lazy.repr_hash(), root.repr_hash());
+    assert!(lazy.count_cells(usize::MAX).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_boc_read_lazy_without_index() -> Result<()> {
+    let mut data = Vec::new();
+    BocWriter::with_root(&build_tree())?.write(&mut data)?;
+    assert!(BocReader::new().read_lazy(data).is_err(), "lazy reading requires index");
+    Ok(())
+}
+
+#[test]
+fn test_boc_read_progress() -> Result<()> {
+    let data = read("src/tests/data/medium.boc")?;
+    let mut with_index = Vec::new();
+    BocWriter::with_roots(read_boc(&data)?.roots)?
+        .write_with_options(&mut with_index, &BocWriteOptions::with_index(true, true))?;
+
+    for (data, inmem) in [(&data, false), (&data, true), (&with_index, false), (&with_index, true)] {
+        let header = read_boc(data)?.header;
+        assert!(header.cells_count > PROGRESS_STEP);
+        let reports = std::cell::RefCell::new(Vec::new());
+        let progress = |progress: &BocReadProgress| reports.borrow_mut().push(*progress);
+        let mut reader = BocReader::new().set_progress(&progress);
+        if inmem {
+            reader.read_inmem(Arc::new(data.clone()))?;
+        } else {
+            reader.read(&mut Cursor::new(data))?;
+        }
+        let reports = reports.into_inner();
+        assert_eq!(reports.len(), header.cells_count.div_ceil(PROGRESS_STEP));
+        for pair in reports.windows(2) {
+            assert!(pair[0].cells_parsed < pair[1].cells_parsed);
+            assert!(pair[0].bytes_consumed < pair[1].bytes_consumed);
+        }
+        let last = reports.last().unwrap();
+        assert_eq!(last.cells_parsed, header.cells_count);
+        assert_eq!(last.cells_count, header.cells_count);
+        assert_eq!(last.bytes_total, data.len() as u64);
+        assert_eq!(last.bytes_consumed, data.len() as u64 - header.has_crc as u64 * 4);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_boc_read_limits() -> Result<()> {
+    let roots = vec![build_tree(), build_tree2(1)];
+    let mut data = Vec::new();
+    BocWriter::with_roots(roots.clone())?.write_with_options(&mut data, &BocWriteOptions::with_index(true, true))?;
+    let header = read_boc(&data)?.header;
+
+    let exact = BocReadLimits {
+        max_cells: Some(header.cells_count),
+        max_data_size: Some(header.tot_cells_size),
+        max_roots: Some(2),
+    };
+    assert_eq!(BocReader::new().set_limits(exact.clone()).read(&mut Cursor::new(&data))?.roots, roots);
+
+    let exceeded = [
+        BocReadLimits { max_cells: Some(header.cells_count - 1), ..exact.clone() },
+        BocReadLimits { max_data_size: Some(header.tot_cells_size - 1), ..exact.clone() },
+        BocReadLimits { max_roots: Some(1), ..exact.clone() },
+    ];
+    for limits in exceeded {
+        let results = [
+            BocReader::new().set_limits(limits.clone()).read(&mut Cursor::new(&data)).map(|_| ()),
+            BocReader::new().set_limits(limits.clone()).read_inmem(Arc::new(data.clone())).map(|_| ()),
+            BocReader::new().set_limits(limits.clone()).read_lazy(data.clone()).map(|_| ()),
+        ];
+        for result in results {
+            let err = result.expect_err("limit must be exceeded");
+            assert!(matches!(err.downcast_ref::<BlockError>(), Some(BlockError::BocLimitExceeded(..))), "{}", err);
+        }
+    }
+    Ok(())
+}
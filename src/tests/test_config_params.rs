@@ -936,3 +936,145 @@ fn test_smft_config() {
     let config2 = SmftParams::construct_from_cell(cell).unwrap();
     assert_eq!(config, config2);
 }
+
+#[test]
+fn test_config_param_19() {
+    let mut cp = ConfigParams::default();
+    assert_eq!(cp.global_id().unwrap(), None);
+    write_read_and_assert(ConfigParam19 { global_id: -239 });
+    cp.set_config(ConfigParamEnum::ConfigParam19(ConfigParam19 { global_id: -239 })).unwrap();
+    assert_eq!(cp.global_id().unwrap(), Some(-239));
+}
+
+#[test]
+fn test_size_limits_config() {
+    let mut cp = ConfigParams::default();
+    assert_eq!(cp.size_limits_config().unwrap(), SizeLimitsConfig::default());
+
+    let limits = SizeLimitsConfig {
+        version: 2,
+        max_msg_bits: 1 << 20,
+        max_msg_cells: 1 << 12,
+        max_library_cells: 500,
+        max_vm_data_depth: 256,
+        max_ext_msg_size: 32768,
+        max_ext_msg_depth: 256,
+        max_acc_state_cells: 1 << 15,
+        max_acc_state_bits: (1 << 15) * 1023,
+        max_acc_public_libraries: 128,
+        defer_out_queue_size_limit: 100,
+        max_msg_extra_currencies: 3,
+        max_acc_fixed_prefix_length: 4,
+    };
+    write_read_and_assert(limits.clone());
+    cp.set_config(ConfigParamEnum::ConfigParam43(limits.clone())).unwrap();
+    assert_eq!(cp.size_limits_config().unwrap(), limits);
+
+    // first version has no account limits - they are default
+    let mut b = BuilderData::new();
+    b.append_u8(0x01).unwrap();
+    b.append_u32(1 << 20).unwrap();
+    b.append_u32(1 << 12).unwrap();
+    b.append_u32(500).unwrap();
+    b.append_u16(256).unwrap();
+    b.append_u32(32768).unwrap();
+    b.append_u16(256).unwrap();
+    let cell_v1 = b.into_cell().unwrap();
+    let limits_v1 = SizeLimitsConfig::construct_from_cell(cell_v1.clone()).unwrap();
+    assert_eq!(limits_v1.version, 1);
+    assert_eq!(limits_v1.max_msg_bits, limits.max_msg_bits);
+    assert_eq!(limits_v1.max_ext_msg_depth, limits.max_ext_msg_depth);
+    assert_eq!(limits_v1.max_acc_state_cells, SizeLimitsConfig::default().max_acc_state_cells);
+    assert_eq!(limits_v1.max_acc_fixed_prefix_length, SizeLimitsConfig::default().max_acc_fixed_prefix_length);
+    // first version is written back as it was read
+    let limits_v1 = write_read_and_assert(limits_v1);
+    assert_eq!(limits_v1.serialize().unwrap().repr_hash(), cell_v1.repr_hash());
+    let mut wrong_v1 = limits_v1;
+    wrong_v1.max_acc_state_cells = limits.max_acc_state_cells;
+    wrong_v1.serialize().expect_err("first version has no account limits");
+
+    let mut b = BuilderData::new();
+    b.append_u8(0x03).unwrap();
+    SizeLimitsConfig::construct_from_cell(b.into_cell().unwrap()).expect_err("wrong tag");
+}
+
+#[test]
+fn test_precompiled_contracts_config() {
+    let mut cp = ConfigParams::default();
+    assert_eq!(cp.precompiled_contracts_config().unwrap(), None);
+
+    let mut config = PrecompiledContractsConfig::new();
+    for i in 1..10 {
+        config.add_contract(&UInt256::from([i; 32]), i as u64 * 1000).unwrap();
+    }
+    write_read_and_assert(config.clone());
+    cp.set_config(ConfigParamEnum::ConfigParam45(config.clone())).unwrap();
+    let config = cp.precompiled_contracts_config().unwrap().unwrap();
+    assert_eq!(config.gas_usage(&UInt256::from([5; 32])).unwrap(), Some(5000));
+    assert_eq!(config.gas_usage(&UInt256::from([11; 32])).unwrap(), None);
+}
+
+fn get_bridge_oracles() -> BridgeOracles {
+    let mut oracles = BridgeOracles::default();
+    for _ in 0..5 {
+        oracles.set(&UInt256::rand(), &UInt256::rand()).unwrap();
+    }
+    oracles
+}
+
+#[test]
+fn test_oracle_bridge_params() {
+    let mut cp = ConfigParams::default();
+    let params = OracleBridgeParams {
+        bridge_address: UInt256::rand(),
+        oracle_multisig_address: UInt256::rand(),
+        oracles: get_bridge_oracles(),
+        external_chain_address: UInt256::rand(),
+    };
+    write_read_and_assert(params.clone());
+
+    cp.set_config(ConfigParamEnum::ConfigParam72(params.clone())).unwrap();
+    assert_eq!(cp.oracle_bridge_params(71).unwrap(), None);
+    assert_eq!(cp.oracle_bridge_params(72).unwrap(), Some(params));
+    cp.oracle_bridge_params(74).expect_err("not an oracle bridge param");
+    write_read_and_assert(cp);
+}
+
+#[test]
+fn test_jetton_bridge_params() {
+    let mut cp = ConfigParams::default();
+    let v0 = JettonBridgeParams::V0(JettonBridgeParamsV0 {
+        bridge_address: UInt256::rand(),
+        oracles_address: UInt256::rand(),
+        oracles: get_bridge_oracles(),
+        state_flags: 1,
+        burn_bridge_fee: Grams::from(1_000_000_000u64),
+    });
+    write_read_and_assert(v0.clone());
+    let v1 = JettonBridgeParams::V1(JettonBridgeParamsV1 {
+        bridge_address: UInt256::rand(),
+        oracles_address: UInt256::rand(),
+        oracles: get_bridge_oracles(),
+        state_flags: 0,
+        prices: JettonBridgePrices {
+            bridge_burn_fee: Grams::from(1u64),
+            bridge_mint_fee: Grams::from(2u64),
+            wallet_min_tons_for_storage: Grams::from(3u64),
+            wallet_gas_consumption: Grams::from(4u64),
+            minter_min_tons_for_storage: Grams::from(5u64),
+            discover_gas_consumption: Grams::from(6u64),
+        },
+        external_chain_address: UInt256::rand(),
+    });
+    write_read_and_assert(v1.clone());
+    assert_eq!(v1.state_flags(), 0);
+    assert_eq!(v0.state_flags(), 1);
+
+    cp.set_config(ConfigParamEnum::ConfigParam79(v0.clone())).unwrap();
+    cp.set_config(ConfigParamEnum::ConfigParam82(v1.clone())).unwrap();
+    assert_eq!(cp.jetton_bridge_params(79).unwrap(), Some(v0));
+    assert_eq!(cp.jetton_bridge_params(81).unwrap(), None);
+    assert_eq!(cp.jetton_bridge_params(82).unwrap(), Some(v1));
+    cp.jetton_bridge_params(80).expect_err("not a jetton bridge param");
+    write_read_and_assert(cp);
+}
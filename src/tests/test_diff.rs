@@ -0,0 +1,220 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    read_single_root_boc, AccountId, BuilderData, ConfigParam18, CreatorStats, CurrencyCollection,
+    Deserializable, HashmapAugType, MsgAddressInt, ProcessedUpto, StateInit, StoragePrices,
+};
+
+fn cell(data: &[u8], refs: &[Cell]) -> Cell {
+    let mut builder = BuilderData::with_raw(data.to_vec(), data.len() * 8).unwrap();
+    for reference in refs {
+        builder.checked_append_reference(reference.clone()).unwrap();
+    }
+    builder.into_cell().unwrap()
+}
+
+fn fields(diffs: &[FieldDiff]) -> Vec<(&str, DiffKind)> {
+    diffs.iter().map(|diff| (diff.field.as_str(), diff.kind)).collect()
+}
+
+#[test]
+fn test_diff_cells() {
+    let leaf1 = cell(&[1], &[]);
+    let leaf2 = cell(&[2], &[]);
+    let leaf3 = cell(&[3], &[]);
+    let old = cell(&[0], &[cell(&[10], std::slice::from_ref(&leaf1)), leaf2.clone()]);
+
+    assert!(diff_cells(&old, &old).is_empty());
+
+    // changed deep leaf only
+    let new = cell(&[0], &[cell(&[10], std::slice::from_ref(&leaf3)), leaf2.clone()]);
+    let diffs = diff_cells(&old, &new);
+    assert_eq!(diffs, vec![CellDiff {
+        path: vec![0, 0], kind: DiffKind::Changed, old: Some(leaf1.clone()), new: Some(leaf3.clone())
+    }]);
+
+    // changed root data and added reference
+    let new = cell(&[5], &[cell(&[10], std::slice::from_ref(&leaf1)), leaf2.clone(), leaf3.clone()]);
+    let diffs = diff_cells(&old, &new);
+    assert_eq!(diffs.len(), 2);
+    assert_eq!((diffs[0].path.as_slice(), diffs[0].kind), (&[][..], DiffKind::Changed));
+    assert_eq!(diffs[1], CellDiff { path: vec![2], kind: DiffKind::Added, old: None, new: Some(leaf3.clone()) });
+
+    // removed reference
+    let new = cell(&[0], &[cell(&[10], &[])]);
+    let diffs = diff_cells(&old, &new);
+    assert_eq!(diffs.len(), 4);
+    assert_eq!((diffs[0].path.as_slice(), diffs[0].kind), (&[][..], DiffKind::Changed));
+    assert_eq!((diffs[1].path.as_slice(), diffs[1].kind), (&[0][..], DiffKind::Changed));
+    assert_eq!(diffs[2], CellDiff { path: vec![0, 0], kind: DiffKind::Removed, old: Some(leaf1), new: None });
+    assert_eq!(diffs[3], CellDiff { path: vec![1], kind: DiffKind::Removed, old: Some(leaf2), new: None });
+
+    // the same pair of cells shared in the tree is reported once
+    let old = cell(&[0], &[leaf3.clone(), leaf3.clone()]);
+    let new = cell(&[0], &[cell(&[4], &[]), cell(&[4], &[])]);
+    assert_eq!(diff_cells(&old, &new).len(), 1);
+}
+
+#[test]
+fn test_diff_accounts() {
+    let addr = MsgAddressInt::standard(0, AccountId::from([0x11; 32]));
+    let state_init = StateInit {
+        code: Some(cell(&[1], &[])),
+        data: Some(cell(&[2], &[])),
+        ..Default::default()
+    };
+    let old = Account::active_by_init_code_hash(
+        addr.clone(), CurrencyCollection::with_grams(100), 10, state_init, false
+    ).unwrap();
+    assert!(diff_accounts(&old, &old).unwrap().is_empty());
+
+    let mut state_init = old.state_init().unwrap().clone();
+    state_init.set_data(cell(&[3], &[]));
+    state_init.set_library_code(cell(&[4], &[]), true).unwrap();
+    let mut balance = CurrencyCollection::with_grams(200);
+    balance.set_other(7, 5).unwrap();
+    let new = Account::active_by_init_code_hash(addr, balance, 10, state_init, false).unwrap();
+    let diffs = diff_accounts(&old, &new).unwrap();
+    let lib_field = format!("state.library[{:x}]", cell(&[4], &[]).repr_hash());
+    assert_eq!(fields(&diffs), vec![
+        ("storage_stat.used", DiffKind::Changed),
+        ("balance.grams", DiffKind::Changed),
+        ("balance.other[7]", DiffKind::Added),
+        ("state.data", DiffKind::Changed),
+        (lib_field.as_str(), DiffKind::Added),
+    ]);
+    assert_eq!(diffs[1].old.as_deref(), Some("Grams(100)"));
+    assert_eq!(diffs[1].new.as_deref(), Some("Grams(200)"));
+
+    let diffs = diff_accounts(&old, &Account::default()).unwrap();
+    assert_eq!(fields(&diffs), vec![("status", DiffKind::Changed)]);
+}
+
+#[test]
+fn test_diff_shard_states() {
+    let old = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    assert!(diff_shard_states(&old, &old).unwrap().is_empty());
+
+    let mut new = old.clone();
+    new.set_seq_no(old.seq_no() + 1);
+    let mut accounts = old.read_accounts().unwrap();
+    let mut id = UInt256::default();
+    accounts.iterate_with_keys(|key: UInt256, _| {
+        id = key;
+        Ok(false)
+    }).unwrap();
+    let shard_acc = accounts.get(&id).unwrap().unwrap();
+    let mut account = shard_acc.read_account().unwrap();
+    account.add_funds(&CurrencyCollection::with_grams(1)).unwrap();
+    accounts.insert(0, &account, shard_acc.last_trans_hash().clone(), shard_acc.last_trans_lt() + 1).unwrap();
+    let new_id = UInt256::from([0x33; 32]);
+    let new_acc = Account::with_address_and_ballance(
+        &MsgAddressInt::standard(old.shard().workchain_id() as i8, AccountId::from(new_id.clone())),
+        &CurrencyCollection::with_grams(5)
+    );
+    accounts.insert(0, &new_acc, UInt256::default(), 0).unwrap();
+    new.write_accounts(&accounts).unwrap();
+
+    let diffs = diff_shard_states(&old, &new).unwrap();
+    let (acc_field, new_acc_field) = (format!("accounts[{:x}]", id), format!("accounts[{:x}]", new_id));
+    let lt_field = format!("{}.last_trans_lt", acc_field);
+    let balance_field = format!("{}.balance.grams", acc_field);
+    let mut expected = vec![
+        ("seq_no", DiffKind::Changed),
+        (lt_field.as_str(), DiffKind::Changed),
+        (balance_field.as_str(), DiffKind::Changed),
+        (new_acc_field.as_str(), DiffKind::Added),
+    ];
+    if id > new_id {
+        expected.swap(1, 3);
+        expected.swap(2, 3);
+    }
+    assert_eq!(fields(&diffs), expected);
+}
+
+#[test]
+fn test_diff_mc_states_per_key() {
+    let bytes = std::fs::read("src/tests/data/block_with_ss/shard-states/2661").unwrap();
+    let old = ShardStateUnsplit::construct_from_cell(read_single_root_boc(bytes).unwrap()).unwrap();
+    let mut new = old.clone();
+
+    let mut queue_info = old.read_out_msg_queue_info().unwrap();
+    let key = ProcessedInfoKey::with_params(0x8000_0000_0000_0000, 1_000_000);
+    queue_info.proc_info_mut().set(&key, &ProcessedUpto::default()).unwrap();
+    new.write_out_msg_queue_info(&queue_info).unwrap();
+
+    let mut extra = old.read_custom().unwrap().unwrap();
+    let mut shard = None;
+    extra.shards.iterate_shards(|ident, _| {
+        shard = Some(ident);
+        Ok(false)
+    }).unwrap();
+    let shard = shard.unwrap();
+    extra.shards.update_shard(&shard, |mut descr| {
+        descr.seq_no += 1;
+        Ok(descr)
+    }).unwrap();
+    extra.shards.add_workchain(7, 0, UInt256::default(), UInt256::default(), None).unwrap();
+    let (seq_no, block_ref, aug) = extra.prev_blocks.get_max(false).unwrap()
+        .map(|(seq_no, block_ref)| {
+            let aug = extra.prev_blocks.get_with_aug(&seq_no).unwrap().unwrap().1;
+            (seq_no, block_ref, aug)
+        })
+        .unwrap();
+    extra.prev_blocks.set(&(seq_no + 1), &block_ref, &aug).unwrap();
+    let stats = extra.block_create_stats.get_or_insert_with(Default::default);
+    let creator = UInt256::from([0x44; 32]);
+    stats.counters.set(&creator, &CreatorStats::default()).unwrap();
+    new.write_custom(Some(&extra)).unwrap();
+
+    let diffs = diff_shard_states(&old, &new).unwrap();
+    let proc_info_field = format!("out_msg_queues_info.proc_info[{:016x}:{}]", key.shard, key.mc_seqno);
+    let shard_field = format!("custom.shards[{}]", shard);
+    let new_wc_field = format!("custom.shards[{}]", ShardIdent::full(7));
+    let prev_blocks_field = format!("custom.prev_blocks[{}]", seq_no + 1);
+    let stats_field = format!("custom.block_create_stats[{:x}]", creator);
+    assert_eq!(fields(&diffs), vec![
+        (proc_info_field.as_str(), DiffKind::Added),
+        (shard_field.as_str(), DiffKind::Changed),
+        (new_wc_field.as_str(), DiffKind::Added),
+        (prev_blocks_field.as_str(), DiffKind::Added),
+        (stats_field.as_str(), DiffKind::Added),
+    ]);
+}
+
+#[test]
+fn test_diff_config_params() {
+    let root = read_single_root_boc(std::fs::read("src/tests/data/config.boc").unwrap()).unwrap();
+    let old = ConfigParams::with_address_and_params(UInt256::from([1; 32]), Some(root));
+    assert!(diff_config_params(&old, &old).unwrap().is_empty());
+
+    let mut new = old.clone();
+    let mut param18 = ConfigParam18::default();
+    param18.insert(&StoragePrices { utime_since: 0, bit_price_ps: 1, cell_price_ps: 500, ..Default::default() }).unwrap();
+    new.set_config(ConfigParamEnum::ConfigParam18(param18.clone())).unwrap();
+    new.set_config(ConfigParamEnum::ConfigParam43(Default::default())).unwrap();
+    new.config_addr = UInt256::from([2; 32]);
+
+    let diffs = diff_config_params(&old, &new).unwrap();
+    assert_eq!(fields(&diffs), vec![
+        ("config_addr", DiffKind::Changed),
+        ("param18", DiffKind::Changed),
+        ("param43", DiffKind::Added),
+    ]);
+    assert_eq!(diffs[1].new, Some(format!("{:?}", ConfigParamEnum::ConfigParam18(param18))));
+
+    let diffs = diff_config_params(&new, &old).unwrap();
+    assert_eq!(diffs[2].kind, DiffKind::Removed);
+}
@@ -0,0 +1,214 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    read_single_root_boc, read_test_config, AccountId, Block, CommonMessage, ConfigParam18,
+    ConfigParamEnum, CurrencyCollection, ExternalInboundMessageHeader, Grams, HashmapAugType,
+    HashmapType, InternalMessageHeader, MsgAddressExt, ShardStateUnsplit, TrComputePhase,
+    Transaction, TransactionDescr,
+};
+
+// checks fees of all ordinary transactions of the block against values calculated by the node
+fn check_block_fees(calc: &FeesCalculator, filename: &str) -> usize {
+    let root = read_single_root_boc(std::fs::read(filename).unwrap()).unwrap();
+    let block = Block::construct_from_cell(root).unwrap();
+    let mut count = 0;
+    block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+        account_block.transactions().iterate_slices(|_, tr| {
+            let tr = Transaction::construct_from_cell(tr.reference(0)?)?;
+            let descr = match tr.read_description()? {
+                TransactionDescr::Ordinary(descr) => descr,
+                _ => return Ok(true)
+            };
+            let vm = match &descr.compute_ph {
+                TrComputePhase::Vm(vm) => vm,
+                _ => return Ok(true)
+            };
+            let in_msg = tr.read_in_msg()?.unwrap();
+            let in_msg = in_msg.get_std()?;
+            let is_masterchain = in_msg.dst_ref().unwrap().is_masterchain();
+
+            let gas_fee = calc.calc_gas_fee(vm.gas_used.as_u64(), is_masterchain);
+            assert_eq!(gas_fee, vm.gas_fees.as_u128());
+
+            let (mut total_fwd_fees, mut total_action_fees) = (0, 0);
+            tr.out_msgs.iterate_slices(|slice| {
+                let msg = CommonMessage::construct_from_cell(slice.reference(0)?)?;
+                let msg = msg.get_std()?;
+                let fees = calc.calc_msg_fwd_fees(msg)?;
+                if let Some(header) = msg.int_header() {
+                    assert_eq!(fees.remaining_fee, header.fwd_fee.as_u128());
+                    assert_eq!(fees.ihr_fee, header.ihr_fee.as_u128());
+                }
+                total_fwd_fees += fees.fwd_fee + fees.ihr_fee;
+                total_action_fees += fees.mine_fee;
+                Ok(true)
+            })?;
+            if let Some(action) = &descr.action {
+                assert_eq!(action.total_fwd_fees.map_or(0, |fee| fee.as_u128()), total_fwd_fees);
+                assert_eq!(action.total_action_fees.map_or(0, |fee| fee.as_u128()), total_action_fees);
+            }
+
+            let import_fee = match in_msg.is_inbound_external() {
+                true => calc.calc_import_fee(in_msg)?,
+                false => 0
+            };
+            let storage_fee = descr.storage_ph.as_ref().map_or(0, |ph| ph.storage_fees_collected.as_u128());
+            assert_eq!(
+                storage_fee + import_fee + gas_fee + total_action_fees,
+                tr.total_fees().grams.as_u128()
+            );
+            count += 1;
+            Ok(true)
+        })?;
+        Ok(true)
+    }).unwrap();
+    count
+}
+
+#[test]
+fn test_fees_golden_transactions() {
+    let calc = FeesCalculator::with_config(&read_test_config()).unwrap();
+    let files = [
+        "src/tests/data/9C2B3FC5AD455917D374CFADBED8FC2343E31A27C1DF2EB29E84404FA96DE9F8.boc",
+        "src/tests/data/9D134C5ABBC859B6ED7A7201757BA4CB5E837641C6E5AEACA31DDD4B1B3D51A2.boc",
+        "src/tests/data/3F7B3F53F9F0684E29D67B813E2197689FE725A77491BD50A5438EA66D4341E0.boc",
+    ];
+    for file in files {
+        assert_ne!(check_block_fees(&calc, file), 0, "{}", file);
+    }
+}
+
+// storage fee of the account state before the block, restored from the old part of the block's
+// state update, is checked against the storage phase of the first transaction of the account
+#[test]
+fn test_fees_golden_storage() {
+    let calc = FeesCalculator::with_config(&read_test_config()).unwrap();
+    let files = [
+        "src/tests/data/9C2B3FC5AD455917D374CFADBED8FC2343E31A27C1DF2EB29E84404FA96DE9F8.boc",
+        "src/tests/data/9D134C5ABBC859B6ED7A7201757BA4CB5E837641C6E5AEACA31DDD4B1B3D51A2.boc",
+    ];
+    for file in files {
+        let block = Block::construct_from_file(file).unwrap();
+        let prev_state = ShardStateUnsplit::construct_from_cell(block.read_state_update().unwrap().old).unwrap();
+        let accounts = prev_state.read_accounts().unwrap();
+        let mut count = 0;
+        block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+            // accounts created by the block have no storage fee
+            let account = match accounts.account(account_block.account_id())? {
+                Some(shard_account) => shard_account.read_account()?,
+                None => return Ok(true)
+            };
+            account_block.transaction_iterate(|tr| {
+                if let TransactionDescr::Ordinary(descr) = tr.read_description()? {
+                    let storage_ph = descr.storage_ph.unwrap();
+                    let expected = storage_ph.storage_fees_collected.as_u128()
+                        + storage_ph.storage_fees_due.map_or(0, |due| due.as_u128());
+                    assert_ne!(expected, 0);
+                    assert_eq!(calc.calc_account_storage_fee(&account, tr.now())?, expected);
+                    count += 1;
+                }
+                Ok(false)
+            })?;
+            Ok(true)
+        }).unwrap();
+        assert_ne!(count, 0, "{}", file);
+    }
+}
+
+fn config_with_storage_prices() -> ConfigParams {
+    let mut config = read_test_config();
+    let mut param18 = ConfigParam18::default();
+    param18.insert(&StoragePrices {
+        utime_since: 0, bit_price_ps: 1, cell_price_ps: 500, mc_bit_price_ps: 1000, mc_cell_price_ps: 500000
+    }).unwrap();
+    param18.insert(&StoragePrices {
+        utime_since: 1000, bit_price_ps: 2, cell_price_ps: 1000, mc_bit_price_ps: 2000, mc_cell_price_ps: 1000000
+    }).unwrap();
+    config.set_config(ConfigParamEnum::ConfigParam18(param18)).unwrap();
+    config
+}
+
+#[test]
+fn test_calc_storage_fee() {
+    let calc = FeesCalculator::with_config(&config_with_storage_prices()).unwrap();
+
+    // never paid and paid in future
+    assert_eq!(calc.calc_storage_fee(2, 100, 0, 1500, false), 0);
+    assert_eq!(calc.calc_storage_fee(2, 100, 1500, 1000, false), 0);
+
+    // inside one interval
+    assert_eq!(calc.calc_storage_fee(2, 100, 100, 600, false), (500 * (2 * 500 + 100) + 0xffff) >> 16);
+    assert_eq!(calc.calc_storage_fee(2, 100, 1100, 1600, true), (500 * (2 * 1000000 + 100 * 2000) + 0xffff) >> 16);
+
+    // across both intervals
+    let fee = 500 * (2 * 500 + 100) + 500 * (2 * 1000 + 100 * 2);
+    assert_eq!(calc.calc_storage_fee(2, 100, 500, 1500, false), (fee + 0xffff) >> 16);
+    let fee = 500 * (2 * 500000 + 100 * 1000) + 500 * (2 * 1000000 + 100 * 2000);
+    assert_eq!(calc.calc_storage_fee(2, 100, 500, 1500, true), (fee + 0xffff) >> 16);
+}
+
+#[test]
+fn test_calc_fees() {
+    let config = config_with_storage_prices();
+    let calc = FeesCalculator::with_config(&config).unwrap();
+
+    let addr = MsgAddressInt::standard(0, AccountId::from([0x11; 32]));
+    let mut account = Account::with_address_and_ballance(&addr, &CurrencyCollection::with_grams(1_000_000_000));
+    account.update_storage_stat().unwrap();
+    account.set_last_paid(500);
+    account.set_due_payment(Some(Grams::from(7u64)));
+    let used = account.storage_info().unwrap().used().clone();
+    let storage_fee = calc.calc_storage_fee(used.cells(), used.bits(), 500, 1500, false) + 7;
+    assert_ne!(storage_fee, 7);
+    assert_eq!(calc.calc_account_storage_fee(&account, 1500).unwrap(), storage_fee);
+
+    // external inbound message pays import fee
+    let msg = Message::with_ext_in_header(ExternalInboundMessageHeader::new(MsgAddressExt::default(), addr.clone()));
+    let fees = calc.calc_fees(&account, &msg, 1500).unwrap();
+    assert_eq!(fees.storage_fee, storage_fee);
+    assert_eq!(fees.import_fee, config.fwd_prices(false).unwrap().lump_price as u128);
+    assert_eq!(fees.total(), fees.storage_fee + fees.import_fee);
+    assert!(calc.calc_msg_fwd_fees(&msg).is_err());
+
+    // internal message pays forward fees split between shards
+    let dst = MsgAddressInt::standard(0, AccountId::from([0x22; 32]));
+    let mut header = InternalMessageHeader::with_addresses(addr.clone(), dst, CurrencyCollection::with_grams(100));
+    header.ihr_disabled = false;
+    let msg = Message::with_int_header(header);
+    let prices = config.fwd_prices(false).unwrap();
+    let fees = calc.calc_fees(&account, &msg, 1500).unwrap();
+    let fwd_fee = prices.lump_price as u128;
+    assert_eq!(fees.import_fee, 0);
+    assert_eq!(fees.action_fee, (fwd_fee * prices.first_frac as u128) >> 16);
+    assert_eq!(fees.fwd_fee + fees.action_fee, fwd_fee);
+    assert_eq!(fees.ihr_fee, (fwd_fee * prices.ihr_price_factor as u128) >> 16);
+    assert!(calc.calc_import_fee(&msg).is_err());
+
+    // internal inbound message is paid by its sender
+    let src = MsgAddressInt::standard(0, AccountId::from([0x22; 32]));
+    let msg = Message::with_int_header(InternalMessageHeader::with_addresses(src, addr.clone(), CurrencyCollection::with_grams(100)));
+    let fees = calc.calc_fees(&account, &msg, 1500).unwrap();
+    assert_eq!(fees, FeesBreakdown { storage_fee, ..Default::default() });
+    assert_eq!(fees.total(), storage_fee);
+
+    // masterchain special accounts pay only due payment
+    let config_addr = MsgAddressInt::standard(-1, AccountId::from(config.config_address().unwrap()));
+    let mut account = Account::with_address_and_ballance(&config_addr, &CurrencyCollection::with_grams(1_000_000_000));
+    account.update_storage_stat().unwrap();
+    account.set_last_paid(500);
+    assert!(calc.is_special_account(&config_addr).unwrap());
+    assert!(!calc.is_special_account(&addr).unwrap());
+    assert_eq!(calc.calc_account_storage_fee(&account, 1500).unwrap(), 0);
+}
@@ -0,0 +1,227 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    Account, Block, CommonMessage, HashmapAugType, InRefValue, Message, OutMessages,
+    ShardAccount, ShardStateUnsplit,
+};
+use std::fmt::Debug;
+
+fn json_round_trip<T>(value: &T) -> T
+where
+    T: Serializable + Serialize + DeserializeOwned + PartialEq + Debug
+{
+    let json = to_json_string(value, true).unwrap();
+    let restored: T = from_json_str(&json).unwrap();
+    pretty_assertions::assert_eq!(value, &restored);
+    assert_eq!(
+        Serializable::serialize(value).unwrap().repr_hash(),
+        Serializable::serialize(&restored).unwrap().repr_hash()
+    );
+    restored
+}
+
+#[test]
+fn test_json_block_round_trip() {
+    let paths = std::fs::read_dir("src/tests/data/block_with_transaction").unwrap();
+    for path in paths {
+        let block = Block::construct_from_file(path.unwrap().path()).unwrap();
+        json_round_trip(&block);
+        block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+            account_block.transactions().iterate_objects(|InRefValue(transaction)| {
+                json_round_trip(&transaction);
+                if let Some(in_msg) = transaction.read_in_msg()? {
+                    json_round_trip(&in_msg);
+                }
+                Ok(true)
+            })
+        }).unwrap();
+    }
+}
+
+#[test]
+fn test_json_message_round_trip() {
+    for name in ["wallet-query.boc", "new-wallet-query.boc", "int-msg-query.boc"] {
+        let message = Message::construct_from_file(format!("src/tests/data/{}", name)).unwrap();
+        json_round_trip(&message);
+    }
+}
+
+#[test]
+fn test_json_shard_state_and_account_round_trip() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    json_round_trip(&state);
+    let mut count = 0;
+    state.read_accounts().unwrap().iterate_objects(|shard_account: ShardAccount| {
+        let account: Account = shard_account.read_account()?;
+        json_round_trip(&account);
+        count += 1;
+        Ok(count < 10)
+    }).unwrap();
+    assert!(count > 0);
+    json_round_trip(&Account::default());
+}
+
+#[test]
+fn test_json_dictionaries_as_maps() {
+    let paths = std::fs::read_dir("src/tests/data/block_with_transaction").unwrap();
+    for path in paths {
+        let block = Block::construct_from_file(path.unwrap().path()).unwrap();
+        let json = to_json_value(&block).unwrap();
+        let account_blocks = json["data"]["extra"]["account_blocks"].as_object().unwrap();
+        let mut count = 0;
+        let account_blocks_dict = block.read_extra().unwrap().read_account_blocks().unwrap();
+        account_blocks_dict.iterate_with_keys_and_aug(|account_id: UInt256, account_block, aug| {
+            let item = &account_blocks[&account_id.as_hex_string()];
+            assert_eq!(item["aug"], to_json_value(&aug)?["data"]);
+            account_block.transactions().iterate_with_keys(|lt: u64, InRefValue(transaction)| {
+                let key = SliceData::load_builder(lt.write_to_new_cell()?)?.as_hex_string();
+                let transaction_json = &item["value"]["transactions"][&key]["value"];
+                assert_eq!(transaction_json, &to_json_value(&transaction)?["data"]);
+                let out_msgs = transaction_json["out_msgs"].as_object().unwrap();
+                assert_eq!(out_msgs.len(), transaction.out_msgs.len()?);
+                transaction.out_msgs.iterate_slices_with_keys(|key, mut value| {
+                    let InRefValue(message) = InRefValue::<CommonMessage>::construct_from(&mut value)?;
+                    assert_eq!(out_msgs[&key.as_hex_string()], to_json_value(&message)?["data"]);
+                    Ok(true)
+                })
+            })?;
+            count += 1;
+            Ok(true)
+        }).unwrap();
+        assert!(count > 0);
+        assert_eq!(count, account_blocks.len());
+    }
+}
+
+#[test]
+fn test_json_raw_dictionaries() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let options = JsonOptions { raw_dictionaries: true };
+    let raw = to_json_value_with_options(&state, &options).unwrap();
+    let accounts = state.read_accounts().unwrap();
+    let boc = base64_encode(write_boc(accounts.data().unwrap()).unwrap());
+    assert_eq!(raw["data"]["accounts"], serde_json::json!({ "boc": boc }));
+    let restored: ShardStateUnsplit = from_json_value(raw).unwrap();
+    pretty_assertions::assert_eq!(state, restored);
+
+    // option is applied only to the call it is passed to
+    let json = to_json_value(&state).unwrap();
+    assert!(json["data"]["accounts"].get("boc").is_none());
+    assert_eq!(json["data"]["accounts"].as_object().unwrap().len(), accounts.len().unwrap());
+
+    let empty = to_json_value_with_options(&OutMessages::default(), &options).unwrap();
+    assert_eq!(empty["data"], serde_json::json!({}));
+    let restored: OutMessages = from_json_value(empty).unwrap();
+    assert!(restored.is_empty());
+
+    let mut mixed = json.clone();
+    mixed["data"]["accounts"]["boc"] = serde_json::Value::String(boc);
+    assert!(from_json_value::<ShardStateUnsplit>(mixed).is_err());
+}
+
+#[test]
+fn test_json_augmentation_is_checked() {
+    let state = ShardStateUnsplit::construct_from_file("src/tests/data/shard_state.boc").unwrap();
+    let mut json = to_json_value(&state).unwrap();
+    let restored: ShardStateUnsplit = from_json_value(json.clone()).unwrap();
+    assert_eq!(restored.read_accounts().unwrap().root_extra(), state.read_accounts().unwrap().root_extra());
+
+    let accounts = json["data"]["accounts"].as_object_mut().unwrap();
+    let item = accounts.values_mut().next().unwrap();
+    item["aug"]["balance"]["grams"] = serde_json::Value::String("1".to_string());
+    assert!(from_json_value::<ShardStateUnsplit>(json).is_err());
+}
+
+#[test]
+fn test_json_hashmap_e() {
+    let mut map = HashmapE::with_bit_len(16);
+    for i in 0..10_u16 {
+        let key = SliceData::load_builder(i.write_to_new_cell().unwrap()).unwrap();
+        map.set(key, &SliceData::new(vec!(i as u8, 0x80))).unwrap();
+    }
+    let json = to_json_value(&map).unwrap();
+    assert_eq!(json["data"]["bit_len"], 16);
+    assert_eq!(json["data"]["items"]["0007"], "07");
+    assert_eq!(json["data"]["items"].as_object().unwrap().len(), 10);
+    let restored: HashmapE = from_json_value(json).unwrap();
+    assert_eq!(restored, map);
+
+    let raw = to_json_value_with_options(&map, &JsonOptions { raw_dictionaries: true }).unwrap();
+    let boc = base64_encode(write_boc(map.data().unwrap()).unwrap());
+    assert_eq!(raw["data"]["items"], serde_json::json!({ "boc": boc }));
+    let restored: HashmapE = from_json_value(raw).unwrap();
+    assert_eq!(restored, map);
+
+    let empty = to_json_value(&HashmapE::with_bit_len(16)).unwrap();
+    assert_eq!(empty["data"], serde_json::json!({ "bit_len": 16, "items": {} }));
+    assert_eq!(from_json_value::<HashmapE>(empty).unwrap(), HashmapE::with_bit_len(16));
+}
+
+#[test]
+fn test_json_leaf_forms() {
+    let mut value = CurrencyCollection::with_grams(1_000_000_000);
+    value.set_other(7, 100500).unwrap();
+    value.set_other(1, 42).unwrap();
+    let json = to_json_value(&value).unwrap();
+    assert_eq!(json, serde_json::json!({
+        "version": JSON_FORMAT_VERSION,
+        "data": { "grams": "1000000000", "other": { "1": "42", "7": "100500" } }
+    }));
+    json_round_trip(&value);
+
+    let json = to_json_value(&CurrencyCollection::with_grams(5)).unwrap();
+    assert_eq!(json["data"], serde_json::json!({ "grams": "5" }));
+
+    let addresses = [
+        "0:1111111111111111111111111111111111111111111111111111111111111111",
+        "-1:3333333333333333333333333333333333333333333333333333333333333333",
+        "101_:0:1111111111111111111111111111111111111111111111111111111111111111",
+        "1000:0123456789abcdef0123456789",
+        "101_:1000:0123456789abcdef",
+    ];
+    for address in addresses {
+        let address = MsgAddressInt::from_str(address).unwrap();
+        let json = to_json_value(&address).unwrap();
+        assert_eq!(json["data"], serde_json::Value::String(address.to_string()));
+        json_round_trip(&address);
+    }
+
+    let hash = UInt256::from([0xAB; 32]);
+    let json = to_json_value(&hash).unwrap();
+    assert_eq!(json["data"], serde_json::Value::String("ab".repeat(32)));
+
+    let slice = SliceData::new(vec![0x12, 0x34, 0x80]);
+    let json = to_json_value(&slice).unwrap();
+    assert_eq!(json["data"], serde_json::Value::String("1234".to_string()));
+    let restored: SliceData = from_json_value(json).unwrap();
+    assert_eq!(slice, restored);
+}
+
+#[test]
+fn test_json_version_check() {
+    let json = to_json_value(&Grams::from(17u64)).unwrap();
+    let grams: Grams = from_json_value(json.clone()).unwrap();
+    assert_eq!(grams, Grams::from(17u64));
+
+    let mut wrong = json.clone();
+    wrong["version"] = serde_json::json!(JSON_FORMAT_VERSION + 1);
+    assert!(from_json_value::<Grams>(wrong).is_err());
+
+    let unversioned = serde_json::json!({ "data": "17" });
+    assert!(from_json_value::<Grams>(unversioned).is_err());
+
+    let no_data = serde_json::json!({ "version": JSON_FORMAT_VERSION });
+    assert!(from_json_value::<Grams>(no_data).is_err());
+}
@@ -15,7 +15,8 @@ use super::*;
 use crate::{
     Serializable,
     blocks::{BlkPrevInfo, BlockExtra, ExtBlkRef, ValueFlow},
-    shard::ShardIdent, BuilderData, Cell, CellType, UsageTree, BocReader
+    shard::ShardIdent, BuilderData, Cell, CellType, UsageTree, BocReader, BlockIdExt,
+    HashmapAugType,
 };
 use std::collections::HashSet;
 use std::fs::File;
@@ -641,4 +642,263 @@ depths: 5
        └─Pruned branch   l: 010   bits: 288   refs: 0   data: 0102b82404f6e84b041b25e452b30da10f01437dfd34efbba8b5772e4bc427df01df0001
          hashes: b82404f6e84b041b25e452b30da10f01437dfd34efbba8b5772e4bc427df01df 76e25eb9c2e42ba849c900812b41b31bd3fa9f7588468f75a7c3d8fa3b766dce
          depths: 1 0");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_state_proof_paths() {
+    let (state, state_root) = get_real_ton_state("src/tests/data/shard_state.boc");
+    let mut ids = Vec::new();
+    state.read_accounts().unwrap().iterate_with_keys(|id: UInt256, _| {
+        ids.push(id);
+        Ok(true)
+    }).unwrap();
+    let paths = vec!(
+        StateProofPath::Account(ids[0].clone()),
+        StateProofPath::Account(UInt256::from([0x77; 32])),
+        StateProofPath::ConfigParam(34),
+        StateProofPath::ConfigParam(1000),
+        StateProofPath::OutQueueEntry(OutMsgQueueKey::with_workchain_id_and_prefix(0, 1, UInt256::from([1; 32]))),
+        StateProofPath::ShardDescr(ShardIdent::full(0)),
+    );
+    let proof = MerkleProof::create_for_paths(&state_root, &paths).unwrap();
+    let proof = MerkleProof::construct_from_bytes(&proof.write_to_bytes().unwrap()).unwrap();
+    assert!(proof.proof.count_cells(usize::MAX).unwrap() < state_root.count_cells(usize::MAX).unwrap());
+
+    let values = proof.check_paths(&state_root.repr_hash(), &paths).unwrap();
+    let expected = paths.iter().map(|path| path.read(&state).unwrap()).collect::<Vec<_>>();
+    assert_eq!(values.len(), expected.len());
+    for (value, expected) in values.iter().zip(expected.iter()) {
+        match (value, expected) {
+            // cells inside are virtualized, so compare their hashes
+            (ProofValue::Account(Some(value)), ProofValue::Account(Some(expected))) => {
+                assert_eq!(value.account_cell().repr_hash(), expected.account_cell().repr_hash());
+                assert_eq!(value.read_account().unwrap(), expected.read_account().unwrap());
+            }
+            _ => assert_eq!(value, expected)
+        }
+    }
+    assert!(matches!(values[1], ProofValue::Account(None)));
+    assert!(matches!(values[3], ProofValue::ConfigParam(None)));
+    assert!(matches!(values[4], ProofValue::OutQueueEntry(None)));
+
+    // wrong root
+    proof.check_paths(&UInt256::from([1; 32]), &paths).expect_err("root hash must be checked");
+    // value which is not in the proof
+    if ids.len() > 1 {
+        proof.check_paths(&state_root.repr_hash(), &[StateProofPath::Account(ids[1].clone())])
+            .expect_err("account is pruned in the proof");
+    }
+    proof.check_paths(&state_root.repr_hash(), &[StateProofPath::ConfigParam(15)])
+        .expect_err("config param is pruned in the proof");
+}
+
+#[test]
+fn test_block_proof_paths() {
+    let (block, block_root) = get_real_ton_block("src/tests/data/key_block.boc");
+    let extra = block.read_extra().unwrap();
+    let tr = get_tr_from_block(&block);
+    let mut in_msg_hash = None;
+    extra.read_in_msg_descr().unwrap().iterate_with_keys(|key: UInt256, _| {
+        in_msg_hash = Some(key);
+        Ok(false)
+    }).unwrap();
+    let mut shards = Vec::new();
+    extra.read_custom().unwrap().unwrap().shards().iterate_shards(|shard, _| {
+        shards.push(shard);
+        Ok(true)
+    }).unwrap();
+
+    let paths = vec!(
+        BlockProofPath::Info,
+        BlockProofPath::Transaction { account: tr.account_id().clone().get_next_hash().unwrap(), lt: tr.logical_time() },
+        BlockProofPath::Transaction { account: UInt256::from([0x77; 32]), lt: 1 },
+        BlockProofPath::InMsg(in_msg_hash.unwrap()),
+        BlockProofPath::OutMsg(UInt256::from([0x77; 32])),
+        BlockProofPath::ConfigParam(34),
+        BlockProofPath::ShardDescr(shards[0].clone()),
+    );
+    let proof = MerkleProof::create_for_paths(&block_root, &paths).unwrap();
+    let values = proof.check_paths(&block_root.repr_hash(), &paths).unwrap();
+
+    assert_eq!(values[0], ProofValue::BlockInfo(block.read_info().unwrap()));
+    match &values[1] {
+        ProofValue::Transaction(Some(value)) => assert_eq!(value.hash().unwrap(), tr.hash().unwrap()),
+        value => panic!("unexpected value {:?}", value)
+    }
+    assert_eq!(values[2], ProofValue::Transaction(None));
+    assert!(matches!(&values[3], ProofValue::InMsg(Some(_))));
+    assert_eq!(values[4], ProofValue::OutMsg(None));
+    assert_eq!(values[5], paths[5].read(&block).unwrap());
+    assert!(matches!(&values[5], ProofValue::ConfigParam(Some(_))));
+    assert_eq!(values[6], paths[6].read(&block).unwrap());
+
+    proof.check_paths(&block_root.repr_hash(), &[BlockProofPath::ConfigParam(15)])
+        .expect_err("config param is pruned in the proof");
+}
+
+#[test]
+fn test_config_params_proof() {
+    let (block, block_root) = get_real_ton_block("src/tests/data/key_block.boc");
+    let info = block.read_info().unwrap();
+    let config = block.read_extra().unwrap().read_custom().unwrap().unwrap().config().cloned().unwrap();
+    let block_id = BlockIdExt::with_params(info.shard().clone(), info.seq_no(), block_root.repr_hash(), UInt256::default());
+
+    let params = [34, 20, 21, 32, 1000];
+    let proof = create_config_params_proof(&block_root, &params).unwrap();
+    let proof = MerkleProof::construct_from_bytes(&proof.write_to_bytes().unwrap()).unwrap();
+    let values = check_config_params_proof(&proof, &block_id, &params).unwrap();
+    assert_eq!(values.len(), params.len());
+    for (index, value) in params.iter().zip(values) {
+        assert_eq!(value, config.config(*index).unwrap());
+    }
+    assert!(matches!(check_config_params_proof(&proof, &block_id, &[34]).unwrap()[0], Some(ConfigParamEnum::ConfigParam34(_))));
+
+    let mut wrong_id = block_id.clone();
+    wrong_id.seq_no += 1;
+    check_config_params_proof(&proof, &wrong_id, &params).expect_err("seq_no must be checked");
+    wrong_id.root_hash = UInt256::from([1; 32]);
+    check_config_params_proof(&proof, &wrong_id, &params).expect_err("root hash must be checked");
+    check_config_params_proof(&proof, &block_id, &[15]).expect_err("param 15 is pruned");
+}
+
+#[test]
+fn test_state_config_params_proof() {
+    let (state, state_root) = get_real_ton_state("src/tests/data/shard_state.boc");
+    let config = state.read_custom().unwrap().unwrap().config().clone();
+
+    // masterchain block which results in the state
+    let update = MerkleUpdate::create(&Cell::default(), &state_root).unwrap();
+    let mut info = BlockInfo::new();
+    info.set_shard(state.shard().clone());
+    info.set_seq_no(state.seq_no() + 1).unwrap();
+    let block = Block::with_params(0, info, ValueFlow::default(), update, BlockExtra::default()).unwrap();
+    let block_root = block.serialize().unwrap();
+    let block_id = BlockIdExt::with_params(state.shard().clone(), state.seq_no() + 1, block_root.repr_hash(), UInt256::default());
+
+    let params = [34, 20, 21, 32, 1000];
+    let (block_proof, state_proof) = create_state_config_params_proof(&block_root, &state_root, &params).unwrap();
+    let values = check_state_config_params_proof(&block_proof, &state_proof, &block_id, &params).unwrap();
+    assert_eq!(values.len(), params.len());
+    for (index, value) in params.iter().zip(values) {
+        assert_eq!(value, config.config(*index).unwrap());
+    }
+
+    // state proof for another state
+    let other_state_root = ShardStateUnsplit::default().serialize().unwrap();
+    let (_, other_state_proof) = create_state_config_params_proof(&block_root, &other_state_root, &[]).unwrap();
+    check_state_config_params_proof(&block_proof, &other_state_proof, &block_id, &[])
+        .expect_err("state hash must be checked");
+}
+
+fn count_cells(cell: &Cell, pruned: &mut usize) -> usize {
+    if cell.is_pruned() {
+        *pruned += 1;
+        return 0
+    }
+    1 + (0..cell.references_count()).map(|i| count_cells(&cell.reference(i).unwrap(), pruned)).sum::<usize>()
+}
+
+fn dict_key(key: u32) -> SliceData {
+    SliceData::load_builder(key.write_to_new_cell().unwrap()).unwrap()
+}
+
+#[test]
+fn test_dict_key_proof() {
+    let mut map = HashmapE::with_bit_len(32);
+    for i in 0..1000u32 {
+        map.set(dict_key(i * 10), &dict_key(i)).unwrap();
+    }
+    let root_hash = map.data().unwrap().repr_hash();
+
+    // presence
+    let proof = create_dict_key_proof(&map, dict_key(5000)).unwrap();
+    let proof = MerkleProof::construct_from_bytes(&proof.write_to_bytes().unwrap()).unwrap();
+    let mut pruned = 0;
+    assert!(count_cells(&proof.proof, &mut pruned) < 20);
+    assert_eq!(check_dict_key_proof(&proof, &root_hash, 32, dict_key(5000)).unwrap(), Some(dict_key(500)));
+    check_dict_key_proof(&proof, &root_hash, 32, dict_key(10)).expect_err("other key is pruned");
+    check_dict_key_proof(&proof, &UInt256::from([1; 32]), 32, dict_key(5000)).expect_err("root hash must be checked");
+
+    // absence: between, before and after all the keys
+    for key in [5001, 0xFFFF_FFFF, 9991, 0x8000_0000] {
+        let proof = create_dict_key_proof(&map, dict_key(key)).unwrap();
+        assert_eq!(check_dict_key_proof(&proof, &root_hash, 32, dict_key(key)).unwrap(), None);
+    }
+    let mut map_from_1 = HashmapE::with_bit_len(32);
+    for i in 1..10u32 {
+        map_from_1.set(dict_key(i), &dict_key(i)).unwrap();
+    }
+    let proof = create_dict_key_proof(&map_from_1, dict_key(0)).unwrap();
+    let root_hash = map_from_1.data().unwrap().repr_hash();
+    assert_eq!(check_dict_key_proof(&proof, &root_hash, 32, dict_key(0)).unwrap(), None);
+
+    create_dict_key_proof(&HashmapE::with_bit_len(32), dict_key(0)).expect_err("empty dictionary has no root");
+}
+
+#[test]
+fn test_dict_range_proof() {
+    let mut map = HashmapE::with_bit_len(32);
+    for i in 0..1000u32 {
+        map.set(dict_key(i * 10), &dict_key(i)).unwrap();
+    }
+    let root_hash = map.data().unwrap().repr_hash();
+    let range = || (Bound::Included(dict_key(2995)), Bound::Excluded(dict_key(3050)));
+
+    let (from, to) = range();
+    let proof = create_dict_range_proof(&map, from, to, false).unwrap();
+    let mut pruned = 0;
+    assert!(count_cells(&proof.proof, &mut pruned) < 50);
+    assert!(pruned > 0);
+
+    let (from, to) = range();
+    let items = check_dict_range_proof(&proof, &root_hash, 32, from, to, false).unwrap();
+    let keys = items.iter().map(|(key, value)| {
+        let key = SliceData::load_builder(key.clone()).unwrap().get_next_u32().unwrap();
+        assert_eq!(value.clone().get_next_u32().unwrap(), key / 10);
+        key
+    }).collect::<Vec<_>>();
+    assert_eq!(keys, vec![3000, 3010, 3020, 3030, 3040]);
+
+    // the proof doesn't cover wider range
+    check_dict_range_proof(&proof, &root_hash, 32, Bound::Included(dict_key(2900)), Bound::Excluded(dict_key(3050)), false)
+        .expect_err("range is not covered by proof");
+    // but covers any key inside
+    assert_eq!(check_dict_key_proof(&proof, &root_hash, 32, dict_key(3020)).unwrap(), Some(dict_key(302)));
+    assert_eq!(check_dict_key_proof(&proof, &root_hash, 32, dict_key(3021)).unwrap(), None);
+
+    // empty range is proven too
+    let empty = || (Bound::Excluded(dict_key(3000)), Bound::Excluded(dict_key(3010)));
+    let (from, to) = empty();
+    let proof = create_dict_range_proof(&map, from, to, false).unwrap();
+    let (from, to) = empty();
+    assert!(check_dict_range_proof(&proof, &root_hash, 32, from, to, false).unwrap().is_empty());
+}
+
+#[test]
+fn test_shard_accounts_proofs() {
+    let (state, _) = get_real_ton_state("src/tests/data/shard_state.boc");
+    let accounts = state.read_accounts().unwrap();
+    let root_hash = accounts.data().unwrap().repr_hash();
+    let mut ids = vec![];
+    accounts.iterate_with_keys(|id: UInt256, _| {
+        ids.push(id);
+        Ok(true)
+    }).unwrap();
+
+    let key = ids[1].write_to_bitstring().unwrap();
+    let proof = create_dict_key_proof(&accounts, key.clone()).unwrap();
+    let mut leaf = check_dict_key_proof(&proof, &root_hash, 256, key).unwrap().unwrap();
+    crate::DepthBalanceInfo::construct_from(&mut leaf).unwrap();
+    let account = ShardAccount::construct_from(&mut leaf).unwrap();
+    assert_eq!(account, accounts.get(&ids[1]).unwrap().unwrap());
+
+    let absent = UInt256::from([0x33; 32]);
+    assert!(accounts.get(&absent).unwrap().is_none());
+    let key = absent.write_to_bitstring().unwrap();
+    let proof = create_dict_key_proof(&accounts, key.clone()).unwrap();
+    assert_eq!(check_dict_key_proof(&proof, &root_hash, 256, key).unwrap(), None);
+
+    let proof = create_dict_range_proof(&accounts, Bound::Unbounded, Bound::Unbounded, false).unwrap();
+    let items = check_dict_range_proof(&proof, &root_hash, 256, Bound::Unbounded, Bound::Unbounded, false).unwrap();
+    assert_eq!(items.len(), ids.len());
+}
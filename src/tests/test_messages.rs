@@ -13,7 +13,7 @@
 
 use crate::{
     ed25519_generate_private_key, read_single_root_boc, write_read_and_assert, Ed25519KeyOption, 
-    SigPubKey, ED25519_SIGNATURE_LENGTH, write_boc,
+    SigPubKey, ED25519_SIGNATURE_LENGTH, write_boc, AccountIdPrefixFull,
 };
 
 use super::*;
@@ -502,4 +502,169 @@ fn test_msg_pack_proof() -> Result<()> {
     proof.check(123, &msg_pack_root.repr_hash(), &validators)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_user_friendly_address() {
+    let raw = "0:83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8";
+    let address = MsgAddressInt::from_str(raw).unwrap();
+    let cases = [
+        (UserFriendlyFlags::with_params(true, false, false), "EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N"),
+        (UserFriendlyFlags::with_params(false, false, false), "UQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqEBI"),
+    ];
+    for (flags, string) in cases {
+        assert_eq!(address.to_user_friendly(flags).unwrap(), string);
+        assert_eq!(MsgAddressInt::from_user_friendly(string).unwrap(), (address.clone(), flags));
+        assert_eq!(MsgAddressInt::from_str(string).unwrap(), address);
+    }
+
+    let elector = MsgAddressInt::standard(-1, [0x33; 32]);
+    let flags = UserFriendlyFlags::with_params(true, false, true);
+    assert_eq!(elector.to_user_friendly(flags).unwrap(), "Ef8zMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzMzM0vF");
+
+    // all the flags and both alphabets
+    for bounceable in [false, true] {
+        for testnet in [false, true] {
+            for url_safe in [false, true] {
+                let address = MsgAddressInt::standard(-1, UInt256::rand());
+                let flags = UserFriendlyFlags::with_params(bounceable, testnet, url_safe);
+                let string = address.to_user_friendly(flags).unwrap();
+                assert_eq!(string.len(), USER_FRIENDLY_ADDRESS_LEN);
+                let (decoded, decoded_flags) = MsgAddressInt::from_user_friendly(&string).unwrap();
+                assert_eq!(decoded, address);
+                assert_eq!(decoded_flags.bounceable, bounceable);
+                assert_eq!(decoded_flags.testnet, testnet);
+                // alphabet is detected only if there are alphabet specific characters
+                if decoded_flags.url_safe != url_safe {
+                    assert!(!string.contains(['-', '_', '+', '/']));
+                }
+            }
+        }
+    }
+    let address = MsgAddressInt::from_str("0:fbfffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffb").unwrap();
+    let standard = address.to_user_friendly(UserFriendlyFlags::with_params(true, false, false)).unwrap();
+    let url_safe = address.to_user_friendly(UserFriendlyFlags::with_params(true, false, true)).unwrap();
+    assert!(standard.contains('/') && url_safe.contains('_'));
+    assert_eq!(MsgAddressInt::from_user_friendly(&standard).unwrap().0, address);
+    assert_eq!(MsgAddressInt::from_user_friendly(&url_safe).unwrap().0, address);
+    let mixed = format!("{}/", &url_safe[..USER_FRIENDLY_ADDRESS_LEN - 1]);
+    MsgAddressInt::from_user_friendly(&mixed).expect_err("alphabets are mixed");
+}
+
+#[test]
+fn test_user_friendly_address_errors() {
+    let string = "EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N";
+    // wrong checksum
+    let wrong = string.replace("B2N", "B2M");
+    let err = MsgAddressInt::from_user_friendly(&wrong).unwrap_err();
+    assert!(err.to_string().contains("checksum"), "{}", err);
+    // wrong length
+    let err = MsgAddressInt::from_user_friendly(&string[1..]).unwrap_err();
+    assert!(err.to_string().contains("48 characters"), "{}", err);
+    MsgAddressInt::from_user_friendly(&format!("{}A", string)).expect_err("too long");
+    MsgAddressInt::from_user_friendly("EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xq!2N").expect_err("not base64");
+    // wrong tag
+    let mut data = base64_decode_url_safe(string).unwrap();
+    data[0] = 0x12;
+    let crc = crc16(&data[..34]);
+    data[34..].copy_from_slice(&crc.to_be_bytes());
+    let err = MsgAddressInt::from_user_friendly(&base64_encode_url_safe(&data)).unwrap_err();
+    assert!(err.to_string().contains("tag"), "{}", err);
+
+    let flags = UserFriendlyFlags::default();
+    // unsupported workchain
+    let address = MsgAddressInt::with_variant(None, 1111, AccountId::from([0x55; 32])).unwrap();
+    let err = address.to_user_friendly(flags).unwrap_err();
+    assert!(err.to_string().contains("workchain 1111"), "{}", err);
+    // variable address is encoded if it fits standard one
+    let address = MsgAddressInt::with_variant(None, 1, AccountId::from([0x55; 32])).unwrap();
+    let string = address.to_user_friendly(flags).unwrap();
+    assert_eq!(
+        MsgAddressInt::from_user_friendly(&string).unwrap().0,
+        MsgAddressInt::standard(1, [0x55; 32])
+    );
+    let address = MsgAddressInt::with_variant(None, 1, SliceData::new(vec![0x55])).unwrap();
+    address.to_user_friendly(flags).expect_err("short address");
+    let anycast = AnycastInfo::with_rewrite_pfx(SliceData::new(vec![0x77, 0x80])).unwrap();
+    let address = MsgAddressInt::with_standart(Some(anycast), 0, AccountId::from([0x55; 32])).unwrap();
+    address.to_user_friendly(flags).expect_err("anycast address");
+}
+
+fn shards_of_depth(workchain_id: i32, depth: u8) -> Vec<ShardIdent> {
+    let mut shards = vec![ShardIdent::full(workchain_id)];
+    for _ in 0..depth {
+        shards = shards.iter().flat_map(|shard| {
+            let (left, right) = shard.split().unwrap();
+            [left, right]
+        }).collect();
+    }
+    shards
+}
+
+#[test]
+fn test_anycast_address_in_shards() {
+    let mut state_init = StateInit::default();
+    state_init.set_code(BuilderData::with_bitstring(vec![0x55, 0x80]).unwrap().into_cell().unwrap());
+    state_init.set_split_depth(Number5::new(3).unwrap());
+    let account_id = AccountId::from(state_init.hash().unwrap());
+    let address = MsgAddressInt::with_anycast(0, account_id.clone(), 3).unwrap();
+    assert_eq!(address.rewrite_pfx().unwrap().depth(), 3);
+    assert_eq!(address.rewritten_address().unwrap(), MsgAddressInt::standard(0, account_id.clone()));
+    state_init.check_split_depth(&address).unwrap();
+
+    for depth in 0..6 {
+        let mut containing = 0;
+        for shard in shards_of_depth(0, depth) {
+            let instance = address.anycast_in_shard(&shard).unwrap();
+            let rewritten = instance.rewritten_address().unwrap();
+            assert!(rewritten.rewrite_pfx().is_none());
+            assert_eq!(rewritten.address().get_slice(3, 253).unwrap(), account_id.get_slice(3, 253).unwrap());
+            // routing and shard see the same effective address
+            let prefix = AccountIdPrefixFull::prefix(&instance).unwrap();
+            assert_eq!(prefix, AccountIdPrefixFull::prefix(&rewritten).unwrap());
+            let contains = shard.contains_address(&instance).unwrap();
+            assert_eq!(contains, shard.contains_address(&rewritten).unwrap());
+            assert_eq!(contains, shard.contains_full_prefix(&prefix));
+            if depth <= 3 {
+                assert!(contains, "{} should be in {}", rewritten, shard);
+            }
+            if contains {
+                containing += 1;
+            }
+            // every instance can be deployed with the same state init
+            state_init.check_split_depth(&instance).unwrap();
+            state_init.check_split_depth(&rewritten).expect_err("address is not anycast");
+        }
+        // deeper shards contain instance only if the rest bits of address match
+        assert_eq!(containing, 1 << depth.min(3));
+    }
+
+    address.anycast_in_shard(&ShardIdent::masterchain()).expect_err("wrong workchain");
+    address.rewritten_address().unwrap().anycast_in_shard(&ShardIdent::full(0)).expect_err("not anycast");
+}
+
+#[test]
+fn test_anycast_address_errors() {
+    let account_id = AccountId::from([0x55; 32]);
+    AnycastInfo::with_address(&account_id, 0).expect_err("depth is zero");
+    AnycastInfo::with_address(&account_id, MAX_ANYCAST_DEPTH + 1).expect_err("depth is too big");
+    AnycastInfo::with_address(&SliceData::new(vec![0x55, 0x80]), 10).expect_err("address is too short");
+    let address = MsgAddressInt::with_anycast(1111, account_id.clone(), 5).unwrap();
+    assert!(matches!(address, MsgAddressInt::AddrVar(_)));
+    let shard = ShardIdent::with_tagged_prefix(1111, 0x0C00_0000_0000_0000).unwrap();
+    let instance = address.anycast_in_shard(&shard).unwrap();
+    assert_eq!(instance.rewrite_pfx().unwrap().rewrite_pfx, SliceData::new(vec![0x0D, 0x80]).get_slice(0, 5).unwrap());
+    assert!(shard.contains_address(&instance).unwrap());
+    assert!(!shard.contains_address(&address).unwrap());
+
+    let state_init = StateInit::default();
+    let account_id = AccountId::from(state_init.hash().unwrap());
+    state_init.check_split_depth(&MsgAddressInt::standard(0, account_id.clone())).unwrap();
+    state_init.check_split_depth(&MsgAddressInt::standard(0, [0x55; 32])).expect_err("wrong hash");
+    let address = MsgAddressInt::with_anycast(0, account_id.clone(), 5).unwrap();
+    state_init.check_split_depth(&address).expect_err("state init has no split depth");
+    let mut state_init = state_init;
+    state_init.set_split_depth(Number5::new(4).unwrap());
+    let address = MsgAddressInt::with_anycast(0, AccountId::from(state_init.hash().unwrap()), 5).unwrap();
+    state_init.check_split_depth(&address).expect_err("depth doesn't match");
+}
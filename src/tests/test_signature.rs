@@ -466,3 +466,182 @@ fn test_check_block_signature() {
       assert!(result.is_err());
     }
 }
+
+struct TestValidator {
+    key: Arc<dyn KeyOption>,
+    bls_key: [u8; crate::BLS_SECRET_KEY_LEN],
+}
+
+fn gen_validators(count: usize) -> (Vec<TestValidator>, ValidatorSet) {
+    let mut validators = Vec::new();
+    let mut list = Vec::new();
+    for _ in 0..count {
+        let key = Ed25519KeyOption::generate().unwrap();
+        let (bls_public, bls_key) = crate::gen_bls_key_pair().unwrap();
+        let public = SigPubKey::from_bytes(key.pub_key().unwrap()).unwrap();
+        list.push(ValidatorDescr::with_params(public, 10, None, Some(bls_public)));
+        validators.push(TestValidator { key, bls_key });
+    }
+    let vset = ValidatorSet::new(0, u32::MAX, count as u16, list).unwrap();
+    (validators, vset)
+}
+
+fn make_key_block(
+    base: &Block,
+    seq_no: u32,
+    prev_key_block_seqno: u32,
+    vset: &ValidatorSet
+) -> (BlockIdExt, Cell) {
+    let mut block = base.clone();
+    let mut info = block.read_info().unwrap();
+    info.set_seq_no(seq_no).unwrap();
+    info.set_prev_key_block_seqno(prev_key_block_seqno);
+    info.set_gen_catchain_seqno(seq_no / 10);
+    block.write_info(&info).unwrap();
+    let mut extra = block.read_extra().unwrap();
+    let mut custom = extra.read_custom().unwrap().unwrap();
+    let config = custom.config_mut().as_mut().unwrap();
+    config.set_config(ConfigParamEnum::ConfigParam34(
+        crate::ConfigParam34 { cur_validators: vset.clone() }
+    )).unwrap();
+    extra.write_custom(Some(&custom)).unwrap();
+    block.write_extra(&extra).unwrap();
+    let root = block.serialize().unwrap();
+    let id = BlockIdExt::with_params(
+        info.shard().clone(), seq_no, root.repr_hash(), UInt256::from([seq_no as u8; 32])
+    );
+    (id, root)
+}
+
+fn make_proof_root(root: &Cell) -> Cell {
+    let paths = [
+        crate::BlockProofPath::Info,
+        crate::BlockProofPath::ConfigParam(28),
+        crate::BlockProofPath::ConfigParam(34),
+    ];
+    MerkleProof::create_for_paths(root, &paths).unwrap().serialize().unwrap()
+}
+
+fn sign_proof(
+    id: &BlockIdExt,
+    root: &Cell,
+    signers: &[TestValidator],
+    prev_vset: &ValidatorSet,
+    cc_config: &CatchainConfig
+) -> BlockProof {
+    let cc_seqno = id.seq_no() / 10;
+    let (_, hash_short) = prev_vset.calc_subset(
+        cc_config, SHARD_FULL, MASTERCHAIN_ID, cc_seqno, 0.into()
+    ).unwrap();
+    let data = Block::build_data_for_sign(&id.root_hash, &id.file_hash);
+    let mut pure_signatures = BlockSignaturesPure::default();
+    for signer in signers {
+        let public = SigPubKey::from_bytes(signer.key.pub_key().unwrap()).unwrap();
+        let node_id_short = ValidatorDescr::with_params(public, 0, None, None).compute_node_id_short();
+        let sign = CryptoSignature::from_bytes(&signer.key.sign(&data).unwrap()).unwrap();
+        pure_signatures.add_sigpair(CryptoSignaturePair::with_params(node_id_short, sign));
+    }
+    let signatures = BlockSignatures::with_params(
+        ValidatorBaseInfo::with_params(hash_short, cc_seqno), pure_signatures
+    );
+    BlockProof::with_params(id.clone(), make_proof_root(root), Some(signatures))
+}
+
+fn sign_bls(id: &BlockIdExt, signers: &[TestValidator], vset: &ValidatorSet, cc_config: &CatchainConfig) -> Vec<u8> {
+    let (subset, _) = vset.calc_subset(
+        cc_config, SHARD_FULL, MASTERCHAIN_ID, id.seq_no() / 10, 0.into()
+    ).unwrap();
+    let data = Block::build_data_for_sign(&id.root_hash, &id.file_hash);
+    let signatures = signers.iter().map(|signer| {
+        let index = subset.iter().position(|vd| vd.public_key.as_slice() == signer.key.pub_key().unwrap()).unwrap();
+        crate::sign_and_add_node_info(&signer.bls_key, &data, index as u16, subset.len() as u16).unwrap()
+    }).collect::<Vec<_>>();
+    crate::aggregate_bls_signatures(&signatures.iter().map(|s| s.as_slice()).collect::<Vec<_>>()).unwrap()
+}
+
+#[test]
+fn test_proof_chain_verifier() {
+    let (base, _, _) = read_block("src/tests/data/key_block.boc");
+    let cc_config = base.read_extra().unwrap().read_custom().unwrap().unwrap()
+        .config().unwrap().catchain_config().unwrap();
+
+    let (validators1, vset1) = gen_validators(4);
+    let (validators2, vset2) = gen_validators(4);
+    let (_, vset3) = gen_validators(4);
+
+    let (id0, root0) = make_key_block(&base, 100, 90, &vset1);
+    let (id1, root1) = make_key_block(&base, 110, 100, &vset2);
+    let (id2, root2) = make_key_block(&base, 120, 110, &vset3);
+
+    // trusted key block may be given by its proof
+    let verifier = ProofChainVerifier::with_key_block(id0.clone(), &make_proof_root(&root0)).unwrap();
+    assert_eq!(verifier.block_id(), &id0);
+    assert_eq!(verifier.validator_set(), &vset1);
+
+    let proof1 = sign_proof(&id1, &root1, &validators1[..3], &vset1, &cc_config);
+    let proof2 = sign_proof(&id2, &root2, &validators2[1..], &vset2, &cc_config);
+    let (id, vset) = verifier.clone().check_chain(&[proof1.clone(), proof2.clone()]).unwrap();
+    assert_eq!(id, id2);
+    assert_eq!(vset, vset3);
+
+    // proofs must go one by one
+    verifier.clone().check_chain(std::slice::from_ref(&proof2)).expect_err("skipped key block");
+    verifier.clone().check_chain(&[proof1.clone(), proof1.clone()]).expect_err("repeated key block");
+
+    // 2/3 of weight is not enough
+    let weak_proof = sign_proof(&id1, &root1, &validators1[..2], &vset1, &cc_config);
+    verifier.clone().check_chain(&[weak_proof]).expect_err("too small weight");
+
+    // signatures of other validators are ignored
+    let wrong_proof = sign_proof(&id1, &root1, &validators2[..3], &vset1, &cc_config);
+    verifier.clone().check_chain(&[wrong_proof]).expect_err("signed by wrong validators");
+
+    // proof for another block
+    let mut bad_proof = proof1.clone();
+    bad_proof.proof_for.root_hash = UInt256::from([1; 32]);
+    verifier.clone().check_chain(&[bad_proof]).expect_err("wrong root hash");
+
+    // BLS path
+    let mut verifier = verifier;
+    let no_signatures = BlockProof::with_params(id1.clone(), make_proof_root(&root1), None);
+    verifier.check_proof_bls(&no_signatures, &sign_bls(&id1, &validators1[..2], &vset1, &cc_config))
+        .expect_err("too small weight");
+    verifier.check_proof_bls(&no_signatures, &sign_bls(&id2, &validators1[1..], &vset1, &cc_config))
+        .expect_err("signature of another block");
+    verifier.check_proof_bls(&no_signatures, &sign_bls(&id1, &validators1[1..], &vset1, &cc_config)).unwrap();
+    assert_eq!(verifier.block_id(), &id1);
+    verifier.check_proof(&proof2).unwrap();
+    assert_eq!(verifier.block_id(), &id2);
+    assert_eq!(verifier.validator_set(), &vset3);
+}
+
+#[test]
+fn test_proof_chain_verifier_from_zerostate() {
+    let (base, _, _) = read_block("src/tests/data/key_block.boc");
+    let config = base.read_extra().unwrap().read_custom().unwrap().unwrap().config().unwrap().clone();
+    let (validators, vset) = gen_validators(3);
+
+    let mut state = crate::ShardStateUnsplit::with_ident(ShardIdent::masterchain());
+    let mut custom = crate::McStateExtra { config, ..Default::default() };
+    custom.config.set_config(ConfigParamEnum::ConfigParam34(
+        crate::ConfigParam34 { cur_validators: vset.clone() }
+    )).unwrap();
+    state.write_custom(Some(&custom)).unwrap();
+    let state_root = state.serialize().unwrap();
+    let zerostate_id = BlockIdExt::with_params(
+        ShardIdent::masterchain(), 0, state_root.repr_hash(), UInt256::from([2; 32])
+    );
+
+    let verifier = ProofChainVerifier::with_zerostate(zerostate_id.clone(), &state_root).unwrap();
+    assert_eq!(verifier.validator_set(), &vset);
+    ProofChainVerifier::with_zerostate(zerostate_id.clone(), &base.serialize().unwrap())
+        .expect_err("wrong state");
+
+    let (_, next_vset) = gen_validators(2);
+    let (id1, root1) = make_key_block(&base, 10, 0, &next_vset);
+    let cc_config = custom.config.catchain_config().unwrap();
+    let proof1 = sign_proof(&id1, &root1, &validators, &vset, &cc_config);
+    let (id, last_vset) = verifier.check_chain(&[proof1]).unwrap();
+    assert_eq!(id, id1);
+    assert_eq!(last_vset, next_vset);
+}
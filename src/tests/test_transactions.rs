@@ -791,4 +791,222 @@ pub fn generate_test_shard_account_block(opts: u8) -> ShardAccountBlocks {
         shard_block.insert(&account_block).unwrap();
     }
     shard_block
-}
\ No newline at end of file
+}
+
+fn builder_in_msg(bounce: bool) -> CommonMessage {
+    CommonMessage::Std(Message::with_int_header(
+        crate::InternalMessageHeader::with_addresses_and_bounce(
+            MsgAddressInt::with_standart(None, 0, [0x55; 32].into()).unwrap(),
+            MsgAddressInt::with_standart(None, 0, [0x66; 32].into()).unwrap(),
+            CurrencyCollection::from_grams(5_000_000_000.into()),
+            bounce,
+        )
+    ))
+}
+
+fn builder_compute_phase(success: bool) -> TrComputePhase {
+    TrComputePhase::Vm(TrComputePhaseVm {
+        success,
+        gas_fees: 300.into(),
+        exit_code: if success { 0 } else { 100 },
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_transaction_builder_ordinary() {
+    let account_id = AccountId::from([0x66; 32]);
+    let address = MsgAddressInt::with_standart(None, 0, account_id.clone()).unwrap();
+    let old_account = Account::with_address_and_ballance(&address, &CurrencyCollection::with_grams(100));
+    let new_account = Account::with_address_and_ballance(&address, &CurrencyCollection::with_grams(200));
+    let (old_cell, new_cell) = (old_account.serialize().unwrap(), new_account.serialize().unwrap());
+    let mut action = TrActionPhase {
+        success: true,
+        valid: true,
+        msgs_created: 2,
+        tot_actions: 2,
+        ..Default::default()
+    };
+    action.add_action_fees(40.into());
+    let out_msg = builder_in_msg(false);
+    let tr = TransactionBuilder::new(account_id.clone())
+        .lt(10)
+        .prev_trans(UInt256::from([1; 32]), 5)
+        .now(1234)
+        .in_msg(builder_in_msg(true))
+        .out_msg(out_msg.clone())
+        .out_msg(out_msg.clone())
+        .accounts(old_cell.clone(), new_cell.clone())
+        .storage_phase(TrStoragePhase::with_params(10.into(), None, AccStatusChange::Unchanged))
+        .credit_phase(TrCreditPhase::with_params(Some(2.into()), CurrencyCollection::with_grams(100)))
+        .compute_phase(builder_compute_phase(true))
+        .action_phase(action.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(tr.account_id(), &account_id);
+    assert_eq!(tr.logical_time(), 10);
+    assert_eq!(tr.prev_trans_lt(), 5);
+    assert_eq!(tr.now(), 1234);
+    assert_eq!(tr.msg_count(), 2);
+    assert_eq!(tr.get_out_msg(1).unwrap(), Some(out_msg));
+    assert_eq!(tr.total_fees(), &CurrencyCollection::with_grams(10 + 2 + 300 + 40));
+    assert_eq!(tr.orig_status, old_account.status());
+    assert_eq!(tr.end_status, new_account.status());
+    assert_eq!(tr.read_state_update().unwrap(), HashUpdate::with_hashes(old_cell.repr_hash(), new_cell.repr_hash()));
+    match tr.read_description().unwrap() {
+        TransactionDescr::Ordinary(descr) => {
+            // message is bounceable so compute phase is first
+            assert!(!descr.credit_first);
+            assert_eq!(descr.action, Some(action));
+            assert!(descr.bounce.is_none());
+        }
+        descr => panic!("wrong description {:?}", descr)
+    }
+    write_read_and_assert(tr);
+
+    // failed compute phase with bounce
+    let tr = TransactionBuilder::with_serde_opts(account_id.clone(), SERDE_OPTS_COMMON_MESSAGE)
+        .in_msg(builder_in_msg(true))
+        .out_msg(builder_in_msg(false))
+        .state_update(HashUpdate::with_hashes(old_cell.repr_hash(), old_cell.repr_hash()))
+        .status(AccountStatus::AccStateActive, AccountStatus::AccStateActive)
+        .credit_phase(TrCreditPhase::default())
+        .compute_phase(builder_compute_phase(false))
+        .aborted(true)
+        .bounce_phase(TrBouncePhase::ok(StorageUsedShort::default(), 7.into(), 3.into()))
+        .total_fees(CurrencyCollection::with_grams(1000))
+        .build()
+        .unwrap();
+    assert_eq!(tr.total_fees(), &CurrencyCollection::with_grams(1000));
+    assert!(tr.read_description().unwrap().is_aborted());
+    write_read_and_assert_with_opts(tr, SERDE_OPTS_COMMON_MESSAGE).unwrap();
+
+    // external message without credit phase is credit first and pays import fee
+    let mut header = crate::ExternalInboundMessageHeader::new(MsgAddressExt::default(), address);
+    header.import_fee = 5.into();
+    let tr = TransactionBuilder::new(account_id)
+        .in_msg(CommonMessage::Std(Message::with_ext_in_header(header)))
+        .accounts(old_cell.clone(), Account::default().serialize().unwrap())
+        .compute_phase(builder_compute_phase(true))
+        .destroyed(true)
+        .build()
+        .unwrap();
+    assert_eq!(tr.total_fees(), &CurrencyCollection::with_grams(305));
+    assert_eq!(tr.end_status, AccountStatus::AccStateNonexist);
+    assert_eq!(tr.read_description().unwrap().is_credit_first(), Some(true));
+}
+
+#[test]
+fn test_transaction_builder_checks_phases() {
+    let builder = TransactionBuilder::new(AccountId::from([0x66; 32]))
+        .state_update(HashUpdate::default())
+        .status(AccountStatus::AccStateActive, AccountStatus::AccStateActive);
+    let bounce = TrBouncePhase::no_funds(StorageUsedShort::default(), 10.into());
+    let check = |builder: TransactionBuilder, message: &str| {
+        let err = builder.build().expect_err(message);
+        assert!(err.to_string().contains(message), "{} should contain {}", err, message);
+    };
+    let ordinary = builder.clone()
+        .in_msg(builder_in_msg(false))
+        .credit_phase(TrCreditPhase::default())
+        .compute_phase(builder_compute_phase(false))
+        .aborted(true);
+    ordinary.clone().build().unwrap();
+    check(ordinary.clone().bounce_phase(bounce.clone()), "bounce flag");
+    check(builder.clone().in_msg(builder_in_msg(true)).compute_phase(builder_compute_phase(true)), "credit phase");
+    check(ordinary.clone().action_phase(TrActionPhase::default()), "successful compute phase");
+    check(ordinary.clone().out_msg(builder_in_msg(false)), "output messages");
+    check(ordinary.clone().destroyed(true), "end status");
+    check(builder.clone().compute_phase(builder_compute_phase(true)), "inbound message");
+    check(TransactionBuilder::new(AccountId::from([0x66; 32])), "state update");
+    check(builder.clone().in_msg(builder_in_msg(true)).credit_phase(TrCreditPhase::default()), "compute phase");
+
+    let bounced = builder.clone()
+        .in_msg(builder_in_msg(true))
+        .credit_phase(TrCreditPhase::default())
+        .compute_phase(builder_compute_phase(false))
+        .bounce_phase(bounce.clone());
+    check(bounced.clone(), "aborted");
+    bounced.aborted(true).build().unwrap();
+
+    // tick-tock
+    let tick_tock = builder.clone()
+        .tick_tock(TransactionTickTock::Tock)
+        .storage_phase(TrStoragePhase::default())
+        .compute_phase(builder_compute_phase(true))
+        .action_phase(TrActionPhase::default());
+    let tr = tick_tock.clone().build().unwrap();
+    match tr.read_description().unwrap() {
+        TransactionDescr::TickTock(descr) => assert!(descr.tt.is_tock()),
+        descr => panic!("wrong description {:?}", descr)
+    }
+    check(tick_tock.clone().in_msg(builder_in_msg(false)), "tick-tock transaction can't have inbound message");
+    check(tick_tock.clone().credit_phase(TrCreditPhase::default()), "tick-tock transaction can't have credit phase");
+    check(tick_tock.clone().bounce_phase(bounce), "tick-tock transaction can't have bounce phase");
+    check(builder.clone().tick_tock(TransactionTickTock::Tick).compute_phase(builder_compute_phase(true)), "storage phase");
+
+    // storage
+    let tr = builder.clone().storage().storage_phase(TrStoragePhase::default()).build().unwrap();
+    assert_eq!(tr.read_description().unwrap(), TransactionDescr::Storage(TrStoragePhase::default()));
+    check(builder.clone().storage().storage_phase(TrStoragePhase::default()).compute_phase(builder_compute_phase(true)), "compute phase");
+    check(builder.clone().storage().storage_phase(TrStoragePhase::default()).aborted(true), "aborted flag");
+}
+
+#[test]
+fn test_transaction_builder_split_merge() {
+    let builder = TransactionBuilder::new(AccountId::from([0x66; 32]))
+        .state_update(HashUpdate::default())
+        .status(AccountStatus::AccStateActive, AccountStatus::AccStateActive);
+    let split_info = SplitMergeInfo {
+        cur_shard_pfx_len: 3,
+        acc_split_depth: 5,
+        this_addr: UInt256::from([1; 32]),
+        sibling_addr: UInt256::from([2; 32]),
+    };
+
+    let prepare = builder.clone()
+        .lt(1)
+        .split_prepare(split_info.clone())
+        .compute_phase(builder_compute_phase(true))
+        .build()
+        .unwrap();
+    assert!(prepare.read_description().unwrap().is_split());
+    builder.clone().split_prepare(split_info.clone()).storage_phase(TrStoragePhase::default()).build()
+        .expect_err("split prepare has no storage phase");
+    let install = builder.clone()
+        .lt(2)
+        .split_install(split_info.clone(), prepare.clone(), true)
+        .build()
+        .unwrap();
+    match install.read_description().unwrap() {
+        TransactionDescr::SplitInstall(descr) => {
+            assert!(descr.installed);
+            assert_eq!(descr.prepare_transaction.as_ref(), &prepare);
+        }
+        descr => panic!("wrong description {:?}", descr)
+    }
+    builder.clone().split_install(split_info.clone(), prepare.clone(), true).compute_phase(builder_compute_phase(true)).build()
+        .expect_err("split install has no compute phase");
+    write_read_and_assert(install);
+
+    let prepare = builder.clone()
+        .merge_prepare(split_info.clone())
+        .storage_phase(TrStoragePhase::with_params(10.into(), None, AccStatusChange::Unchanged))
+        .aborted(true)
+        .build()
+        .unwrap();
+    assert!(prepare.read_description().unwrap().is_merge());
+    assert_eq!(prepare.total_fees(), &CurrencyCollection::with_grams(10));
+    builder.clone().merge_prepare(split_info.clone()).build().expect_err("merge prepare has storage phase");
+    let install = builder.clone()
+        .merge_install(split_info.clone(), prepare.clone())
+        .credit_phase(TrCreditPhase::default())
+        .compute_phase(builder_compute_phase(true))
+        .action_phase(TrActionPhase::default())
+        .build()
+        .unwrap();
+    assert_eq!(install.read_description().unwrap().action_phase_ref(), Some(&TrActionPhase::default()));
+    builder.merge_install(split_info, prepare).build().expect_err("merge install has compute phase");
+    write_read_and_assert(install);
+}
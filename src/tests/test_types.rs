@@ -134,16 +134,16 @@ fn test_get_bytestring() {
     assert_eq!(slice.get_bytestring(25), vec![0b01111110]);
     assert_eq!(slice.get_bytestring(26), vec![0b11111100]);
     assert_eq!(slice.get_bytestring(31), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(32), vec![]);
+    assert_eq!(slice.get_bytestring(32), Vec::<u8>::new());
 
-    assert_eq!(slice.get_bytestring(33), vec![]);
+    assert_eq!(slice.get_bytestring(33), Vec::<u8>::new());
 
     slice.move_by(1).unwrap();
     assert_eq!(slice.get_bytestring(0), vec![0b01101110, 0b11110111, 0b11011111, 0b01111110]);
     assert_eq!(slice.get_bytestring(1), vec![0b11011101, 0b11101111, 0b10111110, 0b11111100]);
     assert_eq!(slice.get_bytestring(25), vec![0b11111100]);
     assert_eq!(slice.get_bytestring(30), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(31), vec![]);
+    assert_eq!(slice.get_bytestring(31), Vec::<u8>::new());
 
     let mut slice = SliceData::from_raw(vec![0b10110111, 0b01111011, 0b11101111, 0b10111111], 32);
     slice.shrink_data(0..=30);
@@ -151,7 +151,7 @@ fn test_get_bytestring() {
     assert_eq!(slice.get_bytestring(1), vec![0b01101110, 0b11110111, 0b11011111, 0b01111100]);
     assert_eq!(slice.get_bytestring(25), vec![0b01111100]);
     assert_eq!(slice.get_bytestring(30), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(31), vec![]);
+    assert_eq!(slice.get_bytestring(31), Vec::<u8>::new());
 
     let mut slice = SliceData::from_raw(vec![0b10110111, 0b01111011, 0b11101111, 0b10111111], 32);
     slice.shrink_data(0..=29);
@@ -159,21 +159,21 @@ fn test_get_bytestring() {
     assert_eq!(slice.get_bytestring(1), vec![0b01101110, 0b11110111, 0b11011111, 0b01111000]);
     assert_eq!(slice.get_bytestring(25), vec![0b01111000]);
     assert_eq!(slice.get_bytestring(29), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(30), vec![]);
+    assert_eq!(slice.get_bytestring(30), Vec::<u8>::new());
 
     let mut slice = SliceData::from_raw(vec![0b10110111, 0b01111011, 0b11101111, 0b10111111], 32);
     slice.shrink_data(0..=23);
     assert_eq!(slice.get_bytestring(0), vec![0b10110111, 0b01111011, 0b11101111]);
     assert_eq!(slice.get_bytestring(1), vec![0b01101110, 0b11110111, 0b11011110]);
     assert_eq!(slice.get_bytestring(23), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(24), vec![]);
+    assert_eq!(slice.get_bytestring(24), Vec::<u8>::new());
 
     let mut slice = SliceData::from_raw(vec![0b10110111, 0b01111011, 0b11101111, 0b10111111], 32);
     slice.shrink_data(0..=21);
     assert_eq!(slice.get_bytestring(0), vec![0b10110111, 0b01111011, 0b11101100]);
     assert_eq!(slice.get_bytestring(1), vec![0b01101110, 0b11110111, 0b11011000]);
     assert_eq!(slice.get_bytestring(21), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(22), vec![]);
+    assert_eq!(slice.get_bytestring(22), Vec::<u8>::new());
 
     slice.move_by(6).unwrap();
     assert_eq!(slice.get_bytestring(0), vec![0b11011110, 0b11111011]);
@@ -185,7 +185,7 @@ fn test_get_bytestring() {
     assert_eq!(slice.get_bytestring(0), vec![0b10111101, 0b11110110]);
     assert_eq!(slice.get_bytestring(1), vec![0b01111011, 0b11101100]);
     assert_eq!(slice.get_bytestring(14), vec![0b10000000]);
-    assert_eq!(slice.get_bytestring(15), vec![]);
+    assert_eq!(slice.get_bytestring(15), Vec::<u8>::new());
 }
 
 #[test]
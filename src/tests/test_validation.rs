@@ -0,0 +1,357 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use super::*;
+use crate::{
+    read_single_root_boc, read_test_config, AccountId, Block, CommonMessage, Deserializable,
+    ExtOutMessageHeader, HashUpdate, HashmapAugType, InternalMessageHeader, Message,
+    MsgAddressExt, MsgAddressInt, ShardStateUnsplit, StateInit, TickTock, TrActionPhase,
+    TrComputePhaseVm, TrCreditPhase, TrStoragePhase, TransactionBuilder, TransactionTickTock,
+    UInt256, ValueFlow,
+};
+
+const LT: u64 = 10;
+const GAS_USED: u64 = 5000;
+
+fn address() -> MsgAddressInt {
+    MsgAddressInt::with_standart(None, 0, AccountId::from([0x66; 32])).unwrap()
+}
+
+fn account(balance: u64, last_lt: u64) -> Account {
+    let mut account = Account::with_address_and_ballance(&address(), &CurrencyCollection::with_grams(balance));
+    account.set_last_tr_time(last_lt);
+    account
+}
+
+fn int_message(src: MsgAddressInt, dst: MsgAddressInt, value: u64, lt: u64) -> CommonMessage {
+    let mut header = InternalMessageHeader::with_addresses(src, dst, CurrencyCollection::with_grams(value));
+    header.fwd_fee = 1000.into();
+    header.created_lt = lt;
+    CommonMessage::Std(Message::with_int_header(header))
+}
+
+// builder of ordinary transaction receiving 500 and sending 100 with all fees consistent
+fn ordinary(config: &ConfigParams) -> (TransactionBuilder, Account, Account) {
+    let gas_fees = FeesCalculator::with_config(config).unwrap().calc_gas_fee(GAS_USED, false);
+    let (storage_fee, action_fee) = (10, 500);
+    let old_account = account(1_000_000_000, 5);
+    let new_balance = 1_000_000_000 + 500_000_000 - storage_fee - gas_fees as u64 - action_fee - 100_000_000 - 1000;
+    let new_account = account(new_balance, LT + 2);
+    let mut action = TrActionPhase {
+        success: true,
+        valid: true,
+        msgs_created: 1,
+        tot_actions: 1,
+        ..Default::default()
+    };
+    action.add_action_fees(action_fee.into());
+    let other = MsgAddressInt::with_standart(None, 0, AccountId::from([0x55; 32])).unwrap();
+    let builder = TransactionBuilder::new(AccountId::from([0x66; 32]))
+        .lt(LT)
+        .in_msg(int_message(other.clone(), address(), 500_000_000, LT - 1))
+        .out_msg(int_message(address(), other, 100_000_000, LT + 1))
+        .accounts(old_account.serialize().unwrap(), new_account.serialize().unwrap())
+        .storage_phase(TrStoragePhase::with_params(storage_fee.into(), None, AccStatusChange::Unchanged))
+        .credit_phase(TrCreditPhase::with_params(None, CurrencyCollection::with_grams(500_000_000)))
+        .compute_phase(TrComputePhase::Vm(TrComputePhaseVm {
+            success: true,
+            gas_used: crate::VarUInteger7::new(GAS_USED).unwrap(),
+            gas_fees: crate::Grams::new(gas_fees).unwrap(),
+            ..Default::default()
+        }))
+        .action_phase(action);
+    (builder, old_account, new_account)
+}
+
+fn check_error(result: Result<()>, message: &str) {
+    let err = result.expect_err(message);
+    assert!(err.to_string().contains(message), "{} should contain {}", err, message);
+}
+
+#[test]
+fn test_validate_transaction() {
+    let config = read_test_config();
+    let (builder, old_account, new_account) = ordinary(&config);
+    let tr = builder.clone().build().unwrap();
+    validate_transaction(&tr, &old_account, &new_account, &config).unwrap();
+
+    // accounts
+    check_error(validate_transaction(&tr, &new_account, &new_account, &config), "old hash");
+    check_error(validate_transaction(&tr, &old_account, &old_account, &config), "new hash");
+    let mut stranger = old_account.clone();
+    stranger.set_addr(MsgAddressInt::with_standart(None, 0, AccountId::from([0x77; 32])).unwrap());
+    check_error(validate_transaction(&tr, &stranger, &new_account, &config), "is not account");
+
+    // fees and balance
+    let tr = builder.clone().total_fees(CurrencyCollection::with_grams(1)).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "total fees");
+    let richer = account(new_account.balance().unwrap().grams.as_u64().unwrap() + 1, LT + 2);
+    let tr = builder.clone()
+        .accounts(old_account.serialize().unwrap(), richer.serialize().unwrap())
+        .build()
+        .unwrap();
+    check_error(validate_transaction(&tr, &old_account, &richer, &config), "balance is not conserved");
+
+    // logical times
+    let tr = builder.clone().lt(4).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "of old account");
+    let tr = builder.clone().lt(LT + 1).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "of new account");
+    let tr = builder.clone().prev_trans(UInt256::default(), LT).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "previous transaction lt");
+    let other = MsgAddressInt::with_standart(None, 0, AccountId::from([0x55; 32])).unwrap();
+    let tr = builder.clone().in_msg(int_message(other, address(), 500_000_000, LT)).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "inbound message created lt");
+}
+
+#[test]
+fn test_validate_transaction_out_msgs() {
+    let config = read_test_config();
+    let (builder, old_account, _) = ordinary(&config);
+    let other = MsgAddressInt::with_standart(None, 0, AccountId::from([0x55; 32])).unwrap();
+    let base_balance = ordinary(&config).2.balance().unwrap().grams.as_u64().unwrap();
+    // two more messages with forward fees only
+    let new_account = account(base_balance - 2000, LT + 4);
+    let builder = builder.accounts(old_account.serialize().unwrap(), new_account.serialize().unwrap());
+    let build_all = |lts: [u64; 2]| {
+        let mut builder = builder.clone();
+        for lt in lts {
+            builder = builder.out_msg(int_message(address(), other.clone(), 0, lt));
+        }
+        builder.build().unwrap()
+    };
+    let tr = build_all([LT + 2, LT + 3]);
+    validate_transaction(&tr, &old_account, &new_account, &config).unwrap();
+    let tr = build_all([LT + 3, LT + 2]);
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "is not greater than previous lt");
+    let tr = build_all([LT + 2, LT + 4]);
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "is out of range");
+
+    // external outbound message has created lt too
+    let mut header = ExtOutMessageHeader::with_addresses(address(), MsgAddressExt::default());
+    header.created_lt = LT + 1;
+    let new_account = account(base_balance, LT + 3);
+    let tr = ordinary(&config).0
+        .accounts(old_account.serialize().unwrap(), new_account.serialize().unwrap())
+        .out_msg(CommonMessage::Std(Message::with_ext_out_header(header)))
+        .build()
+        .unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "is not greater than previous lt");
+}
+
+#[test]
+fn test_validate_transaction_statuses_and_config() {
+    let config = read_test_config();
+    let (builder, old_account, new_account) = ordinary(&config);
+
+    // statuses
+    let tr = builder.clone()
+        .status(AccountStatus::AccStateActive, AccountStatus::AccStateUninit)
+        .build()
+        .unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "orig status");
+    let tr = builder.clone()
+        .status(AccountStatus::AccStateUninit, AccountStatus::AccStateFrozen)
+        .build()
+        .unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "end status");
+    let tr = builder.clone()
+        .storage_phase(TrStoragePhase::with_params(10.into(), None, AccStatusChange::Deleted))
+        .build()
+        .unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "deleted account");
+
+    let active_with = |special: Option<TickTock>| {
+        let mut state_init = StateInit::default();
+        state_init.set_code(crate::SliceData::new(vec![0x80]).into_cell());
+        if let Some(special) = special {
+            state_init.set_special(special);
+        }
+        Account::active_by_init_code_hash(
+            address(), CurrencyCollection::with_grams(1_000_000_000), 0, state_init, false
+        ).unwrap()
+    };
+    let active = active_with(None);
+    let mut frozen = active.clone();
+    frozen.try_freeze().unwrap();
+    frozen.set_last_tr_time(LT + 1);
+    let storage = |old: &Account, new: &Account, change: AccStatusChange| {
+        TransactionBuilder::new(AccountId::from([0x66; 32]))
+            .lt(LT)
+            .storage()
+            .accounts(old.serialize().unwrap(), new.serialize().unwrap())
+            .storage_phase(TrStoragePhase::with_params(0.into(), None, change))
+            .build()
+            .unwrap()
+    };
+    let tr = storage(&active, &frozen, AccStatusChange::Frozen);
+    validate_transaction(&tr, &active, &frozen, &config).unwrap();
+    let tr = storage(&active, &frozen, AccStatusChange::Unchanged);
+    check_error(validate_transaction(&tr, &active, &frozen, &config), "without status change");
+    let tr = storage(&frozen, &frozen, AccStatusChange::Frozen);
+    check_error(validate_transaction(&tr, &frozen, &frozen, &config), "can't be frozen");
+    let uninit = Account::with_address_and_ballance(&address(), &CurrencyCollection::with_grams(1_000_000_000));
+    let tr = storage(&uninit, &active, AccStatusChange::Unchanged);
+    check_error(validate_transaction(&tr, &uninit, &active, &config), "without compute phase");
+
+    // tick-tock
+    let special = active_with(Some(TickTock::with_values(true, false)));
+    let mut new_special = special.clone();
+    new_special.set_last_tr_time(LT + 1);
+    let tick_tock = |tt: TransactionTickTock| TransactionBuilder::new(AccountId::from([0x66; 32]))
+        .lt(LT)
+        .tick_tock(tt)
+        .accounts(special.serialize().unwrap(), new_special.serialize().unwrap())
+        .storage_phase(TrStoragePhase::default())
+        .compute_phase(TrComputePhase::default())
+        .build()
+        .unwrap();
+    validate_transaction(&tick_tock(TransactionTickTock::Tick), &special, &new_special, &config).unwrap();
+    check_error(
+        validate_transaction(&tick_tock(TransactionTickTock::Tock), &special, &new_special, &config),
+        "no Tock flag"
+    );
+
+    // gas
+    let compute = |gas_used: u64, gas_fees: u128| TrComputePhase::Vm(TrComputePhaseVm {
+        success: true,
+        gas_used: crate::VarUInteger7::new(gas_used).unwrap(),
+        gas_fees: crate::Grams::new(gas_fees).unwrap(),
+        ..Default::default()
+    });
+    // fees and balance are kept consistent with gas fees of base transaction
+    let gas_fees = FeesCalculator::with_config(&config).unwrap().calc_gas_fee(GAS_USED, false);
+    let gas_limit = config.gas_prices(false).unwrap().gas_limit;
+    let tr = builder.clone().compute_phase(compute(gas_limit + 1, gas_fees)).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "gas limit");
+    let tr = builder.clone().compute_phase(compute(GAS_USED + 1000, gas_fees)).build().unwrap();
+    check_error(validate_transaction(&tr, &old_account, &new_account, &config), "gas fees");
+}
+
+fn read_block_with_states(index: u32) -> (Block, ShardStateUnsplit, ShardStateUnsplit) {
+    let read_state = |index: u32| {
+        let bytes = std::fs::read(format!("src/tests/data/block_with_ss/shard-states/{}", index)).unwrap();
+        ShardStateUnsplit::construct_from_cell(read_single_root_boc(bytes).unwrap()).unwrap()
+    };
+    let bytes = std::fs::read(format!("src/tests/data/block_with_ss/blocks/{}", index)).unwrap();
+    (Block::construct_from_bytes(&bytes).unwrap(), read_state(index - 1), read_state(index))
+}
+
+// all transactions of accounts with one transaction in the block are checked against real states
+#[test]
+fn test_validate_real_transactions() {
+    let mut count = 0;
+    for index in 2660..=2670 {
+        let (block, prev_state, new_state) = read_block_with_states(index);
+        let config = prev_state.read_custom().unwrap().unwrap().config().clone();
+        let (prev_accounts, new_accounts) = (prev_state.read_accounts().unwrap(), new_state.read_accounts().unwrap());
+        block.read_extra().unwrap().read_account_blocks().unwrap().iterate_objects(|account_block| {
+            if account_block.transaction_count()? != 1 {
+                return Ok(true)
+            }
+            let read_account = |shard_account: Option<crate::ShardAccount>| match shard_account {
+                Some(shard_account) => shard_account.read_account(),
+                None => Ok(Account::default())
+            };
+            let old_account = read_account(prev_accounts.account(account_block.account_id())?)?;
+            let new_account = read_account(new_accounts.account(account_block.account_id())?)?;
+            account_block.transaction_iterate(|tr| {
+                validate_transaction(&tr, &old_account, &new_account, &config)?;
+                let wrong = HashUpdate::with_hashes(UInt256::default(), tr.read_state_update()?.new_hash);
+                let mut tr = tr;
+                tr.write_state_update(&wrong)?;
+                assert!(validate_transaction(&tr, &old_account, &new_account, &config).is_err());
+                count += 1;
+                Ok(true)
+            })
+        }).unwrap();
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn test_check_value_flow_real_blocks() {
+    // masterchain and shardchain blocks
+    for index in (2660..=2665).chain(571525..=571530) {
+        let (block, prev_state, new_state) = read_block_with_states(index);
+        assert_eq!(check_value_flow(&block, &prev_state, &new_state).unwrap(), vec![], "block {}", index);
+    }
+}
+
+#[test]
+fn test_check_value_flow_mismatches() {
+    let with_value_flow = |block: &Block, change: &dyn Fn(&mut ValueFlow)| {
+        let mut block = block.clone();
+        let mut value_flow = block.read_value_flow().unwrap();
+        change(&mut value_flow);
+        block.write_value_flow(&value_flow).unwrap();
+        block
+    };
+    let one = CurrencyCollection::with_grams(1);
+    let add_one = |value: &mut CurrencyCollection| {
+        value.add(&CurrencyCollection::with_grams(1)).unwrap();
+    };
+
+    let (block, prev_state, new_state) = read_block_with_states(571525);
+    let value_flow = block.read_value_flow().unwrap();
+    let block = with_value_flow(&block, &|value_flow| value_flow.minted = CurrencyCollection::with_grams(1));
+    let errors = check_value_flow(&block, &prev_state, &new_state).unwrap();
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert_eq!(errors[0], ValueFlowError::NonZeroInShardchain { field: "minted", found: one.clone() });
+    assert!(matches!(errors[1], ValueFlowError::Unbalanced { .. }));
+
+    // every mismatch is reported
+    let block = with_value_flow(&block, &|value_flow| {
+        value_flow.minted = CurrencyCollection::default();
+        add_one(&mut value_flow.exported);
+        add_one(&mut value_flow.fees_collected);
+    });
+    let errors = check_value_flow(&block, &prev_state, &new_state).unwrap();
+    let mut exported = value_flow.exported.clone();
+    add_one(&mut exported);
+    assert_eq!(errors[0], ValueFlowError::Exported { expected: value_flow.exported.clone(), found: exported });
+    assert!(matches!(errors[1], ValueFlowError::FeesCollected { .. }));
+    assert!(matches!(errors[2], ValueFlowError::ValidatorFees { .. }));
+    assert!(matches!(errors[3], ValueFlowError::Unbalanced { .. }));
+    assert_eq!(errors.len(), 4);
+
+    // states
+    let (block, prev_state, new_state) = read_block_with_states(2661);
+    let mut richer_state = new_state.clone();
+    add_one(richer_state.total_balance_mut());
+    let errors = check_value_flow(&block, &prev_state, &richer_state).unwrap();
+    assert!(matches!(errors[0], ValueFlowError::ToNextBlk { .. }));
+    assert!(matches!(errors[1], ValueFlowError::NewStateBalance { .. }));
+    assert_eq!(errors.len(), 2);
+    assert!(errors[1].to_string().starts_with("total balance of new state"));
+
+    // masterchain fees of shards
+    let tampered = with_value_flow(&block, &|value_flow| add_one(&mut value_flow.fees_imported));
+    let errors = check_value_flow(&tampered, &prev_state, &new_state).unwrap();
+    assert!(matches!(errors[0], ValueFlowError::FeesImported { .. }));
+    assert!(matches!(errors[1], ValueFlowError::FeesCollected { .. }));
+    assert!(matches!(errors[2], ValueFlowError::Unbalanced { .. }));
+    assert_eq!(errors.len(), 3);
+
+    // recovered more than validator fees
+    let value_flow = block.read_value_flow().unwrap();
+    let mut validator_fees = prev_state.total_validator_fees().clone();
+    validator_fees.add(&value_flow.fees_collected).unwrap();
+    let mut recovered = validator_fees.clone();
+    add_one(&mut recovered);
+    let tampered = with_value_flow(&block, &|value_flow| value_flow.recovered = recovered.clone());
+    let errors = check_value_flow(&tampered, &prev_state, &new_state).unwrap();
+    assert_eq!(errors[0], ValueFlowError::Recovered { expected: validator_fees, found: recovered.clone() });
+    assert!(matches!(errors[1], ValueFlowError::Unbalanced { .. }));
+    assert_eq!(errors.len(), 2);
+}
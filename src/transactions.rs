@@ -21,7 +21,7 @@ use crate::{
     messages::Message,
     common_message::CommonMessage,
     shard::ShardStateUnsplit,
-    types::{ChildCell, CurrencyCollection, Grams, InRefValue, VarUInteger3, VarUInteger7},
+    types::{AddSub, ChildCell, CurrencyCollection, Grams, InRefValue, VarUInteger3, VarUInteger7},
     Serializable, Deserializable,
     error, fail, hm_label, AccountId, BuilderData, Cell, HashmapType, IBitstring, Result,
     SliceData, UInt256, UsageTree, SERDE_OPTS_EMPTY, SERDE_OPTS_COMMON_MESSAGE,
@@ -38,6 +38,7 @@ acst_frozen$10 = AccStatusChange;    // init -> frozen
 acst_deleted$11 = AccStatusChange;   // frozen -> deleted
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccStatusChange {
     #[default]
     Unchanged,
@@ -76,6 +77,7 @@ cskip_bad_state$01 = ComputeSkipReason;
 cskip_no_gas$10 = ComputeSkipReason;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum ComputeSkipReason {
     #[default]
     NoState,
@@ -143,11 +145,13 @@ tr_phase_compute_vm$1
   = TrComputePhase;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrComputePhaseSkipped {
     pub reason: ComputeSkipReason
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrComputePhaseVm {
     pub success: bool,
     pub msg_state_used: bool,
@@ -174,6 +178,7 @@ impl TrComputePhaseVm {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum TrComputePhase {
     Skipped(TrComputePhaseSkipped),
     Vm(TrComputePhaseVm)
@@ -302,6 +307,7 @@ tr_phase_storage$_
 = TrStoragePhase;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrStoragePhase {
     pub storage_fees_collected: Grams,
     pub storage_fees_due: Option<Grams>,
@@ -353,12 +359,14 @@ tr_phase_bounce_ok$1
 = TrBouncePhase;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrBouncePhaseNofunds {
     pub msg_size: StorageUsedShort,
     pub req_fwd_fees: Grams,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrBouncePhaseOk {
     pub msg_size: StorageUsedShort,
     pub msg_fees: Grams,
@@ -366,6 +374,7 @@ pub struct TrBouncePhaseOk {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum TrBouncePhase {
     #[default]
     Negfunds,
@@ -456,6 +465,7 @@ tr_phase_credit$_
 = TrCreditPhase;
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrCreditPhase {
     pub due_fees_collected: Option<Grams>,
     pub credit: CurrencyCollection,
@@ -492,6 +502,7 @@ tock$1 = TickTock;
 There are two kinds of TickTock: in transaction and in messages.
 */
 #[derive(Default, PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransactionTickTock {
     #[default]
     Tick,
@@ -529,6 +540,7 @@ impl Deserializable for TransactionTickTock {
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TrActionPhase {
     pub success: bool,
     pub valid: bool,
@@ -616,6 +628,7 @@ split_merge_info$_
 */
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct SplitMergeInfo {
     pub cur_shard_pfx_len: u8,
     pub acc_split_depth:  u8,
@@ -667,6 +680,7 @@ trans_ord$0000
     destroyed:Boolean
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionDescrOrdinary {
     pub credit_first: bool,
     pub storage_ph: Option<TrStoragePhase>,
@@ -734,6 +748,7 @@ trans_tick_tock$001
     destroyed:Boolean
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionDescrTickTock {
     pub tt: TransactionTickTock,
     pub storage: TrStoragePhase,
@@ -800,6 +815,7 @@ trans_split_prepare$0100
     destroyed:Boolean
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionDescrSplitPrepare {
     pub split_info: SplitMergeInfo,
     pub compute_ph: TrComputePhase,
@@ -846,6 +862,7 @@ trans_split_install$0101
     installed:Boolean
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionDescrSplitInstall {
     pub split_info: SplitMergeInfo,
     pub prepare_transaction: Arc<Transaction>,
@@ -880,6 +897,7 @@ trans_merge_prepare$0110
     aborted:Boolean
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionDescrMergePrepare {
     pub split_info: SplitMergeInfo,
     pub storage_ph: TrStoragePhase,
@@ -915,6 +933,7 @@ trans_merge_install$0111
     destroyed:Boolean
 */
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionDescrMergeInstall {
     pub split_info: SplitMergeInfo,
     pub prepare_transaction: Arc<Transaction>,
@@ -964,12 +983,14 @@ impl Deserializable for TransactionDescrMergeInstall {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct CopyleftReward {
     pub reward: Grams,
     pub address: AccountId,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum TransactionDescr {
     Ordinary(TransactionDescrOrdinary),
     Storage(TransactionDescrStorage),
@@ -1066,6 +1087,43 @@ impl TransactionDescr {
         }
     }
 
+    ///
+    /// sum of fees collected in storage, credit, compute, action and bounce phases
+    ///
+    pub fn calc_phase_fees(&self) -> Result<Grams> {
+        let (storage_ph, credit_ph, bounce) = match self {
+            TransactionDescr::Ordinary(ref desc) => (desc.storage_ph.as_ref(), desc.credit_ph.as_ref(), desc.bounce.as_ref()),
+            TransactionDescr::Storage(ref desc) => (Some(desc), None, None),
+            TransactionDescr::TickTock(ref desc) => (Some(&desc.storage), None, None),
+            TransactionDescr::MergePrepare(ref desc) => (Some(&desc.storage_ph), None, None),
+            TransactionDescr::MergeInstall(ref desc) => (None, desc.credit_ph.as_ref(), None),
+            TransactionDescr::SplitPrepare(_) | TransactionDescr::SplitInstall(_) => (None, None, None),
+        };
+        let mut parts = Vec::new();
+        if let Some(phase) = storage_ph {
+            parts.push(phase.storage_fees_collected);
+        }
+        if let Some(phase) = credit_ph {
+            parts.push(phase.due_fees_collected.unwrap_or_default());
+        }
+        if let Some(TrComputePhase::Vm(phase)) = self.compute_phase_ref() {
+            parts.push(phase.gas_fees);
+        }
+        if let Some(phase) = self.action_phase_ref() {
+            parts.push(phase.total_action_fees());
+        }
+        if let Some(TrBouncePhase::Ok(phase)) = bounce {
+            parts.push(phase.msg_fees);
+        }
+        let mut fees = Grams::zero();
+        for part in &parts {
+            if !fees.add(part)? {
+                fail!(BlockError::InvalidData("phase fees overflow".to_string()))
+            }
+        }
+        Ok(fees)
+    }
+
     ///
     /// mark the transaction as aborted
     ///
@@ -1178,6 +1236,7 @@ update_hashes#72 {X:Type} old_hash:bits256 new_hash:bits256
 */
 const HASH_UPDATE_TAG: u8 = 0x72;
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct HashUpdate {
     pub old_hash: UInt256,
     pub new_hash: UInt256,
@@ -1261,6 +1320,7 @@ transaction$0111
 = Transaction;
 */
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transaction {
     account_addr: AccountId,
     lt: u64,
@@ -1414,22 +1474,21 @@ impl Transaction {
         &mut self.total_fees
     }
 
-    /// TODO remove if not used
     ///
-    /// Calculate total transaction fees
-    /// transaction fees is the amount fee for all out-messages
+    /// Calculate total transaction fees:
+    /// import fee of external inbound message and fees collected in all phases
     ///
-//    pub fn calc_total_fees(&mut self) -> &CurrencyCollection {
-//        self.total_fees = CurrencyCollection::default();
-        // TODO uncomment after merge with feature-block-builder
-        /*for msg in self.out_msgs.iter() {
-            if let Some(fee) = msg.get_fee()
-            {
-                total += fee;
+    pub fn calc_total_fees(&self) -> Result<CurrencyCollection> {
+        let mut fees = self.read_description()?.calc_phase_fees()?;
+        if let Some(msg) = self.read_in_msg()? {
+            if let Some(header) = msg.get_std()?.ext_in_header() {
+                if !fees.add(&header.import_fee)? {
+                    fail!(BlockError::InvalidData("total fees overflow".to_string()))
+                }
             }
-        }*/
-//        &self.total_fees
-//    }
+        }
+        Ok(CurrencyCollection::from_grams(fees))
+    }
 
     pub fn read_in_msg(&self) -> Result<Option<CommonMessage>> {
         match self.in_msg.empty() {
@@ -1708,6 +1767,394 @@ impl Deserializable for Transaction {
     }
 }
 
+// kind of transaction description with the fields which are not phases
+#[derive(Clone, Debug)]
+enum TransactionKind {
+    Ordinary,
+    Storage,
+    TickTock(TransactionTickTock),
+    SplitPrepare(SplitMergeInfo),
+    SplitInstall(SplitMergeInfo, Arc<Transaction>, bool),
+    MergePrepare(SplitMergeInfo),
+    MergeInstall(SplitMergeInfo, Arc<Transaction>),
+}
+
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TransactionKind::Ordinary => "ordinary",
+            TransactionKind::Storage => "storage",
+            TransactionKind::TickTock(_) => "tick-tock",
+            TransactionKind::SplitPrepare(_) => "split prepare",
+            TransactionKind::SplitInstall(..) => "split install",
+            TransactionKind::MergePrepare(_) => "merge prepare",
+            TransactionKind::MergeInstall(..) => "merge install",
+        };
+        f.write_str(name)
+    }
+}
+
+///
+/// Builder of transaction with description of any kind assembled from phases.
+/// Output messages are indexed in order of adding, total fees are summed from
+/// the phases if not set explicitly and state update is calculated from account cells.
+/// Combination of phases is checked against the kind of description and inbound message.
+///
+#[derive(Clone, Debug)]
+pub struct TransactionBuilder {
+    account_addr: AccountId,
+    opts: u8,
+    lt: u64,
+    prev_trans_hash: UInt256,
+    prev_trans_lt: u64,
+    now: u32,
+    orig_status: Option<AccountStatus>,
+    end_status: Option<AccountStatus>,
+    in_msg: Option<CommonMessage>,
+    out_msgs: Vec<CommonMessage>,
+    total_fees: Option<CurrencyCollection>,
+    accounts: Option<(Cell, Cell)>,
+    state_update: Option<HashUpdate>,
+    kind: TransactionKind,
+    credit_first: Option<bool>,
+    storage_ph: Option<TrStoragePhase>,
+    credit_ph: Option<TrCreditPhase>,
+    compute_ph: Option<TrComputePhase>,
+    action: Option<TrActionPhase>,
+    bounce: Option<TrBouncePhase>,
+    aborted: bool,
+    destroyed: bool,
+}
+
+impl TransactionBuilder {
+    pub fn new(account_addr: AccountId) -> Self {
+        Self::with_serde_opts(account_addr, SERDE_OPTS_EMPTY)
+    }
+    pub fn with_serde_opts(account_addr: AccountId, opts: u8) -> Self {
+        Self {
+            account_addr,
+            opts,
+            lt: 0,
+            prev_trans_hash: UInt256::default(),
+            prev_trans_lt: 0,
+            now: 0,
+            orig_status: None,
+            end_status: None,
+            in_msg: None,
+            out_msgs: Vec::new(),
+            total_fees: None,
+            accounts: None,
+            state_update: None,
+            kind: TransactionKind::Ordinary,
+            credit_first: None,
+            storage_ph: None,
+            credit_ph: None,
+            compute_ph: None,
+            action: None,
+            bounce: None,
+            aborted: false,
+            destroyed: false,
+        }
+    }
+    pub fn lt(mut self, lt: u64) -> Self {
+        self.lt = lt;
+        self
+    }
+    pub fn prev_trans(mut self, hash: UInt256, lt: u64) -> Self {
+        self.prev_trans_hash = hash;
+        self.prev_trans_lt = lt;
+        self
+    }
+    pub fn now(mut self, now: u32) -> Self {
+        self.now = now;
+        self
+    }
+    /// statuses are taken from account cells if not set
+    pub fn status(mut self, orig_status: AccountStatus, end_status: AccountStatus) -> Self {
+        self.orig_status = Some(orig_status);
+        self.end_status = Some(end_status);
+        self
+    }
+    pub fn in_msg(mut self, msg: CommonMessage) -> Self {
+        self.in_msg = Some(msg);
+        self
+    }
+    pub fn out_msg(mut self, msg: CommonMessage) -> Self {
+        self.out_msgs.push(msg);
+        self
+    }
+    pub fn total_fees(mut self, fees: CurrencyCollection) -> Self {
+        self.total_fees = Some(fees);
+        self
+    }
+    /// serialized accounts before and after transaction for state update and statuses
+    pub fn accounts(mut self, old_account: Cell, new_account: Cell) -> Self {
+        self.accounts = Some((old_account, new_account));
+        self
+    }
+    pub fn state_update(mut self, state_update: HashUpdate) -> Self {
+        self.state_update = Some(state_update);
+        self
+    }
+    /// credit phase is first by default if inbound message is not bounceable
+    pub fn ordinary(mut self, credit_first: bool) -> Self {
+        self.kind = TransactionKind::Ordinary;
+        self.credit_first = Some(credit_first);
+        self
+    }
+    pub fn storage(mut self) -> Self {
+        self.kind = TransactionKind::Storage;
+        self
+    }
+    pub fn tick_tock(mut self, tt: TransactionTickTock) -> Self {
+        self.kind = TransactionKind::TickTock(tt);
+        self
+    }
+    pub fn split_prepare(mut self, split_info: SplitMergeInfo) -> Self {
+        self.kind = TransactionKind::SplitPrepare(split_info);
+        self
+    }
+    pub fn split_install(mut self, split_info: SplitMergeInfo, prepare_transaction: Transaction, installed: bool) -> Self {
+        self.kind = TransactionKind::SplitInstall(split_info, Arc::new(prepare_transaction), installed);
+        self
+    }
+    pub fn merge_prepare(mut self, split_info: SplitMergeInfo) -> Self {
+        self.kind = TransactionKind::MergePrepare(split_info);
+        self
+    }
+    pub fn merge_install(mut self, split_info: SplitMergeInfo, prepare_transaction: Transaction) -> Self {
+        self.kind = TransactionKind::MergeInstall(split_info, Arc::new(prepare_transaction));
+        self
+    }
+    pub fn storage_phase(mut self, phase: TrStoragePhase) -> Self {
+        self.storage_ph = Some(phase);
+        self
+    }
+    pub fn credit_phase(mut self, phase: TrCreditPhase) -> Self {
+        self.credit_ph = Some(phase);
+        self
+    }
+    pub fn compute_phase(mut self, phase: TrComputePhase) -> Self {
+        self.compute_ph = Some(phase);
+        self
+    }
+    pub fn action_phase(mut self, phase: TrActionPhase) -> Self {
+        self.action = Some(phase);
+        self
+    }
+    pub fn bounce_phase(mut self, phase: TrBouncePhase) -> Self {
+        self.bounce = Some(phase);
+        self
+    }
+    pub fn aborted(mut self, aborted: bool) -> Self {
+        self.aborted = aborted;
+        self
+    }
+    pub fn destroyed(mut self, destroyed: bool) -> Self {
+        self.destroyed = destroyed;
+        self
+    }
+
+    pub fn build(self) -> Result<Transaction> {
+        let (state_update, statuses) = match (&self.state_update, &self.accounts) {
+            (Some(_), Some(_)) => fail!(BlockError::InvalidArg(
+                "both state update and accounts are set".to_string()
+            )),
+            (Some(state_update), None) => (state_update.clone(), None),
+            (None, Some((old, new))) => {
+                let state_update = HashUpdate::with_hashes(old.repr_hash(), new.repr_hash());
+                let old = Account::construct_from_cell(old.clone())?.status();
+                let new = Account::construct_from_cell(new.clone())?.status();
+                (state_update, Some((old, new)))
+            }
+            (None, None) => fail!(BlockError::InvalidArg(
+                "state update or accounts should be set".to_string()
+            ))
+        };
+        let (orig_status, end_status) = match (self.orig_status.clone(), self.end_status.clone(), statuses) {
+            (Some(orig), Some(end), _) => (orig, end),
+            (_, _, Some(statuses)) => statuses,
+            _ => fail!(BlockError::InvalidArg("statuses of account should be set".to_string()))
+        };
+        if self.destroyed && end_status != AccountStatus::AccStateNonexist {
+            fail!(BlockError::InvalidData(format!(
+                "destroyed account has end status {:?}", end_status
+            )))
+        }
+        let descr = self.build_description()?;
+
+        let mut tr = Transaction::with_address_and_status(self.account_addr, orig_status);
+        tr.in_msg = ChildCell::with_serde_opts(self.opts);
+        tr.out_msgs = OutMessages::with_serde_opts(self.opts);
+        tr.state_update = ChildCell::with_serde_opts(self.opts);
+        tr.description = ChildCell::with_serde_opts(self.opts);
+        tr.lt = self.lt;
+        tr.prev_trans_hash = self.prev_trans_hash;
+        tr.prev_trans_lt = self.prev_trans_lt;
+        tr.now = self.now;
+        tr.end_status = end_status;
+        tr.write_in_msg(self.in_msg.as_ref())?;
+        for msg in &self.out_msgs {
+            // sizes of messages are already in phases so they are not appended to description
+            let msg_cell = msg.serialize_with_opts(self.opts)?;
+            tr.out_msgs.setref(&U15(tr.outmsg_cnt), &msg_cell)?;
+            tr.outmsg_cnt += 1;
+        }
+        tr.write_state_update(&state_update)?;
+        tr.write_description(&descr)?;
+        tr.total_fees = match self.total_fees {
+            Some(total_fees) => total_fees,
+            None => tr.calc_total_fees()?
+        };
+        Ok(tr)
+    }
+
+    fn build_description(&self) -> Result<TransactionDescr> {
+        let kind = &self.kind;
+        let unexpected = |phase: &str| -> Result<TransactionDescr> {
+            fail!(BlockError::InvalidData(format!("{} transaction can't have {}", kind, phase)))
+        };
+        let required = |phase: &str| -> Result<TransactionDescr> {
+            fail!(BlockError::InvalidData(format!("{} transaction should have {}", kind, phase)))
+        };
+        let in_msg = match &self.in_msg {
+            Some(msg) => Some(msg.get_std()?),
+            None => None
+        };
+        let is_ordinary = matches!(kind, TransactionKind::Ordinary);
+        if in_msg.is_some() && !is_ordinary {
+            return unexpected("inbound message")
+        }
+        if self.bounce.is_some() && !is_ordinary {
+            return unexpected("bounce phase")
+        }
+        let has_compute = !matches!(
+            kind, TransactionKind::Storage | TransactionKind::SplitInstall(..) | TransactionKind::MergePrepare(_)
+        );
+        if !has_compute {
+            if self.compute_ph.is_some() {
+                return unexpected("compute phase")
+            }
+            if self.action.is_some() {
+                return unexpected("action phase")
+            }
+            if self.destroyed {
+                return unexpected("destroyed flag")
+            }
+        }
+        if self.credit_ph.is_some() && !matches!(kind, TransactionKind::Ordinary | TransactionKind::MergeInstall(..)) {
+            return unexpected("credit phase")
+        }
+        if self.storage_ph.is_some() && matches!(
+            kind, TransactionKind::SplitPrepare(_) | TransactionKind::SplitInstall(..) | TransactionKind::MergeInstall(..)
+        ) {
+            return unexpected("storage phase")
+        }
+        if self.aborted && matches!(kind, TransactionKind::Storage | TransactionKind::SplitInstall(..)) {
+            return unexpected("aborted flag")
+        }
+        if self.action.is_some() {
+            match &self.compute_ph {
+                Some(TrComputePhase::Vm(vm)) if vm.success => (),
+                _ => fail!(BlockError::InvalidData(
+                    "action phase requires successful compute phase".to_string()
+                ))
+            }
+        }
+        let bounce_ok = matches!(self.bounce, Some(TrBouncePhase::Ok(_)));
+        if !self.out_msgs.is_empty() && self.action.is_none() && !bounce_ok {
+            fail!(BlockError::InvalidData(
+                "output messages require action phase or bounce phase".to_string()
+            ))
+        }
+        let compute_ph = match (has_compute, &self.compute_ph) {
+            (true, Some(compute_ph)) => compute_ph.clone(),
+            (true, None) => return required("compute phase"),
+            (false, _) => TrComputePhase::default()
+        };
+        let storage = || match &self.storage_ph {
+            Some(storage_ph) => Ok(storage_ph.clone()),
+            None => required("storage phase").map(|_| TrStoragePhase::default())
+        };
+        let descr = match kind {
+            TransactionKind::Ordinary => {
+                let msg = match in_msg {
+                    Some(msg) => msg,
+                    None => return required("inbound message")
+                };
+                let bounce_flag = msg.int_header().map(|header| header.bounce).unwrap_or_default();
+                if msg.is_internal() != self.credit_ph.is_some() {
+                    fail!(BlockError::InvalidData(
+                        "credit phase should be present only for internal inbound message".to_string()
+                    ))
+                }
+                if self.bounce.is_some() {
+                    if !bounce_flag {
+                        fail!(BlockError::InvalidData(
+                            "bounce phase requires inbound message with bounce flag".to_string()
+                        ))
+                    }
+                    if !self.aborted {
+                        fail!(BlockError::InvalidData(
+                            "bounce phase is only for aborted transaction".to_string()
+                        ))
+                    }
+                }
+                TransactionDescr::Ordinary(TransactionDescrOrdinary {
+                    credit_first: self.credit_first.unwrap_or(!bounce_flag),
+                    storage_ph: self.storage_ph.clone(),
+                    credit_ph: self.credit_ph.clone(),
+                    compute_ph,
+                    action: self.action.clone(),
+                    aborted: self.aborted,
+                    bounce: self.bounce.clone(),
+                    destroyed: self.destroyed,
+                })
+            }
+            TransactionKind::Storage => TransactionDescr::Storage(storage()?),
+            TransactionKind::TickTock(tt) => TransactionDescr::TickTock(TransactionDescrTickTock {
+                tt: tt.clone(),
+                storage: storage()?,
+                compute_ph,
+                action: self.action.clone(),
+                aborted: self.aborted,
+                destroyed: self.destroyed,
+            }),
+            TransactionKind::SplitPrepare(split_info) => TransactionDescr::SplitPrepare(TransactionDescrSplitPrepare {
+                split_info: split_info.clone(),
+                compute_ph,
+                action: self.action.clone(),
+                aborted: self.aborted,
+                destroyed: self.destroyed,
+            }),
+            TransactionKind::SplitInstall(split_info, prepare_transaction, installed) => {
+                TransactionDescr::SplitInstall(TransactionDescrSplitInstall {
+                    split_info: split_info.clone(),
+                    prepare_transaction: prepare_transaction.clone(),
+                    installed: *installed,
+                })
+            }
+            TransactionKind::MergePrepare(split_info) => TransactionDescr::MergePrepare(TransactionDescrMergePrepare {
+                split_info: split_info.clone(),
+                storage_ph: storage()?,
+                aborted: self.aborted,
+            }),
+            TransactionKind::MergeInstall(split_info, prepare_transaction) => {
+                TransactionDescr::MergeInstall(TransactionDescrMergeInstall {
+                    split_info: split_info.clone(),
+                    prepare_transaction: prepare_transaction.clone(),
+                    credit_ph: self.credit_ph.clone(),
+                    compute_ph,
+                    action: self.action.clone(),
+                    aborted: self.aborted,
+                    destroyed: self.destroyed,
+                })
+            }
+        };
+        Ok(descr)
+    }
+}
+
 define_HashmapAugE!(Transactions, 64, u64, InRefValue<Transaction>, CurrencyCollection);
 
 define_HashmapE!(MeshTransactions, 32, Transactions);
@@ -1743,6 +2190,7 @@ impl Augmentation<CurrencyCollection> for InRefValue<Transaction> {
 ///      state_update:^(HASH_UPDATE Account)
 /// = AccountBlock;
 #[derive(Clone, Debug, Default, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct AccountBlock {
     account_addr: AccountId,
     transactions: Transactions,      // HashmapAug 64 ^Transaction CurrencyCollection
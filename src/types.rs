@@ -1511,6 +1511,7 @@ impl<X: Serializable> Serializable for Arc<X> {
 }
 
 #[derive(PartialEq, Copy, Clone, Debug, Eq, Default, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnixTime32(u32);
 
 impl UnixTime32 {
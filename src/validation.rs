@@ -0,0 +1,438 @@
+/*
+* Copyright (C) 2019-2024 EverX. All Rights Reserved.
+*
+* Licensed under the SOFTWARE EVALUATION License (the "License"); you may not use
+* this file except in compliance with the License.
+*
+* Unless required by applicable law or agreed to in writing, software
+* distributed under the License is distributed on an "AS IS" BASIS,
+* WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+* See the License for the specific EVERX DEV software governing permissions and
+* limitations under the License.
+*/
+
+use crate::{
+    accounts::{Account, AccountStatus},
+    blocks::Block,
+    config_params::ConfigParams,
+    dictionary::HashmapAugType,
+    fees::FeesCalculator,
+    shard::ShardStateUnsplit,
+    transactions::{AccStatusChange, TrComputePhase, Transaction, TransactionDescr},
+    types::{AddSub, CurrencyCollection, Grams},
+    error::BlockError, fail, Result, Serializable,
+};
+
+#[cfg(test)]
+#[path = "tests/test_validation.rs"]
+mod tests;
+
+///
+/// Checks transaction against states of account before and after it and config params.
+/// Checks are semantic only: structure of transaction is checked by its deserialization.
+/// Returns the first found inconsistency as `BlockError::InvalidData`.
+///
+pub fn validate_transaction(
+    tr: &Transaction,
+    old_account: &Account,
+    new_account: &Account,
+    config: &ConfigParams,
+) -> Result<()> {
+    let descr = tr.read_description()?;
+    check_account_ids(tr, old_account, new_account)?;
+    check_state_update(tr, old_account, new_account)?;
+    check_statuses(tr, &descr, old_account, new_account)?;
+    check_logical_times(tr, old_account, new_account)?;
+    check_total_fees(tr)?;
+    check_balance(tr, &descr, old_account, new_account)?;
+    check_config(tr, &descr, old_account, new_account, config)?;
+    Ok(())
+}
+
+fn invalid(message: String) -> Result<()> {
+    fail!(BlockError::InvalidData(message))
+}
+
+fn check_account_ids(tr: &Transaction, old_account: &Account, new_account: &Account) -> Result<()> {
+    for (name, account) in [("old", old_account), ("new", new_account)] {
+        if let Some(account_id) = account.get_id() {
+            if &account_id != tr.account_id() {
+                return invalid(format!(
+                    "{} account {:x} is not account {:x} of transaction", name, account_id, tr.account_id()
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_state_update(tr: &Transaction, old_account: &Account, new_account: &Account) -> Result<()> {
+    let state_update = tr.read_state_update()?;
+    let old_hash = old_account.serialize()?.repr_hash();
+    if state_update.old_hash != old_hash {
+        return invalid(format!(
+            "old hash of state update {:x} is not hash of old account {:x}", state_update.old_hash, old_hash
+        ))
+    }
+    let new_hash = new_account.serialize()?.repr_hash();
+    if state_update.new_hash != new_hash {
+        return invalid(format!(
+            "new hash of state update {:x} is not hash of new account {:x}", state_update.new_hash, new_hash
+        ))
+    }
+    Ok(())
+}
+
+fn check_statuses(tr: &Transaction, descr: &TransactionDescr, old_account: &Account, new_account: &Account) -> Result<()> {
+    if tr.orig_status != old_account.status() {
+        return invalid(format!(
+            "orig status {:?} is not status of old account {:?}", tr.orig_status, old_account.status()
+        ))
+    }
+    if tr.end_status != new_account.status() {
+        return invalid(format!(
+            "end status {:?} is not status of new account {:?}", tr.end_status, new_account.status()
+        ))
+    }
+    let (storage_change, destroyed) = match descr {
+        TransactionDescr::Ordinary(desc) => (desc.storage_ph.as_ref().map(|ph| &ph.status_change), desc.destroyed),
+        TransactionDescr::Storage(desc) => (Some(&desc.status_change), false),
+        TransactionDescr::TickTock(desc) => (Some(&desc.storage.status_change), desc.destroyed),
+        TransactionDescr::SplitPrepare(desc) => (None, desc.destroyed),
+        TransactionDescr::SplitInstall(_) => (None, false),
+        TransactionDescr::MergePrepare(desc) => (Some(&desc.storage_ph.status_change), false),
+        TransactionDescr::MergeInstall(desc) => (None, desc.destroyed),
+    };
+    let action_change = descr.action_phase_ref().map(|ph| &ph.status_change);
+    let deleted = [storage_change, action_change].contains(&Some(&AccStatusChange::Deleted));
+    if (deleted || destroyed) && tr.end_status != AccountStatus::AccStateNonexist {
+        return invalid(format!("deleted account has end status {:?}", tr.end_status))
+    }
+    let frozen = storage_change == Some(&AccStatusChange::Frozen);
+    if frozen && !matches!(tr.orig_status, AccountStatus::AccStateActive | AccountStatus::AccStateUninit) {
+        return invalid(format!("account with orig status {:?} can't be frozen", tr.orig_status))
+    }
+    if tr.end_status == AccountStatus::AccStateFrozen && tr.orig_status != AccountStatus::AccStateFrozen && !frozen {
+        return invalid("account is frozen without status change in storage phase".to_string())
+    }
+    // only code of account can be run to activate it
+    let has_vm = matches!(descr.compute_phase_ref(), Some(TrComputePhase::Vm(_)));
+    if tr.end_status == AccountStatus::AccStateActive && tr.orig_status != AccountStatus::AccStateActive && !has_vm {
+        return invalid(format!("account with orig status {:?} is activated without compute phase", tr.orig_status))
+    }
+    Ok(())
+}
+
+fn check_logical_times(tr: &Transaction, old_account: &Account, new_account: &Account) -> Result<()> {
+    let lt = tr.logical_time();
+    if tr.prev_trans_lt() >= lt {
+        return invalid(format!("previous transaction lt {} is not less than lt {}", tr.prev_trans_lt(), lt))
+    }
+    if let Some(last_lt) = old_account.last_tr_time() {
+        if last_lt > lt {
+            return invalid(format!("last transaction lt {} of old account is greater than lt {}", last_lt, lt))
+        }
+    }
+    let end_lt = lt + tr.msg_count() as u64 + 1;
+    if let Some(last_lt) = new_account.last_tr_time() {
+        if last_lt != end_lt {
+            return invalid(format!("last transaction lt {} of new account should be {}", last_lt, end_lt))
+        }
+    }
+    if let Some(msg) = tr.read_in_msg()? {
+        if let Some(msg_lt) = msg.get_std()?.int_header().map(|header| header.created_lt) {
+            if msg_lt >= lt {
+                return invalid(format!("inbound message created lt {} is not less than lt {}", msg_lt, lt))
+            }
+        }
+    }
+    // output messages are created one by one right after the transaction
+    let mut prev_lt = lt;
+    for index in 0..tr.msg_count() {
+        let msg = match tr.get_out_msg(index)? {
+            Some(msg) => msg,
+            None => return invalid(format!("output message {} is absent", index))
+        };
+        let msg_lt = match msg.get_std()?.lt() {
+            Some(msg_lt) => msg_lt,
+            None => return invalid(format!("output message {} has no created lt", index))
+        };
+        if msg_lt <= prev_lt {
+            return invalid(format!(
+                "output message {} created lt {} is not greater than previous lt {}", index, msg_lt, prev_lt
+            ))
+        }
+        if msg_lt >= end_lt {
+            return invalid(format!(
+                "output message {} created lt {} is out of range ({}, {})", index, msg_lt, lt, end_lt
+            ))
+        }
+        prev_lt = msg_lt;
+    }
+    let mut count = 0;
+    tr.iterate_out_msgs(|_| {
+        count += 1;
+        Ok(true)
+    })?;
+    if count != tr.msg_count() as usize {
+        return invalid(format!("transaction has {} output messages but count is {}", count, tr.msg_count()))
+    }
+    Ok(())
+}
+
+fn check_total_fees(tr: &Transaction) -> Result<()> {
+    let fees = tr.calc_total_fees()?;
+    if tr.total_fees() != &fees {
+        return invalid(format!("total fees {} are not sum of phase fees {}", tr.total_fees(), fees))
+    }
+    Ok(())
+}
+
+fn add_value(total: &mut CurrencyCollection, value: &CurrencyCollection) -> Result<()> {
+    if !total.add(value)? {
+        fail!(BlockError::InvalidData("value overflow".to_string()))
+    }
+    Ok(())
+}
+
+// old balance + inbound value = new balance + fees + value and fees of output messages
+fn check_balance(tr: &Transaction, descr: &TransactionDescr, old_account: &Account, new_account: &Account) -> Result<()> {
+    // balance of the account installed by split is taken from the prepare transaction
+    if let TransactionDescr::SplitInstall(_) = descr {
+        return Ok(())
+    }
+    let mut income = old_account.balance_checked();
+    if let Some(msg) = tr.read_in_msg()? {
+        if let Some(header) = msg.get_std()?.int_header() {
+            add_value(&mut income, &header.value)?;
+        }
+    }
+    let mut outcome = new_account.balance_checked();
+    add_value(&mut outcome, tr.total_fees())?;
+    let mut out_msgs = Ok(());
+    tr.iterate_out_msgs(|msg| {
+        if let Some(header) = msg.get_std()?.int_header() {
+            out_msgs = add_value(&mut outcome, &header.value)
+                .and_then(|_| add_value(&mut outcome, &CurrencyCollection::from_grams(header.fwd_fee)))
+                .and_then(|_| add_value(&mut outcome, &CurrencyCollection::from_grams(header.ihr_fee)));
+        }
+        Ok(out_msgs.is_ok())
+    })?;
+    out_msgs?;
+    if income != outcome {
+        return invalid(format!(
+            "balance is not conserved: old balance and inbound value {} are not equal to \
+            new balance, fees and outbound value {}", income, outcome
+        ))
+    }
+    Ok(())
+}
+
+fn check_config(
+    tr: &Transaction,
+    descr: &TransactionDescr,
+    old_account: &Account,
+    new_account: &Account,
+    config: &ConfigParams,
+) -> Result<()> {
+    if let TransactionDescr::TickTock(desc) = descr {
+        let allowed = old_account.get_tick_tock()
+            .map(|tt| if desc.tt.is_tick() { tt.tick } else { tt.tock })
+            .unwrap_or_default();
+        if !allowed {
+            return invalid(format!("account has no {:?} flag for tick-tock transaction", desc.tt))
+        }
+    }
+    let vm = match descr.compute_phase_ref() {
+        Some(TrComputePhase::Vm(vm)) => vm,
+        _ => return Ok(())
+    };
+    let address = match old_account.get_addr().or_else(|| new_account.get_addr()) {
+        Some(address) => address,
+        None => return invalid(format!("compute phase of transaction {} without account", tr.logical_time()))
+    };
+    let calc = FeesCalculator::with_config(config)?;
+    let is_masterchain = address.is_masterchain();
+    let is_special = calc.is_special_account(address)?;
+    let prices = calc.gas_prices(is_masterchain);
+    let gas_used = vm.gas_used.as_u64();
+    let gas_limit = match is_special {
+        true => prices.gas_limit.max(prices.special_gas_limit),
+        false => prices.gas_limit
+    };
+    if gas_used > gas_limit {
+        return invalid(format!("gas used {} is greater than gas limit {}", gas_used, gas_limit))
+    }
+    // special accounts don't pay for gas
+    let gas_fees = calc.calc_gas_fee(gas_used, is_masterchain);
+    if vm.gas_fees.as_u128() != gas_fees && !(is_special && vm.gas_fees.is_zero()) {
+        return invalid(format!("gas fees {} are not price of gas used {}", vm.gas_fees, gas_fees))
+    }
+    Ok(())
+}
+
+///
+/// Mismatch of value flow of block with its contents and states
+///
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum ValueFlowError {
+    #[error("from_prev_blk {found} is not total balance of previous state {expected}")]
+    FromPrevBlk { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("to_next_blk {found} is not total balance of new state {expected}")]
+    ToNextBlk { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("total balance of previous state {found} is not sum of balances of accounts {expected}")]
+    PrevStateBalance { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("total balance of new state {found} is not sum of balances of accounts {expected}")]
+    NewStateBalance { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("imported {found} is not value imported by InMsgDescr {expected}")]
+    Imported { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("exported {found} is not value exported by OutMsgDescr {expected}")]
+    Exported { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("fees_imported {found} is not sum of shard fees {expected}")]
+    FeesImported { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("fees_collected with copyleft rewards {found} is not sum of transaction, import, imported \
+        and created fees {expected}")]
+    FeesCollected { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("recovered {found} is greater than validator fees {expected}")]
+    Recovered { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("total validator fees of new state {found} are not {expected}")]
+    ValidatorFees { expected: CurrencyCollection, found: CurrencyCollection },
+    #[error("{field} of shardchain block is {found} instead of zero")]
+    NonZeroInShardchain { field: &'static str, found: CurrencyCollection },
+    #[error("value flow is not balanced: incoming value {income} is not outgoing value {outcome}")]
+    Unbalanced { income: CurrencyCollection, outcome: CurrencyCollection },
+}
+
+fn sum_values(values: &[&CurrencyCollection]) -> Result<CurrencyCollection> {
+    let mut total = CurrencyCollection::default();
+    for value in values {
+        add_value(&mut total, value)?;
+    }
+    Ok(total)
+}
+
+///
+/// Checks value flow of block against augmentations of InMsgDescr, OutMsgDescr and
+/// ShardAccountBlocks and against total balances of states before and after the block.
+/// Returns all found mismatches, the empty vector means value flow is consistent.
+/// Copyleft rewards are taken out of collected fees so they are counted with `fees_collected`.
+///
+pub fn check_value_flow(
+    block: &Block,
+    prev_state: &ShardStateUnsplit,
+    new_state: &ShardStateUnsplit,
+) -> Result<Vec<ValueFlowError>> {
+    let value_flow = block.read_value_flow()?;
+    let extra = block.read_extra()?;
+    let mut errors = Vec::new();
+
+    // balances of states
+    if &value_flow.from_prev_blk != prev_state.total_balance() {
+        errors.push(ValueFlowError::FromPrevBlk {
+            expected: prev_state.total_balance().clone(),
+            found: value_flow.from_prev_blk.clone(),
+        });
+    }
+    if &value_flow.to_next_blk != new_state.total_balance() {
+        errors.push(ValueFlowError::ToNextBlk {
+            expected: new_state.total_balance().clone(),
+            found: value_flow.to_next_blk.clone(),
+        });
+    }
+    let accounts = prev_state.read_accounts()?;
+    if accounts.full_balance() != prev_state.total_balance() {
+        errors.push(ValueFlowError::PrevStateBalance {
+            expected: accounts.full_balance().clone(),
+            found: prev_state.total_balance().clone(),
+        });
+    }
+    let accounts = new_state.read_accounts()?;
+    if accounts.full_balance() != new_state.total_balance() {
+        errors.push(ValueFlowError::NewStateBalance {
+            expected: accounts.full_balance().clone(),
+            found: new_state.total_balance().clone(),
+        });
+    }
+
+    // messages
+    let import_fees = extra.read_in_msg_descr()?.full_import_fees().clone();
+    if value_flow.imported != import_fees.value_imported {
+        errors.push(ValueFlowError::Imported {
+            expected: import_fees.value_imported.clone(),
+            found: value_flow.imported.clone(),
+        });
+    }
+    let out_msg_descr = extra.read_out_msg_descr()?;
+    if &value_flow.exported != out_msg_descr.full_exported() {
+        errors.push(ValueFlowError::Exported {
+            expected: out_msg_descr.full_exported().clone(),
+            found: value_flow.exported.clone(),
+        });
+    }
+
+    // fees
+    match extra.read_custom()? {
+        Some(mc_extra) => {
+            let shard_fees = &mc_extra.fees().root_extra().fees;
+            if &value_flow.fees_imported != shard_fees {
+                errors.push(ValueFlowError::FeesImported {
+                    expected: shard_fees.clone(),
+                    found: value_flow.fees_imported.clone(),
+                });
+            }
+        }
+        None => {
+            for (field, value) in [("minted", &value_flow.minted), ("recovered", &value_flow.recovered)] {
+                if !value.is_zero()? {
+                    errors.push(ValueFlowError::NonZeroInShardchain { field, found: value.clone() });
+                }
+            }
+        }
+    }
+    let mut copyleft_rewards = Grams::zero();
+    if !value_flow.copyleft_rewards.iterate(|reward| copyleft_rewards.add(&reward))? {
+        fail!(BlockError::InvalidData("copyleft rewards overflow".to_string()))
+    }
+    let fees_collected = sum_values(&[
+        &value_flow.fees_collected, &CurrencyCollection::from_grams(copyleft_rewards)
+    ])?;
+    let expected = sum_values(&[
+        extra.read_account_blocks()?.full_transaction_fees(),
+        &CurrencyCollection::from_grams(import_fees.fees_collected),
+        &value_flow.fees_imported,
+        &value_flow.created,
+    ])?;
+    if fees_collected != expected {
+        errors.push(ValueFlowError::FeesCollected { expected, found: fees_collected.clone() });
+    }
+    // collected fees are stored in state until they are recovered in masterchain
+    let validator_fees = sum_values(&[prev_state.total_validator_fees(), &value_flow.fees_collected])?;
+    let mut expected = validator_fees.clone();
+    if !expected.sub(&value_flow.recovered)? {
+        errors.push(ValueFlowError::Recovered {
+            expected: validator_fees,
+            found: value_flow.recovered.clone(),
+        });
+    } else if &expected != new_state.total_validator_fees() {
+        errors.push(ValueFlowError::ValidatorFees {
+            expected,
+            found: new_state.total_validator_fees().clone(),
+        });
+    }
+
+    // from_prev_blk + imported + fees_imported + created + minted + recovered =
+    // to_next_blk + exported + fees_collected
+    let income = sum_values(&[
+        &value_flow.from_prev_blk,
+        &value_flow.imported,
+        &value_flow.fees_imported,
+        &value_flow.created,
+        &value_flow.minted,
+        &value_flow.recovered,
+    ])?;
+    let outcome = sum_values(&[&value_flow.to_next_blk, &value_flow.exported, &fees_collected])?;
+    if income != outcome {
+        errors.push(ValueFlowError::Unbalanced { income, outcome });
+    }
+    Ok(errors)
+}
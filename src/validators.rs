@@ -45,6 +45,7 @@ validator_info$_
 
 /// Validator info struct
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValidatorInfo {
     pub validator_list_hash_short: u32,
     pub catchain_seqno: u32,
@@ -737,6 +738,7 @@ const VALIDATORS_SHARD_STAT_TAG: u8 = 0x1; // 4 bits
 const VALIDATORS_STAT_EXPECTED_MAX: usize = 256;
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct ValidatorsStat { 
     // Single u16 value for each validator.
     // VALIDATORS_STAT_EXPECTED_MAX values are stored inplace, 
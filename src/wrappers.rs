@@ -14,7 +14,7 @@
 use crate::{error, fail, Result};
 use aes_ctr::cipher::stream::{NewStreamCipher, SyncStreamCipher};
 use core::ops::Range;
-use crc::{Crc, CRC_32_ISCSI};
+use crc::{Crc, CRC_16_XMODEM, CRC_32_ISCSI};
 use ed25519_dalek::{SecretKey, Verifier, VerifyingKey, SigningKey, Signer};
 use sha2::Digest;
 
@@ -71,6 +71,10 @@ pub fn base64_encode_url_safe(input: impl AsRef<[u8]>) -> String {
     base64::encode_config(input, base64::URL_SAFE)
 }
 
+pub fn base64_decode_url_safe(input: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    Ok(base64::decode_config(input, base64::URL_SAFE)?)
+}
+
 // Ed25519 --------------------------------------------------------------
 
 pub struct Ed25519ExpandedPrivateKey {
@@ -238,6 +242,11 @@ pub fn sha512_digest(data: impl AsRef<[u8]>) -> [u8; 64] {
 }
 
 const CASTAGNOLI: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+const XMODEM: Crc<u16> = Crc::<u16>::new(&CRC_16_XMODEM);
+
+pub fn crc16(data: impl AsRef<[u8]>) -> u16 {
+    XMODEM.checksum(data.as_ref())
+}
 
 pub struct Crc32<'a> {
     hasher: crc::Digest<'a, u32>